@@ -0,0 +1,60 @@
+use std::fmt::Write;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use cueparse::Cue;
+
+/// A full 99-track album rip, the largest cue a CD can need
+fn album() -> String {
+    let mut cue = String::from(
+        "CATALOG 0123456789012\nREM GENRE Rock\nREM DATE 1999\nPERFORMER \"Some Artist\"\nTITLE \"Some Album\"\nFILE \"album.wav\" WAVE\n",
+    );
+
+    for track in 1..=99 {
+        write!(
+            cue,
+            "  TRACK {track:02} AUDIO\n    TITLE \"Track number {track}\"\n    PERFORMER \"Some Artist\"\n    ISRC USABC22{track:05}\n    INDEX 00 {:02}:00:00\n    INDEX 01 {:02}:02:00\n",
+            track - 1,
+            track - 1,
+        )
+        .unwrap();
+    }
+
+    cue
+}
+
+/// One file per track, using the less common commands that come after the usual ones in the grammar
+fn tracks_per_file() -> String {
+    let mut cue = String::from("REM COMMENT \"Exact Audio Copy\"\nTITLE \"Some Album\"\n");
+
+    for track in 1..=99 {
+        write!(
+            cue,
+            "FILE \"{track:02}.flac\" WAVE\n  TRACK {track:02} AUDIO\n    FLAGS DCP PRE\n    SONGWRITER \"Someone\"\n    REM REPLAYGAIN_TRACK_GAIN -7.50 dB\n    PREGAP 00:02:00\n    INDEX 01 00:00:00\n    POSTGAP 00:01:00\n",
+        )
+        .unwrap();
+    }
+
+    cue
+}
+
+fn parse(c: &mut Criterion) {
+    let album = album();
+    let tracks_per_file = tracks_per_file();
+    let example = include_str!("../test_files/example.cue");
+
+    c.bench_function("parse 99 tracks", |b| {
+        b.iter(|| Cue::from_str(black_box(&album)).unwrap())
+    });
+
+    c.bench_function("parse 99 files", |b| {
+        b.iter(|| Cue::from_str(black_box(&tracks_per_file)).unwrap())
+    });
+
+    // its out of range time leaves it to the grammar rather than the fast path
+    c.bench_function("parse example", |b| {
+        b.iter(|| Cue::from_str(black_box(example)).unwrap())
+    });
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
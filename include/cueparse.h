@@ -0,0 +1,123 @@
+#ifndef CUEPARSE_H
+#define CUEPARSE_H
+
+/* Generated by cbindgen from src/capi.rs, don't edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct CueparseCue CueparseCue;
+
+/**
+ * Parses `len` bytes at `data` as a cue, detecting the text encoding. Returns NULL on failure,
+ * and sets `*error` to a message if `error` isn't NULL.
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes, and `error` must be NULL or point to writable
+ * memory for a pointer.
+ */
+struct CueparseCue *cueparse_parse(const uint8_t *data, size_t len, char **error);
+
+/**
+ * Frees a cue returned by [`cueparse_parse`]. Does nothing for NULL.
+ *
+ * # Safety
+ *
+ * `cue` must be NULL or returned by [`cueparse_parse`], and not already freed.
+ */
+void cueparse_free(struct CueparseCue *cue);
+
+/**
+ * Frees a string returned by this API. Does nothing for NULL.
+ *
+ * # Safety
+ *
+ * `string` must be NULL or returned by this API, and not already freed.
+ */
+void cueparse_string_free(char *string);
+
+/**
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+char *cueparse_title(const struct CueparseCue *cue);
+
+/**
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+char *cueparse_performer(const struct CueparseCue *cue);
+
+/**
+ * Length of the disc in frames, if the lead-out is known
+ *
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+int64_t cueparse_duration(const struct CueparseCue *cue);
+
+/**
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+size_t cueparse_track_count(const struct CueparseCue *cue);
+
+/**
+ * The number of the track at `index`, counting from 0 in the order of the cue
+ *
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+int32_t cueparse_track_number(const struct CueparseCue *cue, size_t index);
+
+/**
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+char *cueparse_track_title(const struct CueparseCue *cue, size_t index);
+
+/**
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+char *cueparse_track_performer(const struct CueparseCue *cue, size_t index);
+
+/**
+ * The path of the `FILE` holding the track, as written in the cue
+ *
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+char *cueparse_track_file(const struct CueparseCue *cue, size_t index);
+
+/**
+ * Start of the track's `INDEX 01` within its file, in frames
+ *
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+int64_t cueparse_track_start(const struct CueparseCue *cue, size_t index);
+
+/**
+ * Length of the track in frames, see [`Cue::track_length`]
+ *
+ * # Safety
+ *
+ * `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+ */
+int64_t cueparse_track_length(const struct CueparseCue *cue, size_t index);
+
+#endif  /* CUEPARSE_H */
@@ -0,0 +1,245 @@
+//! AccurateRip v1/v2 checksums for verifying rips against the AccurateRip database
+
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind, Read},
+    path::Path,
+};
+
+use crate::{Cue, TrackMode};
+
+/// Number of stereo samples in one CD sector
+const SAMPLES_PER_SECTOR: u64 = 588;
+
+/// AccurateRip ignores the first and last 5 sectors of the disc, which drives can't read reliably
+const SKIPPED_SAMPLES: u64 = SAMPLES_PER_SECTOR * 5;
+
+const BUFFER_SIZE: usize = 2352 * 64;
+
+/// AccurateRip checksums for a single audio track
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackChecksum {
+    pub track_index: u8,
+    pub v1: u32,
+    pub v2: u32,
+}
+
+/// Computes AccurateRip checksums for every audio track in `cue`.
+///
+/// `open` is called once for each `FILE` referenced by the cue, in order, and must return a reader
+/// over its raw audio as 44.1kHz 16-bit little-endian stereo PCM, i.e. with any container header
+/// stripped and compressed audio decoded. Data tracks are skipped, so on enhanced CDs the last
+/// audio track is treated as the last track of the disc.
+pub fn checksums<F, R>(cue: &Cue, mut open: F) -> io::Result<Vec<TrackChecksum>>
+where
+    F: FnMut(&Path) -> io::Result<R>,
+    R: Read,
+{
+    let mut files: Vec<(&Path, Vec<(usize, u64)>)> = Vec::new();
+
+    for (i, track) in cue.tracks.iter().enumerate() {
+        let path = track
+            .file
+            .as_deref()
+            .ok_or_else(|| invalid_input(format!("track {} has no FILE", track.track_index)))?;
+        let start = track
+            .start()
+            .ok_or_else(|| invalid_input(format!("track {} has no INDEX 01", track.track_index)))?;
+        let start = u64::from(start) * SAMPLES_PER_SECTOR;
+
+        match files.last_mut() {
+            Some((last, starts)) if *last == path => starts.push((i, start)),
+            _ => files.push((path, vec![(i, start)])),
+        }
+    }
+
+    let audio = |i: usize| cue.tracks[i].mode == TrackMode::Audio;
+    let first = (0..cue.tracks.len()).find(|&i| audio(i));
+    let last = (0..cue.tracks.len()).rev().find(|&i| audio(i));
+
+    let mut checksums = Vec::new();
+    let mut current: Option<Accumulator> = None;
+
+    for (path, starts) in files {
+        let mut reader = open(path)?;
+        let mut starts = starts.into_iter().peekable();
+        let mut position = 0;
+
+        read_samples(&mut reader, |sample| {
+            while let Some((i, _)) = starts.next_if(|&(_, start)| start <= position) {
+                checksums.extend(current.take().map(Accumulator::finish));
+
+                if audio(i) {
+                    current = Some(Accumulator::new(
+                        cue.tracks[i].track_index,
+                        Some(i) == first,
+                        Some(i) == last,
+                    ));
+                }
+            }
+
+            if let Some(accumulator) = current.as_mut() {
+                accumulator.push(sample);
+            }
+
+            position += 1;
+        })?;
+    }
+
+    checksums.extend(current.map(Accumulator::finish));
+
+    Ok(checksums)
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidInput, message)
+}
+
+/// Feeds every 32-bit stereo sample in `reader` to `f`, ignoring a trailing partial sample
+fn read_samples(reader: &mut impl Read, mut f: impl FnMut(u32)) -> io::Result<()> {
+    let mut buf = vec![0; BUFFER_SIZE];
+    let mut filled = 0;
+
+    loop {
+        let read = match reader.read(&mut buf[filled..]) {
+            Ok(0) => return Ok(()),
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        filled += read;
+
+        let whole = filled - filled % 4;
+
+        for sample in buf[..whole].chunks_exact(4) {
+            f(u32::from_le_bytes([
+                sample[0], sample[1], sample[2], sample[3],
+            ]));
+        }
+
+        buf.copy_within(whole..filled, 0);
+        filled -= whole;
+    }
+}
+
+/// Running v1/v2 sums for one track
+struct Accumulator {
+    track_index: u8,
+    multiplier: u64,
+    check_start: u64,
+    v1: u32,
+    v2: u32,
+    /// For the last track, contributions are held back until it's known they aren't in the final
+    /// skipped sectors
+    pending: Option<VecDeque<(u32, u32)>>,
+}
+
+impl Accumulator {
+    fn new(track_index: u8, first: bool, last: bool) -> Self {
+        Self {
+            track_index,
+            multiplier: 1,
+            check_start: if first { SKIPPED_SAMPLES } else { 0 },
+            v1: 0,
+            v2: 0,
+            pending: last.then(|| VecDeque::with_capacity(SKIPPED_SAMPLES as usize + 1)),
+        }
+    }
+
+    fn push(&mut self, sample: u32) {
+        if self.multiplier >= self.check_start {
+            let product = sample as u64 * self.multiplier;
+            let (lo, hi) = (product as u32, (product >> 32) as u32);
+
+            match self.pending.as_mut() {
+                Some(pending) => {
+                    pending.push_back((lo, hi));
+
+                    if pending.len() as u64 > SKIPPED_SAMPLES {
+                        let (lo, hi) = pending.pop_front().unwrap();
+                        self.add(lo, hi);
+                    }
+                }
+                None => self.add(lo, hi),
+            }
+        }
+
+        self.multiplier += 1;
+    }
+
+    fn add(&mut self, lo: u32, hi: u32) {
+        self.v1 = self.v1.wrapping_add(lo);
+        self.v2 = self.v2.wrapping_add(lo).wrapping_add(hi);
+    }
+
+    fn finish(self) -> TrackChecksum {
+        TrackChecksum {
+            track_index: self.track_index,
+            v1: self.v1,
+            v2: self.v2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use super::*;
+
+    fn silence_of_ones(samples: usize) -> Vec<u8> {
+        1u32.to_le_bytes().repeat(samples)
+    }
+
+    #[test]
+    fn skips_first_and_last_sectors() {
+        let cue = Cue::from_str(
+            "FILE \"disc.bin\" BINARY\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 00:00:10\n",
+        )
+        .unwrap();
+
+        // 10 sectors in track 1, 11 in track 2
+        let data = silence_of_ones(588 * 21);
+        let sums = checksums(&cue, |_| Ok(&data[..])).unwrap();
+
+        // multipliers 2940..=5880 for track 1, 1..=(6468 - 2940) for track 2
+        let track_1 = (2940..=5880).sum::<u32>();
+        let track_2 = (1..=3528).sum::<u32>();
+
+        assert_eq!(
+            sums,
+            vec![
+                TrackChecksum {
+                    track_index: 1,
+                    v1: track_1,
+                    v2: track_1
+                },
+                TrackChecksum {
+                    track_index: 2,
+                    v1: track_2,
+                    v2: track_2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tracks_span_files() {
+        let cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nFILE \"b.wav\" WAVE\nTRACK 02 AUDIO\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        let files: HashMap<PathBuf, Vec<u8>> = [
+            (PathBuf::from("a.wav"), silence_of_ones(588 * 10)),
+            (PathBuf::from("b.wav"), silence_of_ones(588 * 10)),
+        ]
+        .into();
+
+        let sums = checksums(&cue, |path| Ok(&files[path][..])).unwrap();
+
+        assert_eq!(sums[0].v1, (2940..=5880).sum::<u32>());
+        assert_eq!(sums[1].v1, (1..=2940).sum::<u32>());
+    }
+}
@@ -0,0 +1,175 @@
+//! Random cues for fuzzing. Only values that a cue can hold are generated, e.g. text without
+//! quotes or line breaks and tracks numbered in order, so that they can be written out and parsed
+//! back.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{Catalog, Cue, FileFormat, Frames, Track, TrackFlags, TrackIndex, TrackMode};
+
+impl<'a> Arbitrary<'a> for Frames {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.int_in_range(0..=Self::MAX.0).map(Self)
+    }
+}
+
+impl<'a> Arbitrary<'a> for TrackFlags {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::from_bits_truncate(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for TrackMode {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        use TrackMode::*;
+
+        u.choose_iter([
+            Audio, Cdg, Mode1_2048, Mode1_2352, Mode2_2336, Mode2_2352, Cdi_2336, Cdi_2352,
+        ])
+    }
+}
+
+impl<'a> Arbitrary<'a> for FileFormat {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        use FileFormat::*;
+
+        let other = Other(["FLAC", "OGG", "WV"][u.choose_index(3)?].into());
+
+        u.choose_iter([Unspecified, Binary, Motorola, Aiff, Wave, Mp3, other])
+    }
+}
+
+impl<'a> Arbitrary<'a> for TrackIndex {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::new(u.int_in_range(0..=99)?, Some(u.arbitrary()?)))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Track {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut track = Self::new(u.int_in_range(1..=99)?, u.arbitrary()?);
+
+        track.set_indices(
+            u.arbitrary_iter::<TrackIndex>()?
+                .take(4)
+                .collect::<Result<Vec<_>>>()?,
+        );
+        track.flags = u.arbitrary()?;
+        track.title = text(u)?;
+        track.performer = text(u)?.map(Into::into);
+        track.songwriter = text(u)?.map(Into::into);
+        track.arranger = text(u)?.map(Into::into);
+        track.isrc = isrc(u)?;
+        track.pregap = u.arbitrary()?;
+        track.postgap = u.arbitrary()?;
+
+        if u.arbitrary()? {
+            track.set_file(file(u)?, u.arbitrary()?);
+        }
+
+        Ok(track)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Cue {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut cue = Cue {
+            catalog: catalog(u)?,
+            title: text(u)?,
+            performer: text(u)?.map(Into::into),
+            songwriter: text(u)?.map(Into::into),
+            arranger: text(u)?.map(Into::into),
+            leadout: u.arbitrary()?,
+            ..Default::default()
+        };
+
+        for comment in u.arbitrary_iter::<String>()?.take(4) {
+            cue.comments.push(line(comment?));
+        }
+
+        let (path, format) = (file(u)?, u.arbitrary::<FileFormat>()?);
+
+        for number in 1..=u.int_in_range(0..=99)? {
+            let mut track: Track = u.arbitrary()?;
+
+            track.track_index = number;
+
+            if track.file.is_none() {
+                track.set_file(&path, format.clone());
+            }
+
+            cue.tracks.push(track);
+        }
+
+        Ok(cue)
+    }
+}
+
+/// Removes what a line of a cue can't hold
+fn line(text: String) -> String {
+    text.replace(['\r', '\n'], "")
+}
+
+/// Text that can be written quoted
+fn text(u: &mut Unstructured) -> Result<Option<String>> {
+    let text: Option<String> = u.arbitrary()?;
+
+    Ok(text.map(|text| line(text).replace('"', "")))
+}
+
+fn file(u: &mut Unstructured) -> Result<String> {
+    Ok(text(u)?.unwrap_or_default() + ".wav")
+}
+
+fn digits(u: &mut Unstructured, len: usize) -> Result<String> {
+    (0..len)
+        .map(|_| Ok(char::from(b'0' + u.int_in_range(0..=9)?)))
+        .collect()
+}
+
+fn catalog(u: &mut Unstructured) -> Result<Option<Catalog>> {
+    if !u.arbitrary()? {
+        return Ok(None);
+    }
+
+    let len = u.int_in_range(12..=13)?;
+
+    digits(u, len).map(|digits| Some(Catalog::raw(digits)))
+}
+
+fn isrc(u: &mut Unstructured) -> Result<Option<String>> {
+    if !u.arbitrary()? {
+        return Ok(None);
+    }
+
+    let mut isrc: String = (0..5)
+        .map(|_| Ok(char::from(b'A' + u.int_in_range(0..=25)?)))
+        .collect::<Result<_>>()?;
+
+    isrc += &digits(u, 7)?;
+
+    Ok(Some(isrc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_valid_values() {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i * 7919 % 251) as u8).collect();
+        let mut u = Unstructured::new(&data);
+
+        let cue = Cue::arbitrary(&mut u).unwrap();
+
+        for (i, track) in cue.tracks.iter().enumerate() {
+            assert_eq!(usize::from(track.track_index), i + 1);
+            assert!(track.file.is_some());
+        }
+
+        for text in cue.title.iter().chain(&cue.comments) {
+            assert!(!text.contains(['"', '\r', '\n']));
+        }
+
+        assert!(Frames::arbitrary(&mut u).unwrap() <= Frames::MAX);
+    }
+}
@@ -0,0 +1,78 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use cueparse::{convert, DiscLayout};
+
+use crate::{cue_path, read_cue};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The cue to convert, or `-` for stdin
+    path: Option<PathBuf>,
+    /// The format to convert to
+    #[arg(long, value_enum)]
+    to: Format,
+    /// The audio file for `--to rekordbox`, by default the cue's FILE next to it
+    #[arg(long, value_name = "FILE")]
+    audio: Option<PathBuf>,
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Format {
+    /// cdrdao TOC file
+    Toc,
+    /// FFmpeg metadata file with chapters
+    Ffmetadata,
+    /// Matroska chapters XML
+    Mkvchapters,
+    /// OGM simple chapters, as also read by mkvmerge
+    Ogmchapters,
+    /// The serde JSON of the cue
+    Json,
+    /// xmcd file, as used by CDDB
+    Xmcd,
+    /// PQ sheet, for an image with a REM LEAD-OUT
+    Pq,
+    /// Rekordbox XML collection with the tracks as cue points
+    Rekordbox,
+}
+
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let cue = read_cue(cue_path(&args.path))?;
+
+    let converted = match args.to {
+        Format::Toc => convert::to_toc(&cue)?,
+        Format::Ffmetadata => convert::to_ffmetadata(&cue)?,
+        Format::Mkvchapters => convert::to_mkv_chapters(&cue)?,
+        Format::Ogmchapters => convert::to_ogm_chapters(&cue)?,
+        Format::Json => serde_json::to_string_pretty(&cue)? + "\n",
+        Format::Xmcd => convert::to_xmcd(&cue)?,
+        Format::Pq => convert::to_pq_sheet(&cue, &DiscLayout::new(&cue, |_| None)?),
+        Format::Rekordbox => {
+            let audio = match (&args.audio, &args.path, cue.tracks.first()) {
+                (Some(audio), _, _) => audio.clone(),
+                (None, Some(path), Some(track)) if path != Path::new("-") => path
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .join(track.file.as_deref().unwrap_or(Path::new(""))),
+                _ => return Err("--to rekordbox needs --audio".into()),
+            };
+
+            convert::to_rekordbox_xml(&cue, &std::path::absolute(audio)?)?
+        }
+    };
+
+    match &args.output {
+        Some(path) => fs::write(path, converted)?,
+        None => io::stdout().lock().write_all(converted.as_bytes())?,
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,45 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use cueparse::DiffOptions;
+
+use crate::read_cue;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The original cue, or `-` for stdin
+    old: PathBuf,
+    /// The cue to compare it with
+    new: PathBuf,
+    /// Leave out changes to REM comments
+    #[arg(long)]
+    ignore_comments: bool,
+    /// Leave out changes only in whitespace or path separators
+    #[arg(long)]
+    ignore_formatting: bool,
+}
+
+/// Exits with 1 when the cues differ, like `diff`
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let old = read_cue(&args.old)?;
+    let new = read_cue(&args.new)?;
+    let options = DiffOptions {
+        ignore_comments: args.ignore_comments,
+        ignore_formatting: args.ignore_formatting,
+    };
+    let changes = old.diff_with(&new, &options);
+    let mut out = io::stdout().lock();
+
+    for change in &changes {
+        writeln!(out, "{change}")?;
+    }
+
+    Ok(if changes.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
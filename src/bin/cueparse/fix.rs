@@ -0,0 +1,97 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use cueparse::{decode, Cue};
+
+use crate::{cue_path, display_path, read_input};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The cue to repair, or `-` for stdin
+    path: Option<PathBuf>,
+    /// Also make repairs that could be wrong, such as renumbering tracks that are out of order
+    #[arg(long)]
+    force: bool,
+    /// Overwrite the file with the repaired cue, instead of printing it
+    #[arg(short, long)]
+    in_place: bool,
+}
+
+/// Prints the repaired cue, and what was changed to stderr
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let path = cue_path(&args.path);
+    let bytes = read_input(path)?;
+    let text = decode(&bytes);
+    let mut cue = Cue::from_str(&text).map_err(|e| format!("{}:{e}", display_path(path)))?;
+
+    for track in misplaced_flags(&text) {
+        eprintln!("fixed: track {track:02}: moved FLAGS before its indices");
+    }
+
+    let mut skipped = false;
+
+    for repair in cue.repair(args.force) {
+        if repair.applied {
+            eprintln!("fixed: {repair}");
+        } else {
+            eprintln!("skipped, needs --force: {repair}");
+            skipped = true;
+        }
+    }
+
+    let fixed = cue.to_string();
+
+    if !args.in_place {
+        io::stdout().lock().write_all(fixed.as_bytes())?;
+    } else if path == Path::new("-") {
+        return Err("can't fix stdin in place".into());
+    } else {
+        fs::write(path, fixed)?;
+    }
+
+    Ok(if skipped {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Tracks with `FLAGS` after an `INDEX`, which some players ignore. Writing the cue back out
+/// puts them first.
+fn misplaced_flags(text: &str) -> Vec<u8> {
+    let mut tracks = Vec::new();
+    let mut track: Option<u8> = None;
+    let mut seen_index = false;
+
+    for line in text.lines() {
+        let mut words = line.split_whitespace();
+
+        match words.next() {
+            Some("TRACK") => {
+                track = words.next().and_then(|number| number.parse().ok());
+                seen_index = false;
+            }
+            Some("INDEX") => seen_index = true,
+            Some("FLAGS") if seen_index => tracks.extend(track),
+            _ => {}
+        }
+    }
+
+    tracks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_misplaced_flags() {
+        let text = "FILE a.wav WAVE\nTRACK 01 AUDIO\nFLAGS DCP\nINDEX 01 00:00:00\nTRACK 02 AUDIO\n  INDEX 01 01:00:00\n  FLAGS PRE\n";
+
+        assert_eq!(misplaced_flags(text), [2]);
+    }
+}
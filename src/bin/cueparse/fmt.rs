@@ -0,0 +1,36 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use crate::{cue_path, read_cue};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The cue to format, or `-` for stdin
+    path: Option<PathBuf>,
+    /// Overwrite the file with the formatted cue, instead of printing it
+    #[arg(short, long)]
+    in_place: bool,
+}
+
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let path = cue_path(&args.path);
+    let mut cue = read_cue(path)?;
+
+    cue.normalize();
+
+    let formatted = cue.to_string();
+
+    if !args.in_place {
+        io::stdout().lock().write_all(formatted.as_bytes())?;
+    } else if path == Path::new("-") {
+        return Err("can't format stdin in place".into());
+    } else {
+        fs::write(path, formatted)?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
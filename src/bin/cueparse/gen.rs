@@ -0,0 +1,52 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use cueparse::generate;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// A track for each audio file in this directory, sorted by name
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with = "from_image",
+        required_unless_present = "from_image"
+    )]
+    from_dir: Option<PathBuf>,
+    /// A single image of the whole disc or mix, split into tracks by `--tracklist`
+    #[arg(long, value_name = "FILE", requires = "tracklist")]
+    from_image: Option<PathBuf>,
+    /// Lines giving where each track of the image starts and its title, such as `03:45 Title`
+    #[arg(long, value_name = "FILE", requires = "from_image")]
+    tracklist: Option<PathBuf>,
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// The cue refers to files by name alone, to be saved next to them
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let cue = match (&args.from_dir, &args.from_image, &args.tracklist) {
+        (Some(dir), _, _) => generate::from_dir(dir)?,
+        (_, Some(image), Some(tracklist)) => {
+            let name = image.file_name().map_or(image.as_path(), Path::new);
+
+            generate::from_tracklist(name, &fs::read_to_string(tracklist)?)
+                .map_err(|e| format!("{}: {e}", tracklist.display()))?
+        }
+        _ => unreachable!("checked by clap"),
+    };
+
+    let text = cue.to_string();
+
+    match &args.output {
+        Some(path) => fs::write(path, text)?,
+        None => io::stdout().lock().write_all(text.as_bytes())?,
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
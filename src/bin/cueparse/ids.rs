@@ -0,0 +1,49 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use cueparse::{discid::DiscToc, Frames};
+
+use crate::{cue_path, read_cue};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The cue of a whole disc image, or `-` for stdin
+    path: Option<PathBuf>,
+    /// The start of the lead-out, for cues without a REM LEAD-OUT
+    #[arg(long, value_name = "MM:SS:FF", value_parser = parse_msf)]
+    leadout: Option<Frames>,
+}
+
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let mut cue = read_cue(cue_path(&args.path))?;
+
+    if args.leadout.is_some() {
+        cue.leadout = args.leadout;
+    }
+
+    let toc = DiscToc::from_cue(&cue)?;
+    let mut out = io::stdout().lock();
+
+    writeln!(out, "MusicBrainz  {}", toc.musicbrainz())?;
+    writeln!(out, "CDDB         {:08x}", toc.cddb())?;
+    writeln!(out, "AccurateRip  {}", toc.accuraterip())?;
+    writeln!(out, "CTDB         {}", toc.ctdb())?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn parse_msf(text: &str) -> Result<Frames, String> {
+    let parts: Vec<u32> = text
+        .split(':')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("{text} isn't MM:SS:FF"))?;
+
+    match parts[..] {
+        [m, s, f] if s < 60 && f < 75 => Frames::from_msf(m, s, f).map_err(|e| e.to_string()),
+        _ => Err(format!("{text} isn't MM:SS:FF")),
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use cueparse::{Cue, Diagnostic, DiscCapacity, LintProfile, ParseOptions, Severity};
+
+use crate::{display_path, read_input};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The cues to check, or `-` for stdin
+    #[arg(default_value = "-")]
+    paths: Vec<PathBuf>,
+    /// Also check the limits of a standard
+    #[arg(long, value_enum, default_value_t = Profile::Default)]
+    profile: Profile,
+    /// The length in minutes of the CD the Red Book profile checks the disc fits on
+    #[arg(long, value_enum, default_value_t = Capacity::Minutes80)]
+    capacity: Capacity,
+    /// Fail on warnings as well as errors
+    #[arg(long)]
+    deny_warnings: bool,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Profile {
+    Default,
+    Redbook,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Capacity {
+    #[value(name = "74")]
+    Minutes74,
+    #[value(name = "80")]
+    Minutes80,
+    #[value(name = "90")]
+    Minutes90,
+    #[value(name = "99")]
+    Minutes99,
+}
+
+/// Exits with 1 if any cue has errors, or warnings with `--deny-warnings`, so that it can be
+/// used in scripts and CI
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let profile = match args.profile {
+        Profile::Default => LintProfile::Default,
+        Profile::Redbook => LintProfile::RedBook,
+    };
+    let capacity = match args.capacity {
+        Capacity::Minutes74 => DiscCapacity::Minutes74,
+        Capacity::Minutes80 => DiscCapacity::Minutes80,
+        Capacity::Minutes90 => DiscCapacity::Minutes90,
+        Capacity::Minutes99 => DiscCapacity::Minutes99,
+    };
+    let mut out = io::stdout().lock();
+    let mut failed = false;
+
+    for path in &args.paths {
+        let source = match read_source(path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("cueparse: {}: {e}", display_path(path));
+                failed = true;
+                continue;
+            }
+        };
+
+        for diagnostic in check(&source, profile, capacity) {
+            failed |= diagnostic.severity == Severity::Error || args.deny_warnings;
+
+            write_diagnostic(&mut out, &display_path(path), &source, &diagnostic)?;
+        }
+    }
+
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn read_source(path: &Path) -> io::Result<String> {
+    Ok(cueparse::decode(&read_input(path)?).into_owned())
+}
+
+/// Parses leniently, so that every problem the parser can work around is reported rather than
+/// only the first, then lints what was parsed
+fn check(source: &str, profile: LintProfile, capacity: DiscCapacity) -> Vec<Diagnostic> {
+    let options = ParseOptions {
+        lenient: true,
+        ..Default::default()
+    };
+
+    match Cue::from_str_with_warnings(source, &options) {
+        Ok(outcome) => {
+            let warnings = outcome.warnings.iter().map(Diagnostic::from);
+            let lints = outcome.cue.lint_with_capacity(profile, capacity);
+
+            warnings.chain(lints.iter().map(Diagnostic::from)).collect()
+        }
+        Err(e) => vec![Diagnostic::from(&e)],
+    }
+}
+
+/// Writes a diagnostic in the style of rustc, underlining where it is in the source
+fn write_diagnostic(
+    out: &mut impl Write,
+    path: &str,
+    source: &str,
+    diagnostic: &Diagnostic,
+) -> io::Result<()> {
+    let severity = diagnostic.severity.as_str();
+
+    writeln!(
+        out,
+        "{severity}[{}]: {}",
+        diagnostic.code, diagnostic.message
+    )?;
+
+    let Some(location) = diagnostic.location else {
+        return writeln!(out, " --> {path}\n");
+    };
+
+    let line = source.lines().nth(location.line - 1).unwrap_or_default();
+    let number = location.line.to_string();
+    let pad = " ".repeat(number.len());
+
+    let line_end = source[location.start..]
+        .find(['\r', '\n'])
+        .map_or(source.len(), |end| location.start + end);
+    let width = source[location.start..location.end.min(line_end)]
+        .chars()
+        .count()
+        .max(1);
+    let indent = " ".repeat(location.column - 1);
+
+    writeln!(out, "{pad}--> {path}:{}:{}", location.line, location.column)?;
+    writeln!(out, "{pad} |")?;
+    writeln!(out, "{number} | {line}")?;
+    writeln!(out, "{pad} | {indent}{}\n", "^".repeat(width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotates_source() {
+        let source = "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 1:2\n";
+        let diagnostics = check(source, LintProfile::Default, DiscCapacity::default());
+        let mut out = Vec::new();
+
+        write_diagnostic(&mut out, "a.cue", source, &diagnostics[0]).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "error[CUE003]: Expected MM:SS:FF, HH:MM:SS:FF or a number of frames\n --> a.cue:3:10\n  |\n3 | INDEX 01 1:2\n  |          ^^^\n\n"
+        );
+
+        let lints = check(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\n",
+            LintProfile::Default,
+            DiscCapacity::default(),
+        );
+
+        assert_eq!(lints[0].code, "CUE201");
+    }
+}
@@ -0,0 +1,134 @@
+//! Command line tool for inspecting cues, installed with `cargo install cueparse --features cli`
+
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand};
+use cueparse::{Cue, Frames};
+
+mod convert;
+mod diff;
+mod fix;
+mod fmt;
+mod gen;
+mod ids;
+mod lint;
+mod parse;
+mod split;
+#[cfg(feature = "lofty")]
+mod tag;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints the parsed structure of a cue
+    Parse(parse::Args),
+    /// Checks cues for problems, printing each with where it is
+    Lint(lint::Args),
+    /// Converts a cue into the chapter or disc format of another tool
+    Convert(convert::Args),
+    /// Compares two cues field by field, printing what changed
+    Diff(diff::Args),
+    /// Repairs common mistakes in a cue, printing what was changed
+    Fix(fix::Args),
+    /// Rewrites a cue in a canonical layout, with consistent quoting and indentation
+    Fmt(fmt::Args),
+    /// Creates a cue for a directory of tracks, or for an image and a tracklist
+    Gen(gen::Args),
+    /// Prints the MusicBrainz, CDDB, AccurateRip and CTDB identifiers of a disc
+    Ids(ids::Args),
+    /// Splits a disc image into a file for each track
+    Split(split::Args),
+    /// Tags the files split from a cue with its titles, performers and other metadata
+    #[cfg(feature = "lofty")]
+    Tag(tag::Args),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Parse(args) => parse::run(&args),
+        Command::Lint(args) => lint::run(&args),
+        Command::Convert(args) => convert::run(&args),
+        Command::Diff(args) => diff::run(&args),
+        Command::Fix(args) => fix::run(&args),
+        Command::Fmt(args) => fmt::run(&args),
+        Command::Gen(args) => gen::run(&args),
+        Command::Ids(args) => ids::run(&args),
+        Command::Split(args) => split::run(&args),
+        #[cfg(feature = "lofty")]
+        Command::Tag(args) => tag::run(&args),
+    };
+
+    match result {
+        Ok(code) => code,
+        // Output piped into something like `head`, which has seen enough
+        Err(e)
+            if e.downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("cueparse: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Reads a cue from a file, or from stdin for `-`, detecting its text encoding. Both are read as
+/// bytes and parsed the same way, with the limit on the length of untrusted input. Errors are
+/// prefixed with the path, as in `album.cue:3:10: ...`.
+fn read_cue(path: &Path) -> Result<Cue> {
+    let result = read_input(path)
+        .map_err(cueparse::Error::from)
+        .and_then(|bytes| Cue::from_bytes(&bytes));
+
+    result.map_err(|e| format!("{}:{e}", display_path(path)).into())
+}
+
+/// The bytes of a file, or of stdin for `-`
+fn read_input(path: &Path) -> io::Result<Vec<u8>> {
+    if path != Path::new("-") {
+        return fs::read(path);
+    }
+
+    let mut bytes = Vec::new();
+
+    io::stdin().read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+fn display_path(path: &Path) -> String {
+    if path == Path::new("-") {
+        "<stdin>".into()
+    } else {
+        path.display().to_string()
+    }
+}
+
+/// The path of a cue argument, reading stdin when it's left out
+fn cue_path(path: &Option<PathBuf>) -> &Path {
+    path.as_deref().unwrap_or(Path::new("-"))
+}
+
+fn msf(frames: Frames) -> String {
+    let (m, s, f) = frames.to_msf();
+
+    format!("{m:02}:{s:02}:{f:02}")
+}
@@ -0,0 +1,112 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use cueparse::{Cue, Track};
+
+use crate::{cue_path, msf, read_cue};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The cue to read, or `-` for stdin
+    path: Option<PathBuf>,
+    /// Print JSON instead of text
+    #[arg(long)]
+    json: bool,
+}
+
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let cue = read_cue(cue_path(&args.path))?;
+    let mut out = io::stdout().lock();
+
+    if args.json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&cue)?)?;
+    } else {
+        write_text(&cue, &mut out)?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn write_field(
+    out: &mut impl Write,
+    indent: &str,
+    name: &str,
+    value: Option<&str>,
+) -> io::Result<()> {
+    match value {
+        Some(value) => writeln!(out, "{indent}{name:<11}{value}"),
+        None => Ok(()),
+    }
+}
+
+/// One field per line, with each track's times and length
+fn write_text(cue: &Cue, out: &mut impl Write) -> io::Result<()> {
+    write_field(out, "", "Title", cue.title.as_deref())?;
+    write_field(out, "", "Performer", cue.performer.as_deref())?;
+    write_field(out, "", "Songwriter", cue.songwriter.as_deref())?;
+    write_field(out, "", "Catalog", cue.catalog.as_deref())?;
+    write_field(out, "", "Genre", cue.genre.as_deref())?;
+    write_field(out, "", "Date", cue.date.as_deref())?;
+    write_field(out, "", "Duration", cue.duration().map(msf).as_deref())?;
+
+    for comment in &cue.comments {
+        write_field(out, "", "Comment", Some(comment))?;
+    }
+
+    for (file, tracks) in cue.tracks_by_file() {
+        let file = file.map(|file| match tracks[0].format.as_str() {
+            Some(format) => format!("{} ({format})", file.display()),
+            None => file.display().to_string(),
+        });
+
+        writeln!(out)?;
+        write_field(out, "", "File", Some(file.as_deref().unwrap_or("none")))?;
+
+        for track in tracks {
+            write_track(cue, track, out)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_track(cue: &Cue, track: &Track, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "  Track {:02} ({})", track.track_index, track.mode)?;
+    write_field(out, "    ", "Title", track.title.as_deref())?;
+    write_field(out, "    ", "Performer", track.performer.as_deref())?;
+    write_field(out, "    ", "ISRC", track.isrc.as_deref())?;
+
+    for index in track.indices() {
+        let name = format!("Index {:02}", index.index());
+
+        write_field(out, "    ", &name, index.time().map(msf).as_deref())?;
+    }
+
+    let length = cue.track_length(track.track_index).map(msf);
+
+    write_field(out, "    ", "Length", length.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_layout() {
+        let cue = Cue::from_str(
+            "TITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One\"\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+        let mut out = Vec::new();
+
+        write_text(&cue, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Title      Album\n\nFile       a.wav (WAVE)\n  Track 01 (AUDIO)\n    Title      One\n    Index 01   00:00:00\n    Length     01:00:00\n  Track 02 (AUDIO)\n    Index 01   01:00:00\n"
+        );
+    }
+}
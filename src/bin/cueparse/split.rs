@@ -0,0 +1,284 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use cueparse::{Cue, FileFormat, FilenameOptions, SectorRange, Template};
+
+use crate::read_cue;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The cue describing the image
+    path: PathBuf,
+    /// The image to split, instead of the cue's FILE. Only for cues with a single FILE.
+    #[arg(long)]
+    image: Option<PathBuf>,
+    /// Where to write the tracks
+    #[arg(long, default_value = ".")]
+    out: PathBuf,
+    /// Name the tracks from their metadata, as in "%tracknumber:2% - %title%", instead of as
+    /// in Redump dumps
+    #[arg(long)]
+    template: Option<Template>,
+    /// Print the byte range of each track instead of writing anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// One track's bytes in an image, and where they go
+struct Plan {
+    track_index: u8,
+    source: PathBuf,
+    output: PathBuf,
+    start: u64,
+    /// `None` to copy to the end of the file
+    end: Option<u64>,
+    /// The `fmt ` chunk of the source for WAVE files, to write a header with
+    wav_format: Option<Vec<u8>>,
+}
+
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let cue = read_cue(&args.path)?;
+    let dir = args.path.parent().unwrap_or(Path::new(""));
+    let plans = plan(
+        &cue,
+        dir,
+        args.image.as_deref(),
+        &args.out,
+        args.template.as_ref(),
+    )?;
+
+    if args.dry_run {
+        for plan in &plans {
+            let end = plan.end.map_or("end".into(), |end| end.to_string());
+
+            println!(
+                "Track {:02}  {} bytes {}..{end} -> {}",
+                plan.track_index,
+                plan.source.display(),
+                plan.start,
+                plan.output.display()
+            );
+        }
+
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    fs::create_dir_all(&args.out)?;
+
+    for (i, plan) in plans.iter().enumerate() {
+        eprint!("[{}/{}] {} ", i + 1, plans.len(), plan.output.display());
+
+        let written = write_track(plan)?;
+
+        eprintln!("({written} bytes)");
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn plan(
+    cue: &Cue,
+    dir: &Path,
+    image: Option<&Path>,
+    out: &Path,
+    template: Option<&Template>,
+) -> crate::Result<Vec<Plan>> {
+    let ranges = cue.sector_ranges();
+
+    if image.is_some() && ranges.windows(2).any(|pair| pair[0].file != pair[1].file) {
+        return Err("--image can only replace a single FILE".into());
+    }
+
+    // Redump pads track numbers only on discs with 10 or more tracks
+    let width = if ranges.len() >= 10 { 2 } else { 1 };
+    let mut plans = Vec::new();
+
+    for range in &ranges {
+        let source = image.map_or_else(|| dir.join(range.file), Path::to_path_buf);
+        let track = cue.track(range.track_index);
+        let format = track.map_or(FileFormat::Unspecified, |track| track.format.clone());
+        let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+
+        let (extension, wav_format, start, end) = match format {
+            FileFormat::Binary | FileFormat::Unspecified | FileFormat::Motorola => {
+                ("bin", None, range.byte_start, range.byte_end())
+            }
+            FileFormat::Wave => {
+                let (data, format) = wav_layout(&mut BufReader::new(File::open(&source)?))?;
+                let (start, end) = wav_range(range, &data, &format);
+
+                ("wav", Some(format), start, end)
+            }
+            format => return Err(format!("can't split {format} files").into()),
+        };
+
+        let name = if let Some((template, track)) = template.zip(track) {
+            let options = FilenameOptions {
+                template: template.clone(),
+                extension: format!(".{extension}"),
+                ..Default::default()
+            };
+
+            track.safe_filename(cue, &options)
+        } else if ranges.len() == 1 {
+            format!("{stem}.{extension}")
+        } else {
+            format!("{stem} (Track {:0width$}).{extension}", range.track_index)
+        };
+
+        plans.push(Plan {
+            track_index: range.track_index,
+            source,
+            output: out.join(name),
+            start,
+            end,
+            wav_format,
+        });
+    }
+
+    Ok(plans)
+}
+
+fn write_track(plan: &Plan) -> io::Result<u64> {
+    let mut source = File::open(&plan.source)?;
+    let mut output = BufWriter::new(File::create(&plan.output)?);
+    let len = match plan.end {
+        Some(end) => end.saturating_sub(plan.start),
+        None => source.metadata()?.len().saturating_sub(plan.start),
+    };
+
+    if let Some(format) = &plan.wav_format {
+        write_wav_header(&mut output, format, len)?;
+    }
+
+    source.seek(SeekFrom::Start(plan.start))?;
+
+    let written = io::copy(&mut source.take(len), &mut output)?;
+
+    output.flush()?;
+
+    Ok(written)
+}
+
+/// The byte range of a WAVE file's audio, and its `fmt ` chunk
+struct WavData {
+    start: u64,
+    len: u64,
+}
+
+fn wav_layout(reader: &mut impl Read) -> io::Result<(WavData, Vec<u8>)> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let mut header = [0; 12];
+    reader.read_exact(&mut header)?;
+
+    if &header[..4] != b"RIFF" || &header[8..] != b"WAVE" {
+        return Err(invalid("not a WAVE file"));
+    }
+
+    let mut offset = 12;
+    let mut format = None;
+
+    loop {
+        let mut chunk = [0; 8];
+        reader.read_exact(&mut chunk)?;
+
+        let len = u32::from_le_bytes(chunk[4..].try_into().unwrap());
+        offset += 8;
+
+        if &chunk[..4] == b"data" {
+            let format = format.ok_or_else(|| invalid("WAVE file has no fmt chunk"))?;
+
+            return Ok((
+                WavData {
+                    start: offset,
+                    len: len.into(),
+                },
+                format,
+            ));
+        }
+
+        // chunks are padded to an even length
+        let padded = u64::from(len) + u64::from(len % 2);
+
+        if &chunk[..4] == b"fmt " {
+            let mut bytes = vec![0; len as usize];
+            reader.read_exact(&mut bytes)?;
+            io::copy(&mut reader.take(padded - u64::from(len)), &mut io::sink())?;
+
+            format = Some(bytes);
+        } else {
+            io::copy(&mut reader.take(padded), &mut io::sink())?;
+        }
+
+        offset += padded;
+    }
+}
+
+/// A track's bytes in a WAVE file, counting frames by the file's byte rate rather than CD sectors
+fn wav_range(range: &SectorRange, data: &WavData, format: &[u8]) -> (u64, Option<u64>) {
+    let byte_rate = format
+        .get(8..12)
+        .map_or(176_400, |rate| u32::from_le_bytes(rate.try_into().unwrap()));
+    let bytes_per_frame = u64::from(byte_rate) / 75;
+    let at = |frames: cueparse::Frames| {
+        (data.start + u64::from(frames.frames()) * bytes_per_frame).min(data.start + data.len)
+    };
+
+    (
+        at(range.start),
+        Some(range.end.map_or(data.start + data.len, at)),
+    )
+}
+
+fn write_wav_header(out: &mut impl Write, format: &[u8], len: u64) -> io::Result<()> {
+    let len = u32::try_from(len).map_err(|_| io::Error::other("track is too long for WAVE"))?;
+    let riff_len = 4 + 8 + format.len() as u32 + 8 + len;
+
+    out.write_all(b"RIFF")?;
+    out.write_all(&riff_len.to_le_bytes())?;
+    out.write_all(b"WAVEfmt ")?;
+    out.write_all(&(format.len() as u32).to_le_bytes())?;
+    out.write_all(format)?;
+    out.write_all(b"data")?;
+    out.write_all(&len.to_le_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_round_trip() {
+        // 44.1kHz 16-bit stereo PCM
+        let mut format = vec![1, 0, 2, 0];
+        format.extend(44100u32.to_le_bytes());
+        format.extend(176_400u32.to_le_bytes());
+        format.extend([4, 0, 16, 0]);
+
+        let mut wav = Vec::new();
+        write_wav_header(&mut wav, &format, 2352 * 3).unwrap();
+        wav.resize(wav.len() + 2352 * 3, 0);
+
+        let (data, read_format) = wav_layout(&mut wav.as_slice()).unwrap();
+
+        assert_eq!((data.start, data.len), (44, 2352 * 3));
+        assert_eq!(read_format, format);
+
+        let cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 00:00:01\n",
+        )
+        .unwrap();
+        let ranges = cue.sector_ranges();
+
+        assert_eq!(wav_range(&ranges[0], &data, &format), (44, Some(44 + 2352)));
+        assert_eq!(
+            wav_range(&ranges[1], &data, &format),
+            (44 + 2352, Some(44 + 2352 * 3))
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use cueparse::generate::AUDIO_EXTENSIONS;
+
+use crate::read_cue;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The cue the files were split from
+    #[arg(long)]
+    cue: PathBuf,
+    /// The directory holding a file for each track
+    #[arg(long)]
+    dir: PathBuf,
+    /// Print which file each track would tag, without changing any
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// Tags each audio file in the directory whose name gives a track number, such as
+/// `01 - Title.flac` or `Album (Track 1).wav`
+pub fn run(args: &Args) -> crate::Result<ExitCode> {
+    let cue = read_cue(&args.cue)?;
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(&args.dir)? {
+        let path = entry?.path();
+        let is_audio = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                AUDIO_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+            });
+
+        if is_audio {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+
+    let mut failed = false;
+
+    for path in files {
+        let Some(track) = track_number(&path).and_then(|number| cue.track(number)) else {
+            eprintln!("skipped {}: no matching track", path.display());
+            continue;
+        };
+
+        if args.dry_run {
+            println!("Track {:02} -> {}", track.track_index, path.display());
+            continue;
+        }
+
+        match cueparse::tag::write(&cue, track, &path) {
+            Ok(()) => eprintln!(
+                "tagged {} as track {:02}",
+                path.display(),
+                track.track_index
+            ),
+            Err(e) => {
+                eprintln!("cueparse: {}: {e}", path.display());
+                failed = true;
+            }
+        }
+    }
+
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// From a `(Track N)` in the name, as written by `split`, or else the digits it starts with
+fn track_number(path: &Path) -> Option<u8> {
+    let stem = path.file_stem()?.to_str()?;
+    let digits = match stem.rfind("(Track ") {
+        Some(start) => &stem[start + "(Track ".len()..],
+        None => stem,
+    };
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+
+    digits[..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_from_names() {
+        assert_eq!(track_number(Path::new("tracks/01 - One.flac")), Some(1));
+        assert_eq!(track_number(Path::new("Album 2 (Track 12).wav")), Some(12));
+        assert_eq!(track_number(Path::new("cover.flac")), None);
+    }
+}
@@ -0,0 +1,205 @@
+//! Burning a cue straight from its audio files, without writing a cue or TOC for the burner
+//!
+//! [`BurnPlan`] describes each track's source, gap and CD-Text for frontends driving libburn or
+//! a drive directly, and [`BurnPlan::cdrecord_args`] turns it into the arguments of
+//! `cdrecord` or `wodim`.
+
+use std::{ffi::OsString, path::Path};
+
+use crate::{convert::ConvertError, Cue, DiscLayout, Frames, Lba, TrackFlags, TrackMode};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BurnError {
+    #[error(transparent)]
+    Layout(#[from] ConvertError),
+    #[error("track {0} has no FILE")]
+    MissingFile(u8),
+    #[error("track {0} isn't a whole file, which cdrecord needs")]
+    PartialFile(u8),
+    #[error("track {0} adds silence with PREGAP or POSTGAP, which cdrecord can't")]
+    Silence(u8),
+    #[error("cdrecord can't burn track {0}'s mode")]
+    Mode(u8),
+}
+
+/// What to burn, from [`BurnPlan::new`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BurnPlan<'a> {
+    pub catalog: Option<&'a str>,
+    /// The disc's CD-Text
+    pub title: Option<&'a str>,
+    pub performer: Option<&'a str>,
+    pub tracks: Vec<BurnTrack<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BurnTrack<'a> {
+    pub number: u8,
+    pub mode: TrackMode,
+    pub flags: TrackFlags,
+    pub file: &'a Path,
+    /// Where the track's audio starts in its file, at `INDEX 01`
+    pub start: Frames,
+    /// Where it ends, at the next track's `INDEX 01`, so that the next track's gap is burned
+    /// from the end of this one as burners do. `None` to the end of the file.
+    pub end: Option<Frames>,
+    /// The whole gap before the track, including the 2 seconds before track 1
+    pub pregap: Frames,
+    /// The part of [`pregap`](Self::pregap) that's silence added by `PREGAP` rather than
+    /// audio from a file
+    pub silence: Frames,
+    /// Silence after the track from `POSTGAP`
+    pub postgap: Frames,
+    pub isrc: Option<&'a str>,
+    pub title: Option<&'a str>,
+    pub performer: Option<&'a str>,
+}
+
+impl<'a> BurnPlan<'a> {
+    /// Plans to burn the cue, given the length of each of its files from `file_length`, which
+    /// a single file image with a known lead-out doesn't need
+    pub fn new(
+        cue: &'a Cue,
+        file_length: impl FnMut(&Path) -> Option<Frames>,
+    ) -> Result<Self, BurnError> {
+        let layout = DiscLayout::new(cue, file_length)?;
+        let mut tracks = Vec::with_capacity(cue.tracks.len());
+
+        for ((i, track), laid_out) in cue.tracks.iter().enumerate().zip(layout.tracks()) {
+            let file = track
+                .file
+                .as_deref()
+                .ok_or(BurnError::MissingFile(track.track_index))?;
+            let start = track
+                .start()
+                .ok_or(ConvertError::MissingIndex(track.track_index))?;
+            let end = cue
+                .tracks
+                .get(i + 1)
+                .filter(|next| next.file == track.file)
+                .and_then(|next| next.start());
+            let mut silence = track.pregap.map_or(0, Frames::frames);
+
+            // the 2 seconds before track 1 are on every disc, not added
+            if i == 0 {
+                silence = silence.saturating_sub(Lba::LEAD_IN);
+            }
+
+            tracks.push(BurnTrack {
+                number: track.track_index,
+                mode: track.mode,
+                flags: track.flags,
+                file,
+                start,
+                end,
+                pregap: laid_out.pregap,
+                silence: Frames::new(silence),
+                postgap: track.postgap.unwrap_or(Frames::ZERO),
+                isrc: track.isrc.as_deref(),
+                title: track.title.as_deref(),
+                performer: track.performer.as_deref(),
+            });
+        }
+
+        Ok(Self {
+            catalog: cue.catalog.as_deref(),
+            title: cue.title.as_deref(),
+            performer: cue.performer.as_deref(),
+            tracks,
+        })
+    }
+
+    /// The arguments to burn the plan disc at once with `cdrecord` or `wodim`, after any for
+    /// the device. Each track must be a whole file, with its gap at the end of the file before,
+    /// as Exact Audio Copy writes by default, since cdrecord takes a track's pregap from the end
+    /// of the track before. CD-Text and SCMS can't be given as arguments and are left out.
+    pub fn cdrecord_args(&self) -> Result<Vec<OsString>, BurnError> {
+        let mut args: Vec<OsString> = vec!["-dao".into(), "-pad".into()];
+
+        if let Some(catalog) = self.catalog {
+            args.push(format!("mcn={catalog}").into());
+        }
+
+        for (i, track) in self.tracks.iter().enumerate() {
+            if track.start != Frames::ZERO || track.end.is_some() {
+                return Err(BurnError::PartialFile(track.number));
+            }
+
+            if track.silence != Frames::ZERO || track.postgap != Frames::ZERO {
+                return Err(BurnError::Silence(track.number));
+            }
+
+            let mode = match track.mode {
+                TrackMode::Audio => "-audio",
+                TrackMode::Mode1_2048 => "-data",
+                TrackMode::Mode2_2336 => "-mode2",
+                _ => return Err(BurnError::Mode(track.number)),
+            };
+
+            args.push(mode.into());
+
+            // track options stay set for the tracks after, so each track sets all of them
+            for (flag, on, off) in [
+                (TrackFlags::PRE_EMPHASIS_ENABLED, "-preemp", "-nopreemp"),
+                (TrackFlags::DIGITAL_COPY_PERMITTED, "-copy", "-nocopy"),
+            ] {
+                args.push(if track.flags.contains(flag) { on } else { off }.into());
+            }
+
+            if let Some(isrc) = track.isrc {
+                args.push(format!("isrc={isrc}").into());
+            }
+
+            if i > 0 {
+                args.push(format!("pregap={}", track.pregap.frames()).into());
+            }
+
+            args.push(track.file.into());
+        }
+
+        Ok(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cdrecord_args() {
+        let cue = Cue::from_str(
+            "CATALOG 0123456789012\nFILE \"01.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nFLAGS DCP\nISRC USABC9900001\nINDEX 00 02:58:00\nFILE \"02.wav\" WAVE\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        let plan = BurnPlan::new(&cue, |_| Some(Frames::new(180 * 75))).unwrap();
+
+        assert_eq!(plan.tracks[1].pregap, Frames::PREGAP_2S);
+        assert_eq!(
+            plan.cdrecord_args().unwrap(),
+            [
+                "-dao",
+                "-pad",
+                "mcn=0123456789012",
+                "-audio",
+                "-nopreemp",
+                "-nocopy",
+                "01.wav",
+                "-audio",
+                "-nopreemp",
+                "-copy",
+                "isrc=USABC9900001",
+                "pregap=150",
+                "02.wav",
+            ]
+        );
+
+        let image = Cue::from_str(
+            "FILE \"disc.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 03:00:00\nREM LEAD-OUT 06:00:00\n",
+        )
+        .unwrap();
+        let plan = BurnPlan::new(&image, |_| None).unwrap();
+
+        assert_eq!(plan.tracks[0].end, Some(Frames::new(180 * 75)));
+        assert_eq!(plan.cdrecord_args(), Err(BurnError::PartialFile(1)));
+    }
+}
@@ -0,0 +1,52 @@
+use crate::Frames;
+
+/// How much audio a disc holds. CD-Rs are sold in several sizes, and burners writing disc at
+/// once can overburn past the nominal size, so the limit a cue is checked against depends on
+/// the media.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DiscCapacity {
+    /// The original Red Book size
+    Minutes74,
+    /// The common size of CD-Rs
+    #[default]
+    Minutes80,
+    Minutes90,
+    /// The largest discs made, up to [`Frames::MAX_DISC`]
+    Minutes99,
+    /// Another limit, such as a measured overburn margin
+    Custom(Frames),
+}
+
+impl DiscCapacity {
+    /// The length of audio the disc holds
+    pub const fn length(self) -> Frames {
+        match self {
+            Self::Minutes74 => Frames::new(74 * 60 * 75),
+            Self::Minutes80 => Frames::new(80 * 60 * 75),
+            Self::Minutes90 => Frames::new(90 * 60 * 75),
+            Self::Minutes99 => Frames::MAX_DISC,
+            Self::Custom(length) => length,
+        }
+    }
+
+    /// Whether audio of this length fits on the disc, ending before its capacity
+    pub fn fits(self, length: Frames) -> bool {
+        length < self.length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacities() {
+        let eighty = Frames::from_msf(80, 0, 0).unwrap();
+
+        assert!(DiscCapacity::default().fits(Frames::from_msf(79, 59, 74).unwrap()));
+        assert!(!DiscCapacity::default().fits(eighty));
+        assert!(DiscCapacity::Minutes90.fits(eighty));
+        assert!(!DiscCapacity::Minutes74.fits(eighty));
+        assert_eq!(DiscCapacity::Minutes99.length().to_msf(), (99, 0, 0));
+    }
+}
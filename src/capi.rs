@@ -0,0 +1,229 @@
+//! C API, for players and tools written in C or C++
+//!
+//! Build a library to link against with
+//! `cargo rustc --release --features capi --crate-type cdylib` (or `staticlib`), and include
+//! `include/cueparse.h`, which is generated from this module by `cbindgen`.
+//!
+//! Strings returned by the accessors are copies, which must be freed with
+//! [`cueparse_string_free`]. Accessors return NULL or -1 for missing values, for a NULL cue, or
+//! for a track index past the end.
+
+use std::{
+    ffi::{c_char, CString},
+    path::Path,
+    ptr, slice,
+};
+
+use crate::{Cue, Frames, Track};
+
+/// Parses `len` bytes at `data` as a cue, detecting the text encoding. Returns NULL on failure,
+/// and sets `*error` to a message if `error` isn't NULL.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, and `error` must be NULL or point to writable
+/// memory for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_parse(
+    data: *const u8,
+    len: usize,
+    error: *mut *mut c_char,
+) -> *mut Cue {
+    let bytes = if data.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len)
+    };
+
+    match Cue::from_bytes(bytes) {
+        Ok(cue) => Box::into_raw(Box::new(cue)),
+        Err(e) => {
+            if !error.is_null() {
+                *error = c_string(Some(&e.to_string()));
+            }
+
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a cue returned by [`cueparse_parse`]. Does nothing for NULL.
+///
+/// # Safety
+///
+/// `cue` must be NULL or returned by [`cueparse_parse`], and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_free(cue: *mut Cue) {
+    if !cue.is_null() {
+        drop(Box::from_raw(cue));
+    }
+}
+
+/// Frees a string returned by this API. Does nothing for NULL.
+///
+/// # Safety
+///
+/// `string` must be NULL or returned by this API, and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_title(cue: *const Cue) -> *mut c_char {
+    c_string(cue.as_ref().and_then(|cue| cue.title.as_deref()))
+}
+
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_performer(cue: *const Cue) -> *mut c_char {
+    c_string(cue.as_ref().and_then(|cue| cue.performer.as_deref()))
+}
+
+/// Length of the disc in frames, if the lead-out is known
+///
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_duration(cue: *const Cue) -> i64 {
+    frames(cue.as_ref().and_then(Cue::duration))
+}
+
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_track_count(cue: *const Cue) -> usize {
+    cue.as_ref().map_or(0, |cue| cue.tracks.len())
+}
+
+/// The number of the track at `index`, counting from 0 in the order of the cue
+///
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_track_number(cue: *const Cue, index: usize) -> i32 {
+    track(cue, index).map_or(-1, |track| track.track_index.into())
+}
+
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_track_title(cue: *const Cue, index: usize) -> *mut c_char {
+    c_string(track(cue, index).and_then(|track| track.title.as_deref()))
+}
+
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_track_performer(cue: *const Cue, index: usize) -> *mut c_char {
+    c_string(track(cue, index).and_then(|track| track.performer.as_deref()))
+}
+
+/// The path of the `FILE` holding the track, as written in the cue
+///
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_track_file(cue: *const Cue, index: usize) -> *mut c_char {
+    let file = track(cue, index).and_then(|track| track.file.as_deref());
+
+    c_string(file.map(Path::to_string_lossy).as_deref())
+}
+
+/// Start of the track's `INDEX 01` within its file, in frames
+///
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_track_start(cue: *const Cue, index: usize) -> i64 {
+    frames(track(cue, index).and_then(Track::start))
+}
+
+/// Length of the track in frames, see [`Cue::track_length`]
+///
+/// # Safety
+///
+/// `cue` must be NULL or a live cue returned by [`cueparse_parse`].
+#[no_mangle]
+pub unsafe extern "C" fn cueparse_track_length(cue: *const Cue, index: usize) -> i64 {
+    let length = cue.as_ref().and_then(|cue| {
+        let track = cue.tracks.get(index)?;
+
+        cue.track_length(track.track_index)
+    });
+
+    frames(length)
+}
+
+unsafe fn track<'a>(cue: *const Cue, index: usize) -> Option<&'a Track> {
+    cue.as_ref()?.tracks.get(index)
+}
+
+fn frames(frames: Option<Frames>) -> i64 {
+    frames.map_or(-1, |frames| frames.frames().into())
+}
+
+/// Copies a string for C, which can't hold NUL characters, so text containing them is cut short
+fn c_string(value: Option<&str>) -> *mut c_char {
+    let Some(value) = value else {
+        return ptr::null_mut();
+    };
+
+    let value = value.split('\0').next().unwrap_or_default();
+
+    CString::new(value).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    unsafe fn take(string: *mut c_char) -> Option<String> {
+        let value = (!string.is_null()).then(|| CStr::from_ptr(string).to_str().unwrap().into());
+
+        cueparse_string_free(string);
+        value
+    }
+
+    #[test]
+    fn parses_and_reads_tracks() {
+        let input = b"TITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nTITLE \"Two\"\nINDEX 01 01:00:00\n";
+
+        unsafe {
+            let cue = cueparse_parse(input.as_ptr(), input.len(), ptr::null_mut());
+
+            assert_eq!(take(cueparse_title(cue)).as_deref(), Some("Album"));
+            assert_eq!(cueparse_track_count(cue), 2);
+            assert_eq!(cueparse_track_number(cue, 1), 2);
+            assert_eq!(take(cueparse_track_title(cue, 1)).as_deref(), Some("Two"));
+            assert_eq!(take(cueparse_track_file(cue, 0)).as_deref(), Some("a.wav"));
+            assert_eq!(cueparse_track_length(cue, 0), 4500);
+            assert_eq!(cueparse_track_start(cue, 2), -1);
+            assert_eq!(take(cueparse_performer(cue)), None);
+
+            cueparse_free(cue);
+
+            let mut error = ptr::null_mut();
+            let input = b"TRACK";
+
+            assert!(cueparse_parse(input.as_ptr(), input.len(), &mut error).is_null());
+            assert!(take(error).unwrap().starts_with("1:6: "));
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::{fmt, ops::Deref, str::FromStr};
+
+use crate::InvalidValue;
+
+/// A disc's `CATALOG` number, the UPC or EAN of the release. Parsing a cue keeps the number as
+/// written even if its check digit is wrong, which [`Catalog::is_valid`] tells apart.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "String", into = "String"))]
+pub struct Catalog {
+    digits: String,
+    valid: bool,
+}
+
+impl Catalog {
+    /// The number as it is, without checking it, for cues read from elsewhere
+    pub fn raw(number: impl Into<String>) -> Self {
+        let digits = number.into();
+        let valid = check(&digits).is_ok();
+
+        Self { digits, valid }
+    }
+
+    /// Whether it's a 12 digit UPC or 13 digit EAN with the right check digit
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// The number as a 13 digit EAN, with a UPC given a leading 0
+    pub fn ean(&self) -> Option<String> {
+        match self.digits.len() {
+            12 if self.valid => Some(format!("0{}", self.digits)),
+            13 if self.valid => Some(self.digits.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.digits
+    }
+}
+
+/// Parses a UPC or EAN, failing if its check digit is wrong
+impl FromStr for Catalog {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        check(s)?;
+
+        Ok(Self {
+            digits: s.into(),
+            valid: true,
+        })
+    }
+}
+
+impl fmt::Display for Catalog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.digits)
+    }
+}
+
+impl Deref for Catalog {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.digits
+    }
+}
+
+impl From<String> for Catalog {
+    fn from(number: String) -> Self {
+        Self::raw(number)
+    }
+}
+
+impl From<Catalog> for String {
+    fn from(catalog: Catalog) -> Self {
+        catalog.digits
+    }
+}
+
+/// Checks that the number is 12 or 13 digits ending in the GTIN check digit, where the other
+/// digits are weighted 3 and 1 alternately from the right
+fn check(number: &str) -> Result<(), InvalidValue> {
+    if !matches!(number.len(), 12 | 13) || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(InvalidValue::CatalogLength);
+    }
+
+    let (digits, check) = number.split_at(number.len() - 1);
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .zip([3, 1].into_iter().cycle())
+        .map(|(digit, weight)| u32::from(digit - b'0') * weight)
+        .sum();
+    let expected = ((10 - sum % 10) % 10) as u8;
+
+    if check.as_bytes()[0] - b'0' == expected {
+        Ok(())
+    } else {
+        Err(InvalidValue::CatalogCheckDigit(expected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_digits() {
+        let upc: Catalog = "036000291452".parse().unwrap();
+
+        assert_eq!(upc.ean().as_deref(), Some("0036000291452"));
+        assert_eq!(
+            "4006381333932".parse::<Catalog>(),
+            Err(InvalidValue::CatalogCheckDigit(1))
+        );
+        assert!(!Catalog::raw("1111111111111").is_valid());
+        assert_eq!(Catalog::raw("1111111111111").to_string(), "1111111111111");
+    }
+}
@@ -0,0 +1,127 @@
+//! Where comments were in a cue, so that writing it keeps them next to the lines they describe
+
+use std::ops::Range;
+
+/// A command that a comment can be kept in front of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum CueCommand {
+    Catalog,
+    CdTextFile,
+    File,
+    Performer,
+    Songwriter,
+    Arranger,
+    Title,
+    /// The first `TRACK`, for global comments after the rest of the global section
+    Track,
+    Flags,
+    Isrc,
+    Pregap,
+    /// The `INDEX` with this number
+    Index(usize),
+    Postgap,
+}
+
+/// That a comment came right before a command in the same section of the cue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CommentPosition {
+    /// The position of the comment in its `comments`
+    pub comment: usize,
+    pub before: CueCommand,
+}
+
+/// Records that the given comments came before `command`
+pub(crate) fn place(
+    positions: &mut Vec<CommentPosition>,
+    comments: Range<usize>,
+    command: CueCommand,
+) {
+    positions.extend(comments.map(|comment| CommentPosition {
+        comment,
+        before: command,
+    }));
+}
+
+/// The comments that came before `command`
+pub(crate) fn before<'a>(
+    comments: &'a [String],
+    positions: &'a [CommentPosition],
+    command: CueCommand,
+) -> impl Iterator<Item = &'a str> {
+    positions
+        .iter()
+        .filter(move |position| position.before == command)
+        .filter_map(|position| comments.get(position.comment))
+        .map(String::as_str)
+}
+
+/// The comments to write in the usual place, as they have no position or came before a command
+/// that won't be written
+pub(crate) fn unplaced<'a>(
+    comments: &'a [String],
+    positions: &'a [CommentPosition],
+    written: impl Fn(CueCommand) -> bool + 'a,
+) -> impl Iterator<Item = &'a str> {
+    comments.iter().enumerate().filter_map(move |(i, comment)| {
+        let placed = positions
+            .iter()
+            .any(|position| position.comment == i && written(position.before));
+
+        (!placed).then_some(comment.as_str())
+    })
+}
+
+/// Updates the positions for only the comments `keep` is true for being kept
+pub(crate) fn retain(
+    comments: &[String],
+    positions: &mut Vec<CommentPosition>,
+    keep: impl Fn(&str) -> bool,
+) {
+    let mut kept = 0;
+    let new_positions: Vec<_> = comments
+        .iter()
+        .map(|comment| {
+            let position = keep(comment).then_some(kept);
+            kept += usize::from(position.is_some());
+            position
+        })
+        .collect();
+
+    positions.retain_mut(
+        |position| match new_positions.get(position.comment).copied().flatten() {
+            Some(comment) => {
+                position.comment = comment;
+                true
+            }
+            None => false,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Cue;
+
+    #[test]
+    fn comments_keep_their_place() {
+        let text = "REM DISCID 12345678\nPERFORMER \"Band\"\nREM COMMENT \"about the title\"\nTITLE \"Album\"\nFILE \"a.wav\" WAVE\nREM before the first track\n  TRACK 01 AUDIO\n    TITLE \"One\"\n    REM the start\n    INDEX 01 00:00:00\n    REM last\n";
+        let cue = Cue::from_str(text).unwrap();
+
+        assert_eq!(cue.to_string(), text);
+        assert_eq!(Cue::from_str(cue.to_string()).unwrap(), cue);
+
+        // PERFORMER is written first, so the comment before it is too
+        let moved = Cue::from_str(
+            "TITLE \"A\"\nREM C1\nPERFORMER \"P\"\nREM C2\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        assert!(moved.to_string().starts_with("REM C1\nPERFORMER"));
+        assert_eq!(Cue::from_str(moved.to_string()).unwrap(), moved);
+    }
+}
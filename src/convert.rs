@@ -0,0 +1,757 @@
+//! Conversion of a cue into the chapter and disc description formats of other tools
+//!
+//! - [`to_ffmetadata`]: FFmpeg's metadata file, for adding chapters with `ffmpeg -i meta.txt`
+//! - [`to_mkv_chapters`]: Matroska chapters XML, for `mkvmerge --chapters`
+//! - [`to_ogm_chapters`]: the simple `CHAPTER01=` text format of OGM, also read by mkvmerge
+//! - [`to_toc`]: a cdrdao TOC file, for burning
+//! - [`to_xmcd`]: an xmcd file, the format of CDDB and freedb entries
+//! - [`to_pq_sheet`]: a PQ sheet, the log of subcode positions delivered with a master
+//! - [`to_rekordbox_xml`]: a Rekordbox collection with the tracks of a mix as cue points
+//!
+//! Apart from TOC files, which can refer to several files, and PQ sheets, which are made from a
+//! [`DiscLayout`], these describe a single timeline, so they need every track to be in one
+//! `FILE`.
+
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    discid::DiscToc, Cue, DiscLayout, Frames, Lba, Track, TrackFlags, TrackLayout, TrackMode,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConvertError {
+    #[error("this format needs a single FILE holding every track")]
+    MultipleFiles,
+    #[error("track {0} has no INDEX 01")]
+    MissingIndex(u8),
+    #[error("the length of the disc isn't known, add a REM LEAD-OUT")]
+    MissingLeadout,
+    #[error("the length of {} isn't known", .0.display())]
+    UnknownFileLength(PathBuf),
+    #[error("the disc ends before track {0} starts")]
+    LeadoutBeforeTrack(u8),
+    #[error("track {0} starts before the track before it")]
+    TrackBeforePrevious(u8),
+    #[error("track {0} is numbered above 99, the last track a disc can have")]
+    TrackNumberTooLarge(u8),
+    #[error("track {0} or its pregap goes past the longest time a cue can hold")]
+    TooLong(u8),
+}
+
+fn single_file(cue: &Cue) -> Result<(), ConvertError> {
+    if cue.tracks_by_file().nth(1).is_some() {
+        return Err(ConvertError::MultipleFiles);
+    }
+
+    Ok(())
+}
+
+fn start(track: &Track) -> Result<Frames, ConvertError> {
+    track
+        .start()
+        .ok_or(ConvertError::MissingIndex(track.track_index))
+}
+
+/// A track on the timeline, ending at the next track or the lead-out
+struct Chapter<'a> {
+    track: &'a Track,
+    start: Frames,
+    end: Option<Frames>,
+}
+
+fn chapters(cue: &Cue) -> Result<Vec<Chapter<'_>>, ConvertError> {
+    single_file(cue)?;
+
+    let starts = cue
+        .tracks
+        .iter()
+        .map(start)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(cue
+        .tracks
+        .iter()
+        .zip(&starts)
+        .enumerate()
+        .map(|(i, (track, &start))| Chapter {
+            track,
+            start,
+            end: starts.get(i + 1).copied().or(cue.leadout),
+        })
+        .collect())
+}
+
+fn msf(frames: Frames) -> String {
+    let (m, s, f) = frames.to_msf();
+
+    format!("{m:02}:{s:02}:{f:02}")
+}
+
+/// FFmpeg metadata, with a chapter per track counted in frames
+pub fn to_ffmetadata(cue: &Cue) -> Result<String, ConvertError> {
+    fn escape(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+
+        for c in value.chars() {
+            if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+                escaped.push('\\');
+            }
+
+            escaped.push(c);
+        }
+
+        escaped
+    }
+
+    let mut out = String::from(";FFMETADATA1\n");
+
+    if let Some(title) = &cue.title {
+        let _ = writeln!(out, "album={}", escape(title));
+    }
+
+    if let Some(performer) = &cue.performer {
+        let _ = writeln!(out, "artist={}", escape(performer));
+    }
+
+    for Chapter { track, start, end } in chapters(cue)? {
+        let _ = write!(
+            out,
+            "\n[CHAPTER]\nTIMEBASE=1/75\nSTART={}\n",
+            start.frames()
+        );
+
+        if let Some(end) = end {
+            let _ = writeln!(out, "END={}", end.frames());
+        }
+
+        if let Some(title) = &track.title {
+            let _ = writeln!(out, "title={}", escape(title));
+        }
+
+        if let Some(performer) = &track.performer {
+            let _ = writeln!(out, "artist={}", escape(performer));
+        }
+    }
+
+    Ok(out)
+}
+
+/// Matroska chapters XML, with a chapter per track
+pub fn to_mkv_chapters(cue: &Cue) -> Result<String, ConvertError> {
+    fn escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    fn timestamp(frames: Frames) -> String {
+        let nanos = u64::from(frames.frames()) * 1_000_000_000 / 75;
+        let secs = nanos / 1_000_000_000;
+
+        format!(
+            "{:02}:{:02}:{:02}.{:09}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            nanos % 1_000_000_000
+        )
+    }
+
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<!DOCTYPE Chapters SYSTEM \"matroskachapters.dtd\">\n",
+        "<Chapters>\n",
+        "  <EditionEntry>\n",
+    ));
+
+    for Chapter { track, start, end } in chapters(cue)? {
+        let title = match &track.title {
+            Some(title) => escape(title),
+            None => format!("Track {:02}", track.track_index),
+        };
+
+        out += "    <ChapterAtom>\n";
+        let _ = writeln!(
+            out,
+            "      <ChapterTimeStart>{}</ChapterTimeStart>",
+            timestamp(start)
+        );
+
+        if let Some(end) = end {
+            let _ = writeln!(
+                out,
+                "      <ChapterTimeEnd>{}</ChapterTimeEnd>",
+                timestamp(end)
+            );
+        }
+
+        out += "      <ChapterDisplay>\n";
+        let _ = writeln!(out, "        <ChapterString>{title}</ChapterString>");
+        out += "      </ChapterDisplay>\n    </ChapterAtom>\n";
+    }
+
+    out += "  </EditionEntry>\n</Chapters>\n";
+
+    Ok(out)
+}
+
+/// OGM chapters, `CHAPTERnn=HH:MM:SS.mmm` and `CHAPTERnnNAME=` lines for each track, with times
+/// rounded down to the millisecond
+pub fn to_ogm_chapters(cue: &Cue) -> Result<String, ConvertError> {
+    let mut out = String::new();
+
+    for (Chapter { track, start, .. }, number) in chapters(cue)?.into_iter().zip(1..) {
+        let millis = u64::from(start.frames()) * 1000 / 75;
+        let secs = millis / 1000;
+        let title = match &track.title {
+            Some(title) => title.clone(),
+            None => format!("Track {:02}", track.track_index),
+        };
+
+        let _ = writeln!(
+            out,
+            "CHAPTER{number:02}={:02}:{:02}:{:02}.{:03}\nCHAPTER{number:02}NAME={title}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            millis % 1000
+        );
+    }
+
+    Ok(out)
+}
+
+/// A cdrdao TOC file. `INDEX 00` becomes the track's pregap, taken from its file.
+pub fn to_toc(cue: &Cue) -> Result<String, ConvertError> {
+    fn quote(value: &str) -> String {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    /// The disc's CD-Text also maps the language numbers used by the tracks'
+    fn cd_text(out: &mut String, title: Option<&str>, performer: Option<&str>, disc: bool) {
+        if title.is_none() && performer.is_none() {
+            return;
+        }
+
+        out.push_str("CD_TEXT {\n");
+
+        if disc {
+            out.push_str("  LANGUAGE_MAP {\n    0 : EN\n  }\n");
+        }
+
+        out.push_str("  LANGUAGE 0 {\n");
+
+        for (name, value) in [("TITLE", title), ("PERFORMER", performer)] {
+            if let Some(value) = value {
+                let _ = writeln!(out, "    {name} {}", quote(value));
+            }
+        }
+
+        out.push_str("  }\n}\n");
+    }
+
+    let disc_type = if cue.tracks.iter().all(|t| t.mode == TrackMode::Audio) {
+        "CD_DA"
+    } else if cue.tracks.iter().any(|t| {
+        matches!(
+            t.mode,
+            TrackMode::Mode2_2336
+                | TrackMode::Mode2_2352
+                | TrackMode::Cdi_2336
+                | TrackMode::Cdi_2352
+        )
+    }) {
+        "CD_ROM_XA"
+    } else {
+        "CD_ROM"
+    };
+
+    let mut out = format!("{disc_type}\n");
+
+    if let Some(catalog) = &cue.catalog {
+        let _ = writeln!(out, "CATALOG {}", quote(catalog));
+    }
+
+    cd_text(
+        &mut out,
+        cue.title.as_deref(),
+        cue.performer.as_deref(),
+        true,
+    );
+
+    for (file, tracks) in cue.tracks_by_file() {
+        let file = quote(&file.map(Path::to_string_lossy).unwrap_or_default());
+
+        for (i, track) in tracks.iter().enumerate() {
+            let start = start(track)?;
+            // an `INDEX 00` after `INDEX 01` isn't a pregap
+            let from = track
+                .index(0)
+                .and_then(|index| index.time())
+                .filter(|&from| from <= start)
+                .unwrap_or(start);
+            let end = tracks.get(i + 1).map(|next| {
+                let next_start = next.start().unwrap_or(start);
+
+                next.index(0)
+                    .and_then(|index| index.time())
+                    .filter(|&from| from <= next_start)
+                    .unwrap_or(next_start)
+            });
+
+            let mode = match track.mode {
+                TrackMode::Audio => "AUDIO",
+                TrackMode::Cdg => "AUDIO RW_RAW",
+                TrackMode::Mode1_2048 => "MODE1",
+                TrackMode::Mode1_2352 => "MODE1_RAW",
+                TrackMode::Mode2_2336 | TrackMode::Cdi_2336 => "MODE2",
+                TrackMode::Mode2_2352 | TrackMode::Cdi_2352 => "MODE2_RAW",
+            };
+
+            let _ = writeln!(out, "\n// Track {}\nTRACK {mode}", track.track_index);
+
+            for (flag, name) in [
+                (TrackFlags::DIGITAL_COPY_PERMITTED, "COPY"),
+                (TrackFlags::PRE_EMPHASIS_ENABLED, "PRE_EMPHASIS"),
+                (TrackFlags::FOUR_CHANNEL, "FOUR_CHANNEL_AUDIO"),
+            ] {
+                if track.flags.contains(flag) {
+                    let _ = writeln!(out, "{name}");
+                }
+            }
+
+            if let Some(isrc) = &track.isrc {
+                let _ = writeln!(out, "ISRC {}", quote(isrc));
+            }
+
+            cd_text(
+                &mut out,
+                track.title.as_deref(),
+                track.performer.as_deref(),
+                false,
+            );
+
+            if let Some(pregap) = track.pregap {
+                let _ = writeln!(out, "PREGAP {}", msf(pregap));
+            }
+
+            let source = if track.mode == TrackMode::Audio {
+                format!("FILE {file} {}", msf(from))
+            } else {
+                let offset = u64::from(from.frames()) * track.mode.sector_size() as u64;
+
+                format!("DATAFILE {file} #{offset}")
+            };
+
+            match end.and_then(|end| end.frames().checked_sub(from.frames())) {
+                Some(length) => {
+                    let _ = writeln!(out, "{source} {}", msf(Frames::new(length)));
+                }
+                None => {
+                    let _ = writeln!(out, "{source}");
+                }
+            }
+
+            if from != start {
+                let _ = writeln!(
+                    out,
+                    "START {}",
+                    msf(Frames::new(start.frames() - from.frames()))
+                );
+            }
+
+            for index in track.indices.iter().filter(|index| index.index() > 1) {
+                if let Some(time) = index.time().filter(|&time| time >= start) {
+                    let _ = writeln!(
+                        out,
+                        "INDEX {}",
+                        msf(Frames::new(time.frames() - start.frames()))
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// An xmcd file, as stored by CDDB and freedb, which needs the length of the disc. The offsets
+/// and disc ID are those of the cue.s [`DiscToc`], so they count gaps as the disc IDs do.
+pub fn to_xmcd(cue: &Cue) -> Result<String, ConvertError> {
+    let toc = DiscToc::from_cue(cue)?;
+
+    let mut out = String::from("# xmcd\n#\n# Track frame offsets:\n");
+
+    for offset in &toc.offsets {
+        let _ = writeln!(out, "#\t{offset}");
+    }
+
+    let _ = write!(
+        out,
+        "#\n# Disc length: {} seconds\n#\nDISCID={:08x}\n",
+        toc.leadout / 75,
+        toc.cddb()
+    );
+
+    let album = match (&cue.performer, &cue.title) {
+        (Some(performer), Some(title)) => format!("{performer} / {title}"),
+        (_, title) => title.clone().unwrap_or_default(),
+    };
+
+    // DYEAR only holds a year, which a REM DATE starts with
+    let year = cue
+        .date
+        .as_deref()
+        .and_then(|date| date.get(..4))
+        .filter(|year| year.bytes().all(|b| b.is_ascii_digit()))
+        .unwrap_or_default();
+    let genre = cue.genre.as_deref().unwrap_or_default();
+
+    let _ = writeln!(out, "DTITLE={album}\nDYEAR={year}\nDGENRE={genre}");
+
+    for (i, track) in cue.tracks.iter().enumerate() {
+        let title = match (&track.performer, &track.title) {
+            (Some(performer), Some(title)) if cue.performer.as_ref() != Some(performer) => {
+                format!("{performer} / {title}")
+            }
+            (_, title) => title.clone().unwrap_or_default(),
+        };
+
+        let _ = writeln!(out, "TTITLE{i}={title}");
+    }
+
+    out += "EXTD=\n";
+
+    for i in 0..cue.tracks.len() {
+        let _ = writeln!(out, "EXTT{i}=");
+    }
+
+    out += "PLAYORDER=\n";
+
+    Ok(out)
+}
+
+/// A PQ sheet as mastering houses deliver with a disc image: every index of every track with its
+/// absolute time and length, whether it allows copying and has pre-emphasis, and its ISRC,
+/// followed by the lead-out. The pregap `PREGAP` adds is listed as an `INDEX 00`.
+pub fn to_pq_sheet(cue: &Cue, layout: &DiscLayout) -> String {
+    fn pregap_start(track: &TrackLayout) -> Lba {
+        Lba(track.start() as i32 - track.pregap.frames() as i32)
+    }
+
+    /// As MSF from the start of the disc, which may be past the longest time of a cue
+    fn absolute(lba: Lba) -> String {
+        let (m, s, f) = lba.to_msf().unwrap_or_default();
+
+        format!("{m:02}:{s:02}:{f:02}")
+    }
+
+    fn length(frames: u32) -> String {
+        format!(
+            "{:02}:{:02}:{:02}",
+            frames / 75 / 60,
+            frames / 75 % 60,
+            frames % 75
+        )
+    }
+
+    fn yes_no(value: bool) -> &'static str {
+        if value {
+            "Y"
+        } else {
+            "N"
+        }
+    }
+
+    let mut out = String::new();
+
+    for (name, value) in [
+        (
+            "UPC/EAN",
+            cue.catalog.as_ref().map(|catalog| catalog.as_str()),
+        ),
+        ("Title", cue.title.as_deref()),
+        ("Performer", cue.performer.as_deref()),
+    ] {
+        if let Some(value) = value {
+            let _ = writeln!(out, "{name}: {value}");
+        }
+    }
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+
+    out += "Track  Index  Start     Length    Copy  Emph  ISRC\n";
+
+    for session in &layout.sessions {
+        for (i, track) in session.tracks.iter().enumerate() {
+            // with the pregap starting the silence `PREGAP` adds
+            let mut starts: Vec<_> = track
+                .indices
+                .iter()
+                .filter(|index| index.number > 0)
+                .map(|index| (index.number, Lba(index.lba as i32)))
+                .collect();
+
+            if track.pregap > Frames::ZERO {
+                starts.insert(0, (0, pregap_start(track)));
+            }
+
+            let end = session
+                .tracks
+                .get(i + 1)
+                .map_or(Lba(session.leadout as i32), pregap_start);
+            let isrc = cue
+                .tracks
+                .iter()
+                .find(|t| t.track_index == track.number)
+                .and_then(|t| t.isrc.as_deref())
+                .unwrap_or_default();
+
+            for (j, &(number, start)) in starts.iter().enumerate() {
+                let next = starts.get(j + 1).map_or(end, |&(_, next)| next);
+                let line = format!(
+                    "{:02}     {number:02}     {}  {}  {}     {}     {}",
+                    track.number,
+                    absolute(start),
+                    length(next.0.saturating_sub(start.0).max(0) as u32),
+                    yes_no(track.flags.contains(TrackFlags::DIGITAL_COPY_PERMITTED)),
+                    yes_no(track.flags.contains(TrackFlags::PRE_EMPHASIS_ENABLED)),
+                    if number == 1 { isrc } else { "" },
+                );
+
+                let _ = writeln!(out, "{}", line.trim_end());
+            }
+        }
+    }
+
+    let _ = writeln!(
+        out,
+        "Lead-out      {}",
+        absolute(Lba(layout.leadout as i32))
+    );
+
+    out
+}
+
+/// A Rekordbox XML collection holding the audio file, with a memory cue at every index of every
+/// track and hot cues at the starts of the first eight tracks, to import a mix with its track
+/// markers. `audio` should be the file's absolute path, which Rekordbox finds it by.
+pub fn to_rekordbox_xml(cue: &Cue, audio: &Path) -> Result<String, ConvertError> {
+    fn escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    /// A `file://localhost/` URI, with Windows separators turned around
+    fn location(path: &Path) -> String {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let mut uri = String::from("file://localhost/");
+
+        for byte in path.trim_start_matches('/').bytes() {
+            if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+                uri.push(byte as char);
+            } else {
+                let _ = write!(uri, "%{byte:02X}");
+            }
+        }
+
+        uri
+    }
+
+    fn seconds(frames: Frames) -> String {
+        format!("{:.3}", f64::from(frames.frames()) / 75.0)
+    }
+
+    let chapters = chapters(cue)?;
+    let name = cue
+        .title
+        .clone()
+        .or_else(|| audio.file_stem().map(|stem| stem.to_string_lossy().into()))
+        .unwrap_or_default();
+
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<DJ_PLAYLISTS Version=\"1.0.0\">\n",
+        "  <PRODUCT Name=\"cueparse\"/>\n",
+        "  <COLLECTION Entries=\"1\">\n",
+    ));
+
+    let _ = write!(
+        out,
+        "    <TRACK TrackID=\"1\" Name=\"{}\" Artist=\"{}\" Location=\"{}\"",
+        escape(&name),
+        escape(cue.performer.as_deref().unwrap_or_default()),
+        escape(&location(audio))
+    );
+
+    if let Some(leadout) = cue.leadout {
+        let _ = write!(out, " TotalTime=\"{}\"", leadout.frames() / 75);
+    }
+
+    out += ">\n";
+
+    for (i, Chapter { track, start, .. }) in chapters.iter().enumerate() {
+        let title = match &track.title {
+            Some(title) => escape(title),
+            None => format!("Track {:02}", track.track_index),
+        };
+
+        for index in &track.indices {
+            let Some(time) = index.time() else {
+                continue;
+            };
+            let name = match index.index() {
+                1 => title.clone(),
+                number => format!("{title} (index {number:02})"),
+            };
+
+            let _ = writeln!(
+                out,
+                "      <POSITION_MARK Name=\"{name}\" Type=\"0\" Start=\"{}\" Num=\"-1\"/>",
+                seconds(time)
+            );
+        }
+
+        if i < 8 {
+            let _ = writeln!(
+                out,
+                "      <POSITION_MARK Name=\"{title}\" Type=\"0\" Start=\"{}\" Num=\"{i}\"/>",
+                seconds(*start)
+            );
+        }
+    }
+
+    out += concat!(
+        "    </TRACK>\n",
+        "  </COLLECTION>\n",
+        "  <PLAYLISTS>\n",
+        "    <NODE Type=\"0\" Name=\"ROOT\" Count=\"0\"/>\n",
+        "  </PLAYLISTS>\n",
+        "</DJ_PLAYLISTS>\n",
+    );
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue() -> Cue {
+        Cue::from_str(
+            "PERFORMER \"Artist\"\nTITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One\"\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nTITLE \"A <b> & c\"\nFLAGS DCP\nINDEX 00 02:58:00\nINDEX 01 03:00:00\nREM LEAD-OUT 05:00:00\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn chapter_formats() {
+        let cue = cue();
+        let ffmetadata = to_ffmetadata(&cue).unwrap();
+        let mkv = to_mkv_chapters(&cue).unwrap();
+
+        assert!(ffmetadata.starts_with(";FFMETADATA1\nalbum=Album\nartist=Artist\n"));
+        assert!(ffmetadata
+            .contains("[CHAPTER]\nTIMEBASE=1/75\nSTART=13500\nEND=22500\ntitle=A <b> & c\n"));
+        assert!(mkv.contains("<ChapterTimeStart>00:03:00.000000000</ChapterTimeStart>"));
+        assert!(mkv.contains("<ChapterString>A &lt;b&gt; &amp; c</ChapterString>"));
+        assert_eq!(
+            to_ogm_chapters(&cue).unwrap(),
+            "CHAPTER01=00:00:00.000\nCHAPTER01NAME=One\nCHAPTER02=00:03:00.000\nCHAPTER02NAME=A <b> & c\n"
+        );
+
+        let mut split = cue.clone();
+        split.tracks[1].set_file("b.wav", crate::FileFormat::Wave);
+
+        assert_eq!(to_ffmetadata(&split), Err(ConvertError::MultipleFiles));
+    }
+
+    #[test]
+    fn disc_formats() {
+        let cue = cue();
+        let toc = to_toc(&cue).unwrap();
+        let xmcd = to_xmcd(&cue).unwrap();
+
+        assert!(toc.starts_with("CD_DA\nCD_TEXT {\n  LANGUAGE_MAP {\n    0 : EN\n  }\n"));
+        assert!(toc.contains("TRACK AUDIO\nCD_TEXT {\n  LANGUAGE 0 {\n    TITLE \"One\"\n  }\n}\nFILE \"a.wav\" 00:00:00 02:58:00\n"));
+        assert!(toc.contains("TRACK AUDIO\nCOPY\n"));
+        assert!(toc.contains("FILE \"a.wav\" 02:58:00\nSTART 00:02:00\n"));
+
+        let late = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nINDEX 00 00:02:00\n",
+        )
+        .unwrap();
+
+        assert!(!to_toc(&late).unwrap().contains("START"));
+        assert!(xmcd.contains("#\t150\n#\t13650\n#\n# Disc length: 302 seconds\n"));
+        assert!(xmcd.contains("DISCID=0d012c02\nDTITLE=Artist / Album\nDYEAR=\nDGENRE=\n"));
+        assert!(xmcd.contains("TTITLE1=A <b> & c\n"));
+
+        let mut gapped = cue.clone();
+        gapped.tracks[1].pregap = Some(Frames::new(150));
+
+        assert!(to_xmcd(&gapped)
+            .unwrap()
+            .contains("#\t150\n#\t13800\n#\n# Disc length: 304 seconds\n"));
+
+        let mut dated = cue.clone();
+        dated.date = Some("1997-05-21".into());
+        dated.genre = Some("Alternative Rock".into());
+
+        assert!(to_xmcd(&dated)
+            .unwrap()
+            .contains("DYEAR=1997\nDGENRE=Alternative Rock\n"));
+    }
+
+    #[test]
+    fn pq_sheet() {
+        let mut cue = cue();
+        cue.catalog = Some(crate::Catalog::raw("0123456789012"));
+        cue.tracks[0].isrc = Some("USRC17607839".into());
+
+        let layout = DiscLayout::new(&cue, |_| None).unwrap();
+        let pq = to_pq_sheet(&cue, &layout);
+
+        assert!(pq.starts_with("UPC/EAN: 0123456789012\nTitle: Album\nPerformer: Artist\n\n"));
+        assert!(pq.contains(
+            "01     00     00:00:00  00:02:00  N     N\n01     01     00:02:00  02:58:00  N     N     USRC17607839\n"
+        ));
+        assert!(pq.contains(
+            "02     00     03:00:00  00:02:00  Y     N\n02     01     03:02:00  02:00:00  Y     N\n"
+        ));
+        assert!(pq.ends_with("Lead-out      05:02:00\n"));
+
+        let full = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nREM LEAD-OUT 99:59:74\n",
+        )
+        .unwrap();
+        let pq = to_pq_sheet(&full, &DiscLayout::new(&full, |_| None).unwrap());
+
+        assert!(pq.contains("01     01     00:02:00  99:59:74"));
+        assert!(pq.ends_with("Lead-out      100:01:74\n"));
+    }
+
+    #[test]
+    fn rekordbox_xml() {
+        let xml = to_rekordbox_xml(&cue(), Path::new("/music/Mix & more.wav")).unwrap();
+
+        assert!(xml.contains(
+            "<TRACK TrackID=\"1\" Name=\"Album\" Artist=\"Artist\" Location=\"file://localhost/music/Mix%20%26%20more.wav\" TotalTime=\"300\">\n"
+        ));
+        assert!(xml.contains(
+            "<POSITION_MARK Name=\"A &lt;b&gt; &amp; c (index 00)\" Type=\"0\" Start=\"178.000\" Num=\"-1\"/>\n"
+        ));
+        assert!(xml.contains(
+            "<POSITION_MARK Name=\"A &lt;b&gt; &amp; c\" Type=\"0\" Start=\"180.000\" Num=\"1\"/>\n"
+        ));
+    }
+}
@@ -0,0 +1,293 @@
+//! DDP 2.00 export, turning a cue-described premaster into a fileset a pressing plant accepts
+//!
+//! A fileset consists of the identifier (`DDPID`), the map of data streams (`DDPMS`), the audio
+//! image itself and the PQ description stream (`PQDESCR`) listing every track and index point.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{Cue, Frames, FramesError, Lba, Track, TrackFlags, TrackMode};
+
+pub const DDPID_NAME: &str = "DDPID";
+pub const DDPMS_NAME: &str = "DDPMS";
+pub const IMAGE_NAME: &str = "IMAGE.DAT";
+pub const PQ_DESCR_NAME: &str = "PQDESCR";
+
+const SECTOR_SIZE: u64 = 2352;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DdpError {
+    #[error("DDP export needs a single FILE holding the whole image")]
+    MultipleFiles,
+    #[error("track {0} is not an audio track")]
+    NotAudio(u8),
+    #[error("track {0} has no INDEX 01")]
+    MissingIndex(u8),
+    #[error("image length {0} is not a whole number of sectors")]
+    PartialSector(u64),
+    #[error("image is too long for a CD: {0}")]
+    TooLong(#[from] FramesError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// The descriptor files of a DDP fileset, alongside the audio image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ddp {
+    pub ddpid: Vec<u8>,
+    pub ddpms: Vec<u8>,
+    pub pq_descr: Vec<u8>,
+}
+
+impl Ddp {
+    /// Builds the descriptors for an image of `image_len` bytes of raw 16-bit little-endian stereo
+    /// PCM, laid out as described by `cue`
+    pub fn new(cue: &Cue, image_len: u64) -> Result<Self, DdpError> {
+        let mut files = cue.tracks.iter().filter_map(|track| track.file.as_ref());
+
+        if let Some(first) = files.next() {
+            if files.any(|file| file != first) {
+                return Err(DdpError::MultipleFiles);
+            }
+        }
+
+        if let Some(track) = cue.tracks.iter().find(|t| t.mode != TrackMode::Audio) {
+            return Err(DdpError::NotAudio(track.track_index));
+        }
+
+        if !image_len.is_multiple_of(SECTOR_SIZE) {
+            return Err(DdpError::PartialSector(image_len));
+        }
+
+        let sectors = image_len / SECTOR_SIZE;
+        let upc = cue.catalog.as_deref().unwrap_or_default();
+
+        Ok(Self {
+            ddpid: ddpid(upc),
+            ddpms: ddpms(sectors),
+            pq_descr: pq_descr(cue, upc, sectors)?,
+        })
+    }
+
+    /// Writes the descriptors and a copy of `image` to `dir`, completing the fileset
+    pub fn write_fileset(&self, mut image: impl Read, dir: &Path) -> Result<(), DdpError> {
+        std::fs::write(dir.join(DDPID_NAME), &self.ddpid)?;
+        std::fs::write(dir.join(DDPMS_NAME), &self.ddpms)?;
+        std::fs::write(dir.join(PQ_DESCR_NAME), &self.pq_descr)?;
+
+        let mut out = BufWriter::new(File::create(dir.join(IMAGE_NAME))?);
+
+        io::copy(&mut image, &mut out)?;
+        out.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Appends `value` left-aligned in a space-padded field of `len` bytes, truncating if needed
+fn field(buf: &mut Vec<u8>, value: &str, len: usize) {
+    let value = &value.as_bytes()[..value.len().min(len)];
+
+    buf.extend_from_slice(value);
+    buf.resize(buf.len() + len - value.len(), b' ');
+}
+
+/// Appends `value` as a zero-padded decimal field of `len` bytes
+fn number(buf: &mut Vec<u8>, value: u64, len: usize) {
+    field(buf, &format!("{value:0len$}"), len);
+}
+
+fn ddpid(upc: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(128);
+
+    field(&mut buf, "DDP 2.00", 8); // level
+    field(&mut buf, upc, 13);
+    field(&mut buf, "", 8); // map stream start, unused for single-layer CDs
+    field(&mut buf, "", 1);
+    field(&mut buf, "", 8); // master ID length
+    field(&mut buf, "", 1); // media number
+    field(&mut buf, "", 48); // master ID
+    field(&mut buf, "", 1); // book type
+    field(&mut buf, "CD", 2);
+    number(&mut buf, 1, 1); // sides
+    number(&mut buf, 0, 1); // side
+    number(&mut buf, 1, 1); // layers
+    number(&mut buf, 0, 1); // layer
+    field(&mut buf, "", 1); // direction
+    field(&mut buf, "", 28); // user text
+    field(&mut buf, "", 5);
+
+    buf
+}
+
+/// One 128 byte map packet describing a stream of the fileset
+struct MapPacket<'a> {
+    stream_type: &'a str,
+    length: u64,
+    start: u64,
+    subcode: &'a str,
+    mode: &'a str,
+    name: &'a str,
+}
+
+impl MapPacket<'_> {
+    fn write(&self, buf: &mut Vec<u8>) {
+        field(buf, "VVVM", 4);
+        field(buf, self.stream_type, 2);
+        number(buf, 0, 8); // pointer
+        number(buf, self.length, 8);
+        number(buf, self.start, 8);
+        field(buf, self.subcode, 8);
+        field(buf, self.mode, 2);
+        number(buf, 0, 1); // source storage mode
+        number(buf, 0, 1); // not scrambled
+        field(buf, "", 4); // pregaps and postgap are given in the PQ stream
+        field(buf, "", 4);
+        field(buf, "", 4);
+        field(buf, "", 1); // media number
+        field(buf, "", 2); // track
+        field(buf, "", 2); // index
+        field(buf, "", 12); // ISRC
+        field(buf, "", 3); // size
+        field(buf, self.name, 17);
+        field(buf, "", 37);
+    }
+}
+
+fn ddpms(sectors: u64) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(256);
+
+    MapPacket {
+        stream_type: "D0",
+        length: sectors,
+        start: u64::from(Lba::LEAD_IN),
+        subcode: "",
+        mode: "DA",
+        name: IMAGE_NAME,
+    }
+    .write(&mut buf);
+
+    MapPacket {
+        stream_type: "S0",
+        length: 0,
+        start: 0,
+        subcode: "PQ DESCR",
+        mode: "",
+        name: PQ_DESCR_NAME,
+    }
+    .write(&mut buf);
+
+    buf
+}
+
+/// Q-channel control nibble and ADR mode 1, as one hex byte
+fn control(track: &Track) -> String {
+    let mut control = 0;
+
+    if track.flags.contains(TrackFlags::PRE_EMPHASIS_ENABLED) {
+        control |= 0x1;
+    }
+
+    if track.flags.contains(TrackFlags::DIGITAL_COPY_PERMITTED) {
+        control |= 0x2;
+    }
+
+    if track.flags.contains(TrackFlags::FOUR_CHANNEL) {
+        control |= 0x8;
+    }
+
+    format!("{:02X}", control << 4 | 1)
+}
+
+/// Appends one 64 byte PQ descriptor packet for an index point at `time` on the disc
+fn pq_entry(
+    buf: &mut Vec<u8>,
+    track: &str,
+    index: usize,
+    time: Frames,
+    control: &str,
+    isrc: &str,
+    upc: &str,
+) {
+    let (m, s, f) = time.to_msf();
+
+    field(buf, "VVVS", 4);
+    field(buf, track, 2);
+    number(buf, index as u64, 2);
+    number(buf, (m / 60).into(), 2);
+    number(buf, (m % 60).into(), 2);
+    number(buf, s.into(), 2);
+    number(buf, f.into(), 2);
+    field(buf, control, 2);
+    field(buf, isrc, 12);
+    field(buf, upc, 13);
+    field(buf, "", 21);
+}
+
+fn pq_descr(cue: &Cue, upc: &str, sectors: u64) -> Result<Vec<u8>, DdpError> {
+    let mut buf = Vec::new();
+
+    for (i, track) in cue.tracks.iter().enumerate() {
+        let number = format!("{:02}", track.track_index);
+        let control = control(track);
+        let isrc = track.isrc.as_deref().unwrap_or_default();
+
+        if track.start().is_none() {
+            return Err(DdpError::MissingIndex(track.track_index));
+        }
+
+        // the disc's initial pregap is always index 0 of the first track
+        if i == 0 && track.index(0).is_none() {
+            pq_entry(&mut buf, &number, 0, Frames::ZERO, &control, "", upc);
+        }
+
+        for index in &track.indices {
+            let Some(time) = index.time() else { continue };
+            let isrc = if index.index() == 1 { isrc } else { "" };
+            let upc = if i == 0 { upc } else { "" };
+            let time = if i == 0 && index.index() == 0 {
+                Frames::ZERO
+            } else {
+                Frames::try_from(u64::from(time) + u64::from(Lba::LEAD_IN))?
+            };
+
+            pq_entry(&mut buf, &number, index.index(), time, &control, isrc, upc);
+        }
+    }
+
+    let leadout = Frames::try_from(sectors + u64::from(Lba::LEAD_IN))?;
+
+    pq_entry(&mut buf, "AA", 1, leadout, "01", "", "");
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn descriptors() {
+        let cue = Cue::from_str(
+            "CATALOG 0123456789012\nFILE \"premaster.bin\" BINARY\nTRACK 01 AUDIO\nISRC USABC2200001\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nFLAGS DCP\nINDEX 00 02:58:00\nINDEX 01 03:00:00\n",
+        )
+        .unwrap();
+
+        let ddp = Ddp::new(&cue, SECTOR_SIZE * 75 * 60 * 5).unwrap();
+
+        assert_eq!(ddp.ddpid.len(), 128);
+        assert_eq!(&ddp.ddpid[..21], b"DDP 2.000123456789012");
+        assert_eq!(ddp.ddpms.len(), 256);
+
+        let entries: Vec<&[u8]> = ddp.pq_descr.chunks(64).collect();
+
+        assert_eq!(entries.len(), 5);
+        assert_eq!(&entries[0][..18], b"VVVS01000000000001");
+        assert_eq!(&entries[1][..30], b"VVVS01010000020001USABC2200001");
+        assert_eq!(&entries[2][..18], b"VVVS02000003000021");
+        assert_eq!(&entries[4][..18], b"VVVSAA010005020001");
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt::Write;
+
+use crate::{Error, Location, ParseError, Warning};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// An error or warning in a form that's the same for both, for reporting to other tools
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The stable code of the problem, see [`Error::code`]
+    pub code: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// Where in the input the problem is, if it's in the input at all
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    /// Writes the diagnostic as a JSON object, in a layout that's kept stable across versions:
+    ///
+    /// ```json
+    /// {"code":"CUE003","severity":"error","message":"...","span":{"start":38,"end":38,"line":3,"column":10}}
+    /// ```
+    ///
+    /// `span` is `null` when there's no location. Offsets are in bytes, and `line` and `column`
+    /// count from 1, with `column` in characters.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        self.write_json(&mut json);
+
+        json
+    }
+
+    /// Writes diagnostics as a JSON array of the objects written by [`Diagnostic::to_json`]
+    pub fn to_json_array(diagnostics: &[Self]) -> String {
+        let mut json = String::from("[");
+
+        for (i, diagnostic) in diagnostics.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+
+            diagnostic.write_json(&mut json);
+        }
+
+        json.push(']');
+        json
+    }
+
+    fn write_json(&self, json: &mut String) {
+        json.push_str("{\"code\":");
+        write_string(json, self.code);
+        json.push_str(",\"severity\":");
+        write_string(json, self.severity.as_str());
+        json.push_str(",\"message\":");
+        write_string(json, &self.message);
+        json.push_str(",\"span\":");
+
+        match self.location {
+            Some(Location {
+                start,
+                end,
+                line,
+                column,
+            }) => {
+                let _ = write!(
+                    json,
+                    "{{\"start\":{start},\"end\":{end},\"line\":{line},\"column\":{column}}}"
+                );
+            }
+            None => json.push_str("null"),
+        }
+
+        json.push('}');
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(e: &ParseError) -> Self {
+        Self {
+            code: e.code(),
+            severity: Severity::Error,
+            message: e.message().to_string(),
+            location: Some(e.location()),
+        }
+    }
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(e: &Error) -> Self {
+        match e {
+            Error::Parse(e) | Error::Partial { cause: e, .. } => e.into(),
+            Error::Io(_) => Self {
+                code: e.code(),
+                severity: Severity::Error,
+                message: e.to_string(),
+                location: None,
+            },
+        }
+    }
+}
+
+impl From<&Warning> for Diagnostic {
+    fn from(warning: &Warning) -> Self {
+        Self {
+            code: warning.code(),
+            severity: Severity::Warning,
+            message: warning.message.clone(),
+            location: Some(warning.location),
+        }
+    }
+}
+
+fn write_string(json: &mut String, value: &str) {
+    json.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cue, ParseOptions};
+
+    #[test]
+    fn json_layout() {
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        let outcome = Cue::from_str_with_warnings("\u{FEFF}FILE \"a\" WAVE\n", &options).unwrap();
+        let e = Cue::from_str("FILE \"a\" WAVE\nTRACK 01 AUDIO\nINDEX 01 1:2\n").unwrap_err();
+
+        let diagnostics = [Diagnostic::from(&outcome.warnings[0]), Diagnostic::from(&e)];
+
+        assert_eq!(
+            Diagnostic::to_json_array(&diagnostics),
+            concat!(
+                r#"[{"code":"CUE101","severity":"warning","message":"skipped a byte order mark","span":{"start":0,"end":3,"line":1,"column":1}},"#,
+                r#"{"code":"CUE003","severity":"error","message":"Expected MM:SS:FF, HH:MM:SS:FF or a number of frames","span":{"start":38,"end":41,"line":3,"column":10}}]"#
+            )
+        );
+
+        let mut quoted = String::new();
+        write_string(&mut quoted, "a \"b\"\\\n\u{1}");
+
+        assert_eq!(quoted, r#""a \"b\"\\\n\u0001""#);
+    }
+}
@@ -0,0 +1,455 @@
+use std::{fmt, path::Path};
+
+use crate::{Cue, CueField, FileFormat, Frames, ReplayGain, Track, TrackField, TrackFlags};
+
+/// A difference between two cues, found by [`Cue::diff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CueChange {
+    /// A track with this number is only in the new cue
+    TrackAdded(u8),
+    /// A track with this number is only in the old cue
+    TrackRemoved(u8),
+    /// A field has a different value, written as it would be in a cue. Index times are
+    /// [`CueChange::IndexMoved`] instead.
+    Changed {
+        field: CueField,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// An `INDEX` of a track starts at a different time
+    IndexMoved {
+        track: u8,
+        index: usize,
+        old: Frames,
+        new: Frames,
+    },
+}
+
+impl CueChange {
+    /// How many frames an index moved by, negative when it's earlier in the new cue
+    pub fn moved_by(&self) -> Option<i64> {
+        match self {
+            Self::IndexMoved { old, new, .. } => Some(i64::from(new.0) - i64::from(old.0)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CueChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TrackAdded(track) => write!(f, "track {track:02} added"),
+            Self::TrackRemoved(track) => write!(f, "track {track:02} removed"),
+            Self::Changed { field, old, new } => {
+                let name = match field {
+                    CueField::Track(track, field) => {
+                        write!(f, "track {track:02}: ")?;
+                        track_field_name(*field)
+                    }
+                    field => cue_field_name(*field),
+                };
+                let value = |value: &Option<String>| match value {
+                    Some(value) => format!("{value:?}"),
+                    None => "nothing".into(),
+                };
+
+                match field {
+                    CueField::Comments | CueField::Track(_, TrackField::Comments) => {
+                        write!(f, "{name} changed")
+                    }
+                    _ => write!(f, "{name} changed from {} to {}", value(old), value(new)),
+                }
+            }
+            Self::IndexMoved {
+                track,
+                index,
+                old,
+                new,
+            } => write!(
+                f,
+                "track {track:02}: INDEX {index:02} moved by {:+} frames, from {} to {}",
+                self.moved_by().unwrap_or_default(),
+                msf(*old),
+                msf(*new)
+            ),
+        }
+    }
+}
+
+/// Options controlling which differences [`Cue::diff_with`] reports
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptions {
+    /// Leave out changes to `REM` comments
+    pub ignore_comments: bool,
+    /// Treat text that differs only in whitespace, and paths that differ only in using `\`
+    /// rather than `/`, as the same
+    pub ignore_formatting: bool,
+}
+
+impl Cue {
+    /// Compares the fields of two cues, matching tracks by number. Changes to the global fields
+    /// come first, then each track's in order.
+    pub fn diff(&self, other: &Cue) -> Vec<CueChange> {
+        self.diff_with(other, &DiffOptions::default())
+    }
+
+    /// Like [`Cue::diff`], leaving out the kinds of changes `options` ignores
+    pub fn diff_with(&self, other: &Cue, options: &DiffOptions) -> Vec<CueChange> {
+        let mut changes = Vec::new();
+        let mut change = |field, old: Option<String>, new: Option<String>| {
+            if differs(options, field, &old, &new) {
+                changes.push(CueChange::Changed { field, old, new });
+            }
+        };
+
+        change(
+            CueField::Catalog,
+            self.catalog.as_deref().map(Into::into),
+            other.catalog.as_deref().map(Into::into),
+        );
+        change(
+            CueField::CdTextFile,
+            path(self.cd_text_file.as_deref()),
+            path(other.cd_text_file.as_deref()),
+        );
+        change(
+            CueField::File,
+            file(self.path.as_deref(), &self.format),
+            file(other.path.as_deref(), &other.format),
+        );
+        change(
+            CueField::Performer,
+            self.performer.as_deref().map(Into::into),
+            other.performer.as_deref().map(Into::into),
+        );
+        change(
+            CueField::Songwriter,
+            self.songwriter.as_deref().map(Into::into),
+            other.songwriter.as_deref().map(Into::into),
+        );
+        change(
+            CueField::Arranger,
+            self.arranger.as_deref().map(Into::into),
+            other.arranger.as_deref().map(Into::into),
+        );
+        change(CueField::Title, self.title.clone(), other.title.clone());
+        change(
+            CueField::Comments,
+            lines(&self.comments),
+            lines(&other.comments),
+        );
+        change(
+            CueField::Leadout,
+            self.leadout.map(msf),
+            other.leadout.map(msf),
+        );
+        change(CueField::Genre, self.genre.clone(), other.genre.clone());
+        change(CueField::Date, self.date.clone(), other.date.clone());
+        change(
+            CueField::DiscNumber,
+            self.disc_number.map(|disc| disc.to_string()),
+            other.disc_number.map(|disc| disc.to_string()),
+        );
+        change(
+            CueField::TotalDiscs,
+            self.total_discs.map(|discs| discs.to_string()),
+            other.total_discs.map(|discs| discs.to_string()),
+        );
+        change(
+            CueField::ReplayGain,
+            self.replay_gain.map(replay_gain),
+            other.replay_gain.map(replay_gain),
+        );
+
+        for track in &self.tracks {
+            match other.track(track.track_index) {
+                Some(new) => diff_track(track, new, options, &mut changes),
+                None => changes.push(CueChange::TrackRemoved(track.track_index)),
+            }
+        }
+
+        for track in &other.tracks {
+            if self.track(track.track_index).is_none() {
+                changes.push(CueChange::TrackAdded(track.track_index));
+            }
+        }
+
+        changes
+    }
+}
+
+fn diff_track(old: &Track, new: &Track, options: &DiffOptions, changes: &mut Vec<CueChange>) {
+    let number = old.track_index;
+    let mut change = |field, old: Option<String>, new: Option<String>| {
+        let field = CueField::Track(number, field);
+
+        if differs(options, field, &old, &new) {
+            changes.push(CueChange::Changed { field, old, new });
+        }
+    };
+
+    change(
+        TrackField::Mode,
+        Some(old.mode.to_string()),
+        Some(new.mode.to_string()),
+    );
+    change(TrackField::Flags, flags(old.flags), flags(new.flags));
+    change(
+        TrackField::File,
+        file(old.file.as_deref(), &old.format),
+        file(new.file.as_deref(), &new.format),
+    );
+    change(
+        TrackField::Performer,
+        old.performer.as_deref().map(Into::into),
+        new.performer.as_deref().map(Into::into),
+    );
+    change(
+        TrackField::Songwriter,
+        old.songwriter.as_deref().map(Into::into),
+        new.songwriter.as_deref().map(Into::into),
+    );
+    change(
+        TrackField::Arranger,
+        old.arranger.as_deref().map(Into::into),
+        new.arranger.as_deref().map(Into::into),
+    );
+    change(TrackField::Title, old.title.clone(), new.title.clone());
+    change(TrackField::Isrc, old.isrc.clone(), new.isrc.clone());
+    change(TrackField::Pregap, old.pregap.map(msf), new.pregap.map(msf));
+    change(
+        TrackField::Postgap,
+        old.postgap.map(msf),
+        new.postgap.map(msf),
+    );
+    change(
+        TrackField::Comments,
+        lines(&old.comments),
+        lines(&new.comments),
+    );
+    change(
+        TrackField::DiscNumber,
+        old.disc_number.map(|disc| disc.to_string()),
+        new.disc_number.map(|disc| disc.to_string()),
+    );
+    change(
+        TrackField::ReplayGain,
+        old.replay_gain.map(replay_gain),
+        new.replay_gain.map(replay_gain),
+    );
+
+    let numbers =
+        |track: &Track| -> Vec<usize> { track.indices.iter().map(|index| index.index()).collect() };
+
+    // Moving an index is only meaningful when both cues have the same ones, with times
+    let times = |track: &Track| -> Option<Vec<Frames>> {
+        track.indices.iter().map(|index| index.time()).collect()
+    };
+
+    match times(old).zip(times(new)) {
+        Some((old_times, new_times)) if numbers(old) == numbers(new) => {
+            for ((index, old), new) in numbers(old).into_iter().zip(old_times).zip(new_times) {
+                if old != new {
+                    changes.push(CueChange::IndexMoved {
+                        track: number,
+                        index,
+                        old,
+                        new,
+                    });
+                }
+            }
+        }
+        _ => change(TrackField::Indices, indices(old), indices(new)),
+    }
+}
+
+fn differs(
+    options: &DiffOptions,
+    field: CueField,
+    old: &Option<String>,
+    new: &Option<String>,
+) -> bool {
+    match field {
+        CueField::Comments | CueField::Track(_, TrackField::Comments)
+            if options.ignore_comments =>
+        {
+            false
+        }
+        _ if options.ignore_formatting => {
+            let is_path = matches!(
+                field,
+                CueField::File | CueField::CdTextFile | CueField::Track(_, TrackField::File)
+            );
+            let clean = |value: &Option<String>| {
+                value.as_deref().map(|value| {
+                    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+
+                    if is_path {
+                        value.replace('\\', "/")
+                    } else {
+                        value
+                    }
+                })
+            };
+
+            clean(old) != clean(new)
+        }
+        _ => old != new,
+    }
+}
+
+fn cue_field_name(field: CueField) -> &'static str {
+    match field {
+        CueField::Catalog => "CATALOG",
+        CueField::CdTextFile => "CDTEXTFILE",
+        CueField::File => "FILE",
+        CueField::Performer => "PERFORMER",
+        CueField::Songwriter => "SONGWRITER",
+        CueField::Arranger => "ARRANGER",
+        CueField::Title => "TITLE",
+        CueField::Comments => "comments",
+        CueField::Leadout => "lead-out",
+        CueField::Genre => "genre",
+        CueField::Date => "date",
+        CueField::DiscNumber => "disc number",
+        CueField::TotalDiscs => "total discs",
+        CueField::ReplayGain => "ReplayGain",
+        CueField::Track(_, field) => track_field_name(field),
+    }
+}
+
+fn track_field_name(field: TrackField) -> &'static str {
+    match field {
+        TrackField::Mode => "mode",
+        TrackField::Flags => "FLAGS",
+        TrackField::File => "FILE",
+        TrackField::Performer => "PERFORMER",
+        TrackField::Songwriter => "SONGWRITER",
+        TrackField::Arranger => "ARRANGER",
+        TrackField::Title => "TITLE",
+        TrackField::Isrc => "ISRC",
+        TrackField::Pregap => "PREGAP",
+        TrackField::Postgap => "POSTGAP",
+        TrackField::Indices => "indices",
+        TrackField::Comments => "comments",
+        TrackField::DiscNumber => "disc number",
+        TrackField::ReplayGain => "ReplayGain",
+    }
+}
+
+fn replay_gain(replay_gain: ReplayGain) -> String {
+    match replay_gain.peak {
+        Some(peak) => format!("{:+} dB, peak {peak}", replay_gain.gain),
+        None => format!("{:+} dB", replay_gain.gain),
+    }
+}
+
+fn flags(flags: TrackFlags) -> Option<String> {
+    (!flags.is_empty()).then(|| flags.to_string())
+}
+
+fn file(path: Option<&Path>, format: &FileFormat) -> Option<String> {
+    let path = path?.display();
+
+    Some(match format.as_str() {
+        Some(format) => format!("{path} {format}"),
+        None => path.to_string(),
+    })
+}
+
+fn path(path: Option<&Path>) -> Option<String> {
+    path.map(|path| path.display().to_string())
+}
+
+fn lines(lines: &[String]) -> Option<String> {
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+fn indices(track: &Track) -> Option<String> {
+    let indices: Vec<_> = track
+        .indices
+        .iter()
+        .map(|index| match index.time() {
+            Some(time) => format!("{:02} {}", index.index(), msf(time)),
+            None => format!("{:02}", index.index()),
+        })
+        .collect();
+
+    (!indices.is_empty()).then(|| indices.join(", "))
+}
+
+fn msf(frames: Frames) -> String {
+    let (m, s, f) = frames.to_msf();
+
+    format!("{m:02}:{s:02}:{f:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_changes() {
+        let old = Cue::from_str(
+            "TITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One\"\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+        let new = Cue::from_str(
+            "TITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"Uno\"\nINDEX 01 00:00:00\nTRACK 03 AUDIO\nINDEX 01 02:00:00\n",
+        )
+        .unwrap();
+
+        let changes: Vec<_> = old.diff(&new).iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            changes,
+            [
+                "track 01: TITLE changed from \"One\" to \"Uno\"",
+                "track 02 removed",
+                "track 03 added"
+            ]
+        );
+
+        let moved = Cue::from_str(
+            "TITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One\"\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 00:59:74\n",
+        )
+        .unwrap();
+        let changes = old.diff(&moved);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].moved_by(), Some(-1));
+        assert_eq!(
+            changes[0].to_string(),
+            "track 02: INDEX 01 moved by -1 frames, from 01:00:00 to 00:59:74"
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn ignored_changes() {
+        let old = Cue::from_str(
+            "REM COMMENT \"ExactAudioCopy\"\nFILE \"CD1\\a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One  Two\"\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        let new = Cue::from_str(
+            "FILE \"CD1/a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One Two \"\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        let options = DiffOptions {
+            ignore_comments: true,
+            ignore_formatting: true,
+        };
+
+        assert_eq!(old.diff(&new).len(), 4);
+        assert!(old.diff_with(&new, &options).is_empty());
+
+        let mut renamed = new.clone();
+        renamed.tracks[0].title = Some("AC\\DC".into());
+        let mut slashed = new.clone();
+        slashed.tracks[0].title = Some("AC/DC".into());
+
+        assert_eq!(renamed.diff_with(&slashed, &options).len(), 1);
+    }
+}
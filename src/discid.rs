@@ -0,0 +1,290 @@
+//! Identifiers of a disc computed from its table of contents, for looking it up in online
+//! databases
+//!
+//! - [`DiscToc::cddb`]: the CDDB/freedb disc ID
+//! - [`DiscToc::accuraterip`]: the AccurateRip disc ID, as used in its database's file names
+//! - [`DiscToc::musicbrainz`]: the MusicBrainz disc ID, with the `hash` feature
+//! - [`DiscToc::ctdb`]: the CUETools database TOC ID, with the `hash` feature
+
+use std::path::Path;
+
+use crate::{convert::ConvertError, Cue, DiscLayout, Frames, Lba, OffsetOrigin};
+
+/// Where each track of a disc starts, in frames from the start of the disc including the lead-in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscToc {
+    pub first_track: u8,
+    /// The `INDEX 01` of each track, in order
+    pub offsets: Vec<u32>,
+    pub leadout: u32,
+}
+
+impl DiscToc {
+    /// The TOC of a cue with every track in a single `FILE`, as an image of the whole disc, and
+    /// a known lead-out
+    pub fn from_cue(cue: &Cue) -> Result<Self, ConvertError> {
+        if cue.tracks_by_file().nth(1).is_some() {
+            return Err(ConvertError::MultipleFiles);
+        }
+
+        Ok(Self::from_layout(&DiscLayout::new(cue, |_| None)?))
+    }
+
+    /// The TOC of a cue with tracks in several files, such as one per track, laid end to end.
+    /// `file_length` gives the length of each file, from a map or by probing the audio, and the
+    /// lead-out is the end of the last one.
+    pub fn from_files(
+        cue: &Cue,
+        file_length: impl FnMut(&Path) -> Option<Frames>,
+    ) -> Result<Self, ConvertError> {
+        Ok(Self::from_layout(&DiscLayout::new(cue, file_length)?))
+    }
+
+    /// The TOC of every track of a [`DiscLayout`], in all its sessions
+    pub fn from_layout(layout: &DiscLayout) -> Self {
+        Self {
+            first_track: layout.tracks().next().map_or(1, |track| track.number),
+            offsets: layout.track_offsets(OffsetOrigin::DiscStart),
+            leadout: layout.leadout_offset(OffsetOrigin::DiscStart),
+        }
+    }
+
+    pub fn cddb(&self) -> u32 {
+        cddb_id(&self.offsets, self.leadout / 75)
+    }
+
+    /// The AccurateRip disc ID, as in `dBAR-006-000513be-001b2231-3404f606`, the name of the
+    /// disc's file in the database
+    pub fn accuraterip(&self) -> String {
+        let offsets = self.offsets.iter().map(|offset| offset - Lba::LEAD_IN);
+        let leadout = self.leadout - Lba::LEAD_IN;
+        let count = self.offsets.len() as u32;
+
+        let id1 = offsets.clone().fold(leadout, u32::wrapping_add);
+        let id2 = offsets
+            .zip(1..)
+            .fold(leadout.wrapping_mul(count + 1), |sum, (offset, number)| {
+                sum.wrapping_add(offset.max(1).wrapping_mul(number))
+            });
+
+        format!("dBAR-{count:03}-{id1:08x}-{id2:08x}-{:08x}", self.cddb())
+    }
+
+    #[cfg(feature = "hash")]
+    pub fn musicbrainz(&self) -> String {
+        use std::fmt::Write;
+
+        let mut toc = format!(
+            "{:02X}{:02X}{:08X}",
+            self.first_track,
+            usize::from(self.first_track) + self.offsets.len().saturating_sub(1),
+            self.leadout
+        );
+
+        for number in 1..=99u8 {
+            let offset = usize::from(number)
+                .checked_sub(self.first_track.into())
+                .and_then(|i| self.offsets.get(i));
+            let _ = write!(toc, "{:08X}", offset.copied().unwrap_or(0));
+        }
+
+        musicbrainz_base64(&toc)
+    }
+
+    /// The TOC ID of the CUETools database, from the offsets of the tracks after the first
+    /// relative to it
+    #[cfg(feature = "hash")]
+    pub fn ctdb(&self) -> String {
+        use std::fmt::Write;
+
+        let first = self.offsets.first().copied().unwrap_or(Lba::LEAD_IN);
+        let mut toc = String::new();
+
+        for offset in self.offsets.iter().skip(1).chain([&self.leadout]) {
+            let _ = write!(toc, "{:08X}", offset.saturating_sub(first));
+        }
+
+        toc.extend(std::iter::repeat_n(
+            '0',
+            100usize.saturating_sub(self.offsets.len()) * 8,
+        ));
+
+        musicbrainz_base64(&toc)
+    }
+}
+
+impl Cue {
+    /// Where the `INDEX 01` of each track is on the disc, laying its files end to end given the
+    /// length of each from `file_length`. Only the lengths of the files before the last are
+    /// needed. They count from LBA 0, so leave out the 2 second pregap before track 1; a
+    /// [`DiscLayout`] gives offsets that include it.
+    pub fn absolute_starts(
+        &self,
+        mut file_length: impl FnMut(&Path) -> Option<Frames>,
+    ) -> Result<Vec<Frames>, ConvertError> {
+        let files: Vec<_> = self.tracks_by_file().collect();
+        let mut offset = 0;
+        let mut starts = Vec::with_capacity(self.tracks.len());
+
+        for (i, &(path, tracks)) in files.iter().enumerate() {
+            for track in tracks {
+                let start = track
+                    .start()
+                    .ok_or(ConvertError::MissingIndex(track.track_index))?;
+
+                let start = Frames::try_from(offset + start.frames())
+                    .map_err(|_| ConvertError::TooLong(track.track_index))?;
+
+                starts.push(start);
+            }
+
+            if i + 1 < files.len() {
+                offset += length_of(path, &mut file_length)?;
+            }
+        }
+
+        Ok(starts)
+    }
+}
+
+fn length_of(
+    path: Option<&Path>,
+    file_length: &mut impl FnMut(&Path) -> Option<Frames>,
+) -> Result<u32, ConvertError> {
+    path.and_then(&mut *file_length)
+        .map(Frames::frames)
+        .ok_or_else(|| ConvertError::UnknownFileLength(path.unwrap_or(Path::new("")).into()))
+}
+
+/// The CDDB disc ID, from the offsets of the tracks including the lead-in, and the length of the
+/// disc in seconds
+pub(crate) fn cddb_id(offsets: &[u32], length: u32) -> u32 {
+    fn digit_sum(mut n: u32) -> u32 {
+        let mut sum = 0;
+
+        while n > 0 {
+            sum += n % 10;
+            n /= 10;
+        }
+
+        sum
+    }
+
+    let checksum: u32 = offsets.iter().map(|offset| digit_sum(offset / 75)).sum();
+    let first = offsets.first().map_or(0, |offset| offset / 75);
+
+    (checksum % 255) << 24 | length.saturating_sub(first) << 8 | offsets.len() as u32
+}
+
+/// SHA-1 of the text in base64, with the characters that aren't safe in URLs replaced as
+/// MusicBrainz does
+#[cfg(feature = "hash")]
+fn musicbrainz_base64(text: &str) -> String {
+    use sha1::{Digest, Sha1};
+
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789._";
+
+    let hash = Sha1::digest(text.as_bytes());
+    let mut out = String::with_capacity(28);
+
+    for chunk in hash.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize].into());
+        }
+    }
+
+    while !out.len().is_multiple_of(4) {
+        out.push('-');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disc_ids() {
+        // the example disc in MusicBrainz's documentation
+        let cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 03:22:63\nTRACK 03 AUDIO\nINDEX 01 07:08:64\nTRACK 04 AUDIO\nINDEX 01 10:19:17\nTRACK 05 AUDIO\nINDEX 01 14:03:39\nTRACK 06 AUDIO\nINDEX 01 17:51:14\nREM LEAD-OUT 21:10:62\n",
+        )
+        .unwrap();
+        let toc = DiscToc::from_cue(&cue).unwrap();
+
+        assert_eq!(toc.leadout, 95462);
+        assert_eq!(toc.accuraterip(), "dBAR-006-000513be-001b2231-3404f606");
+
+        #[cfg(feature = "hash")]
+        {
+            assert_eq!(toc.musicbrainz(), "49HHV7Eb8UKF3aQiNmu1GR8vKTY-");
+            assert_eq!(toc.ctdb(), "iCHDkr.7dpqDbPy3ehdjqp8oRT0-");
+        }
+    }
+
+    #[test]
+    fn multiple_files() {
+        let cue = Cue::from_str(
+            "FILE \"01.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 00 03:00:00\nFILE \"02.wav\" WAVE\nINDEX 01 00:00:00\nFILE \"03.wav\" WAVE\nTRACK 03 AUDIO\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        let lengths = [("01.wav", 13_575), ("02.wav", 22_500), ("03.wav", 15_000)]
+            .map(|(path, length)| (Path::new(path), Frames::new(length)));
+        let length = |path: &Path| {
+            lengths
+                .iter()
+                .find(|&&(known, _)| known == path)
+                .map(|&(_, length)| length)
+        };
+
+        assert_eq!(
+            cue.absolute_starts(length).unwrap(),
+            [Frames::new(0), Frames::new(13_575), Frames::new(36_075)]
+        );
+
+        let toc = DiscToc::from_files(&cue, length).unwrap();
+
+        assert_eq!(toc.offsets, [150, 13_725, 36_225]);
+        assert_eq!(toc.leadout, 51_225);
+        assert_eq!(
+            DiscToc::from_files(&cue, |path| length(path)
+                .filter(|_| path != Path::new("02.wav"))),
+            Err(ConvertError::UnknownFileLength("02.wav".into()))
+        );
+        assert_eq!(
+            cue.absolute_starts(|_| Some(Frames::from_msf(60, 0, 0).unwrap())),
+            Err(ConvertError::TooLong(3))
+        );
+    }
+
+    #[test]
+    fn rejects_tracks_that_cant_be_on_a_disc() {
+        let numbered = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 255 AUDIO\nINDEX 01 00:00:00\nTRACK 01 AUDIO\nINDEX 01 01:00:00\nREM LEAD-OUT 02:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            DiscToc::from_cue(&numbered),
+            Err(ConvertError::TrackNumberTooLarge(255))
+        );
+
+        let backwards = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 01:00:00\nTRACK 02 AUDIO\nINDEX 01 00:00:00\nREM LEAD-OUT 02:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            DiscToc::from_cue(&backwards),
+            Err(ConvertError::TrackBeforePrevious(2))
+        );
+    }
+}
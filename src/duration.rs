@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use crate::{Cue, Frames};
+
+/// How much of the disc a track takes, from [`Cue::track_durations`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackDuration {
+    pub track: u8,
+    /// From its `INDEX 01` to the next track's, or to the end of the disc
+    pub length: Frames,
+    /// The length as a percentage of the whole disc
+    pub percent: f64,
+}
+
+impl Cue {
+    /// The run time of the disc: the lead-out of a single file image if it's known, otherwise
+    /// the lengths of its files from `file_length` added up. `None` if one isn't known, or if
+    /// they add up to more than the longest time.
+    pub fn total_duration(
+        &self,
+        mut file_length: impl FnMut(&Path) -> Option<Frames>,
+    ) -> Option<Frames> {
+        if self.tracks_by_file().nth(1).is_none() {
+            if let Some(leadout) = self.leadout {
+                return Some(leadout);
+            }
+        }
+
+        self.tracks_by_file()
+            .map(|(path, _)| path.and_then(&mut file_length).map(Frames::frames))
+            .sum::<Option<u32>>()
+            .and_then(|total| Frames::try_from(total).ok())
+    }
+
+    /// The length of each track and its share of [`Cue::total_duration`], as shown by a player's
+    /// progress bar
+    pub fn track_durations(
+        &self,
+        mut file_length: impl FnMut(&Path) -> Option<Frames>,
+    ) -> Option<Vec<TrackDuration>> {
+        let total = self.total_duration(&mut file_length)?;
+        let starts = self.absolute_starts(&mut file_length).ok()?;
+        let ends = starts.iter().skip(1).chain([&total]);
+
+        self.tracks
+            .iter()
+            .zip(starts.iter().zip(ends))
+            .map(|(track, (start, end))| {
+                let length = Frames::new(end.frames().checked_sub(start.frames())?);
+                let percent = match total.frames() {
+                    0 => 0.0,
+                    total => f64::from(length.frames()) * 100.0 / f64::from(total),
+                };
+
+                Some(TrackDuration {
+                    track: track.track_index,
+                    length,
+                    percent,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_durations() {
+        let image = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 01:00:00\nREM LEAD-OUT 04:00:00\n",
+        )
+        .unwrap();
+        let durations = image.track_durations(|_| None).unwrap();
+
+        assert_eq!(image.total_duration(|_| None), Some(Frames::new(18_000)));
+        assert_eq!(durations[0].percent, 25.0);
+        assert_eq!(durations[1].length, Frames::new(13_500));
+
+        let files = Cue::from_str(
+            "FILE \"01.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nFILE \"02.wav\" WAVE\nTRACK 02 AUDIO\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(files.total_duration(|_| None), None);
+        let durations = files.track_durations(|_| Some(Frames::new(4_500))).unwrap();
+        assert_eq!(durations[1].percent, 50.0);
+        assert_eq!(
+            files.total_duration(|_| Some(Frames::from_msf(60, 0, 0).unwrap())),
+            None
+        );
+    }
+}
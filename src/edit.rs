@@ -0,0 +1,454 @@
+use std::{borrow::Cow, collections::BTreeSet, path::PathBuf, sync::Arc};
+
+use crate::{
+    Catalog, Cue, FileFormat, Frames, ReplayGain, Track, TrackFlags, TrackIndex, TrackMode,
+};
+
+/// A global field of a [`Cue`], or a field of one of its tracks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CueField {
+    Catalog,
+    CdTextFile,
+    /// The global `FILE`, along with its format
+    File,
+    Performer,
+    Songwriter,
+    Arranger,
+    Title,
+    Comments,
+    Leadout,
+    Genre,
+    Date,
+    DiscNumber,
+    TotalDiscs,
+    ReplayGain,
+    Track(u8, TrackField),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TrackField {
+    Mode,
+    Flags,
+    /// The track's `FILE`, along with its format
+    File,
+    Performer,
+    Songwriter,
+    Arranger,
+    Title,
+    Isrc,
+    Pregap,
+    Postgap,
+    Indices,
+    Comments,
+    DiscNumber,
+    ReplayGain,
+}
+
+/// Edits to a [`Cue`] that keep track of which fields differ from the original, so that only
+/// those need saving or highlighting. The original is only copied once the first edit is made.
+///
+/// Setting a field back to its original value clears its change.
+#[derive(Debug, Clone)]
+pub struct CueEdit<'a> {
+    original: &'a Cue,
+    edited: Cow<'a, Cue>,
+    changes: BTreeSet<CueField>,
+}
+
+impl<'a> CueEdit<'a> {
+    pub fn new(original: &'a Cue) -> Self {
+        Self {
+            original,
+            edited: Cow::Borrowed(original),
+            changes: BTreeSet::new(),
+        }
+    }
+
+    pub fn original(&self) -> &'a Cue {
+        self.original
+    }
+
+    /// The cue with every edit applied
+    pub fn cue(&self) -> &Cue {
+        &self.edited
+    }
+
+    pub fn into_cue(self) -> Cue {
+        self.edited.into_owned()
+    }
+
+    /// Whether any field differs from the original
+    pub fn is_modified(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    pub fn is_field_modified(&self, field: CueField) -> bool {
+        self.changes.contains(&field)
+    }
+
+    /// Fields that differ from the original, global fields first and then by track
+    pub fn changes(&self) -> impl Iterator<Item = CueField> + '_ {
+        self.changes.iter().copied()
+    }
+
+    /// Edits the track with the given number, if there is one
+    pub fn track(&mut self, track_index: u8) -> Option<TrackEdit<'_, 'a>> {
+        let position = self.original.track_position(track_index)?;
+
+        Some(TrackEdit {
+            edit: self,
+            position,
+        })
+    }
+
+    pub fn set_catalog(&mut self, catalog: Option<Catalog>) {
+        self.update(
+            CueField::Catalog,
+            |cue| cue.catalog = catalog,
+            |a, b| a.catalog == b.catalog,
+        );
+    }
+
+    pub fn set_cd_text_file(&mut self, path: Option<PathBuf>) {
+        self.update(
+            CueField::CdTextFile,
+            |cue| cue.cd_text_file = path,
+            |a, b| a.cd_text_file == b.cd_text_file,
+        );
+    }
+
+    pub fn set_file(&mut self, path: Option<PathBuf>, format: FileFormat) {
+        self.update(
+            CueField::File,
+            |cue| {
+                cue.path = path;
+                cue.format = format;
+            },
+            |a, b| a.path == b.path && a.format == b.format,
+        );
+    }
+
+    pub fn set_performer(&mut self, performer: Option<Arc<str>>) {
+        self.update(
+            CueField::Performer,
+            |cue| cue.performer = performer,
+            |a, b| a.performer == b.performer,
+        );
+    }
+
+    pub fn set_songwriter(&mut self, songwriter: Option<Arc<str>>) {
+        self.update(
+            CueField::Songwriter,
+            |cue| cue.songwriter = songwriter,
+            |a, b| a.songwriter == b.songwriter,
+        );
+    }
+
+    pub fn set_arranger(&mut self, arranger: Option<Arc<str>>) {
+        self.update(
+            CueField::Arranger,
+            |cue| cue.arranger = arranger,
+            |a, b| a.arranger == b.arranger,
+        );
+    }
+
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.update(
+            CueField::Title,
+            |cue| cue.title = title,
+            |a, b| a.title == b.title,
+        );
+    }
+
+    /// Replaces the global comments. Where the old ones were is forgotten, so the new ones are
+    /// written in the usual place, unless they're the original comments again.
+    pub fn set_comments(&mut self, comments: Vec<String>) {
+        let positions = if comments == self.original.comments {
+            self.original.comment_positions.clone()
+        } else {
+            Vec::new()
+        };
+
+        self.update(
+            CueField::Comments,
+            |cue| {
+                cue.comments = comments;
+                cue.comment_positions = positions;
+            },
+            |a, b| a.comments == b.comments,
+        );
+    }
+
+    pub fn set_leadout(&mut self, leadout: Option<Frames>) {
+        self.update(
+            CueField::Leadout,
+            |cue| cue.leadout = leadout,
+            |a, b| a.leadout == b.leadout,
+        );
+    }
+
+    pub fn set_genre(&mut self, genre: Option<String>) {
+        self.update(
+            CueField::Genre,
+            |cue| cue.genre = genre,
+            |a, b| a.genre == b.genre,
+        );
+    }
+
+    pub fn set_date(&mut self, date: Option<String>) {
+        self.update(
+            CueField::Date,
+            |cue| cue.date = date,
+            |a, b| a.date == b.date,
+        );
+    }
+
+    pub fn set_disc_number(&mut self, disc_number: Option<u8>) {
+        self.update(
+            CueField::DiscNumber,
+            |cue| cue.disc_number = disc_number,
+            |a, b| a.disc_number == b.disc_number,
+        );
+    }
+
+    pub fn set_total_discs(&mut self, total_discs: Option<u8>) {
+        self.update(
+            CueField::TotalDiscs,
+            |cue| cue.total_discs = total_discs,
+            |a, b| a.total_discs == b.total_discs,
+        );
+    }
+
+    pub fn set_replay_gain(&mut self, replay_gain: Option<ReplayGain>) {
+        self.update(
+            CueField::ReplayGain,
+            |cue| cue.replay_gain = replay_gain,
+            |a, b| a.replay_gain == b.replay_gain,
+        );
+    }
+
+    /// Applies `set` and records whether `field` now differs from the original, comparing only
+    /// that field with `same`
+    fn update(
+        &mut self,
+        field: CueField,
+        set: impl FnOnce(&mut Cue),
+        same: impl Fn(&Cue, &Cue) -> bool,
+    ) {
+        set(self.edited.to_mut());
+
+        if same(self.original, &self.edited) {
+            self.changes.remove(&field);
+        } else {
+            self.changes.insert(field);
+        }
+    }
+}
+
+/// Edits to one track of a [`CueEdit`]
+#[derive(Debug)]
+pub struct TrackEdit<'e, 'a> {
+    edit: &'e mut CueEdit<'a>,
+    /// Tracks can't be added or removed while editing, so this is the same in both cues
+    position: usize,
+}
+
+impl TrackEdit<'_, '_> {
+    pub fn track(&self) -> &Track {
+        &self.edit.edited.tracks[self.position]
+    }
+
+    pub fn set_mode(&mut self, mode: TrackMode) {
+        self.update(TrackField::Mode, |t| t.mode = mode, |a, b| a.mode == b.mode);
+    }
+
+    pub fn set_flags(&mut self, flags: TrackFlags) {
+        self.update(
+            TrackField::Flags,
+            |t| t.flags = flags,
+            |a, b| a.flags == b.flags,
+        );
+    }
+
+    pub fn set_file(&mut self, path: Option<PathBuf>, format: FileFormat) {
+        self.update(
+            TrackField::File,
+            |t| {
+                t.file = path;
+                t.format = format;
+            },
+            |a, b| a.file == b.file && a.format == b.format,
+        );
+    }
+
+    pub fn set_performer(&mut self, performer: Option<Arc<str>>) {
+        self.update(
+            TrackField::Performer,
+            |t| t.performer = performer,
+            |a, b| a.performer == b.performer,
+        );
+    }
+
+    pub fn set_songwriter(&mut self, songwriter: Option<Arc<str>>) {
+        self.update(
+            TrackField::Songwriter,
+            |t| t.songwriter = songwriter,
+            |a, b| a.songwriter == b.songwriter,
+        );
+    }
+
+    pub fn set_arranger(&mut self, arranger: Option<Arc<str>>) {
+        self.update(
+            TrackField::Arranger,
+            |t| t.arranger = arranger,
+            |a, b| a.arranger == b.arranger,
+        );
+    }
+
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.update(
+            TrackField::Title,
+            |t| t.title = title,
+            |a, b| a.title == b.title,
+        );
+    }
+
+    pub fn set_isrc(&mut self, isrc: Option<String>) {
+        self.update(TrackField::Isrc, |t| t.isrc = isrc, |a, b| a.isrc == b.isrc);
+    }
+
+    pub fn set_pregap(&mut self, pregap: Option<Frames>) {
+        self.update(
+            TrackField::Pregap,
+            |t| t.pregap = pregap,
+            |a, b| a.pregap == b.pregap,
+        );
+    }
+
+    pub fn set_postgap(&mut self, postgap: Option<Frames>) {
+        self.update(
+            TrackField::Postgap,
+            |t| t.postgap = postgap,
+            |a, b| a.postgap == b.postgap,
+        );
+    }
+
+    pub fn set_indices(&mut self, indices: impl Into<Vec<TrackIndex>>) {
+        let indices = indices.into();
+
+        self.update(
+            TrackField::Indices,
+            |t| t.set_indices(indices),
+            |a, b| a.indices == b.indices,
+        );
+    }
+
+    /// Replaces the track's comments, forgetting where the old ones were as
+    /// [`CueEdit::set_comments`] does
+    pub fn set_comments(&mut self, comments: impl Into<Vec<String>>) {
+        let comments = comments.into();
+        let original = &self.edit.original.tracks[self.position];
+        let positions = if comments == original.comments() {
+            original.comment_positions.clone()
+        } else {
+            Vec::new()
+        };
+
+        self.update(
+            TrackField::Comments,
+            |t| {
+                t.set_comments(comments);
+                t.comment_positions = positions;
+            },
+            |a, b| a.comments == b.comments,
+        );
+    }
+
+    pub fn set_disc_number(&mut self, disc_number: Option<u8>) {
+        self.update(
+            TrackField::DiscNumber,
+            |t| t.disc_number = disc_number,
+            |a, b| a.disc_number == b.disc_number,
+        );
+    }
+
+    pub fn set_replay_gain(&mut self, replay_gain: Option<ReplayGain>) {
+        self.update(
+            TrackField::ReplayGain,
+            |t| t.replay_gain = replay_gain,
+            |a, b| a.replay_gain == b.replay_gain,
+        );
+    }
+
+    fn update(
+        &mut self,
+        field: TrackField,
+        set: impl FnOnce(&mut Track),
+        same: impl Fn(&Track, &Track) -> bool,
+    ) {
+        let position = self.position;
+        let track_index = self.edit.original.tracks[position].track_index;
+
+        self.edit.update(
+            CueField::Track(track_index, field),
+            |cue| set(&mut cue.tracks[position]),
+            |a, b| same(&a.tracks[position], &b.tracks[position]),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_changed_fields() {
+        let cue = Cue::from_str(
+            "TITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One\"\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+        let mut edit = CueEdit::new(&cue);
+
+        assert!(matches!(edit.edited, Cow::Borrowed(_)));
+
+        edit.set_title(Some("Album (Remastered)".into()));
+        edit.track(2).unwrap().set_title(Some("Two".into()));
+        edit.track(1).unwrap().set_title(Some("One".into()));
+
+        assert_eq!(
+            edit.changes().collect::<Vec<_>>(),
+            vec![CueField::Title, CueField::Track(2, TrackField::Title)]
+        );
+        assert_eq!(edit.cue().tracks[1].title.as_deref(), Some("Two"));
+        assert_eq!(edit.original().tracks[1].title, None);
+
+        edit.set_title(Some("Album".into()));
+
+        assert!(!edit.is_field_modified(CueField::Title));
+        assert!(edit.is_modified());
+        assert!(edit.track(3).is_none());
+    }
+
+    #[test]
+    fn replaced_comments_lose_their_positions() {
+        let cue = Cue::from_str(
+            "TITLE \"Album\"\nREM before the file\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nREM before the index\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        let mut edit = CueEdit::new(&cue);
+
+        edit.set_comments(vec!["new".into()]);
+        edit.track(1)
+            .unwrap()
+            .set_comments(["also new".to_string()]);
+
+        assert_eq!(
+            edit.cue().to_string(),
+            "REM new\nTITLE \"Album\"\nFILE \"a.wav\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\n    REM also new\n"
+        );
+
+        edit.set_comments(cue.comments.clone());
+
+        assert_eq!(edit.cue().comment_positions, cue.comment_positions);
+    }
+}
@@ -0,0 +1,54 @@
+use std::borrow::Cow;
+
+/// Windows-1252 characters for bytes 0x80-0x9F, where it differs from Latin-1
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Decodes a cue file's bytes, detecting the encoding.
+///
+/// A byte order mark selects UTF-8 or UTF-16. Without one, valid UTF-8 is used as-is and anything
+/// else is taken to be Windows-1252, which most older Windows rippers wrote.
+pub fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest),
+        [0xFF, 0xFE, rest @ ..] => Cow::Owned(decode_utf16(rest, u16::from_le_bytes)),
+        [0xFE, 0xFF, rest @ ..] => Cow::Owned(decode_utf16(rest, u16::from_be_bytes)),
+        _ => match std::str::from_utf8(bytes) {
+            Ok(text) => Cow::Borrowed(text),
+            Err(_) => Cow::Owned(bytes.iter().map(|&b| windows_1252(b)).collect()),
+        },
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]));
+
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+fn windows_1252(byte: u8) -> char {
+    match byte {
+        0x80..=0x9F => WINDOWS_1252[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_encodings() {
+        assert_eq!(decode(b"\xEF\xBB\xBFTITLE \"x\""), "TITLE \"x\"");
+        assert_eq!(decode(b"\xFF\xFET\x00\xE9\x00"), "Té");
+        assert_eq!(decode(b"\xFE\xFF\x00T\x00\xE9"), "Té");
+        assert_eq!(decode("Beyoncé".as_bytes()), "Beyoncé");
+        assert_eq!(decode(b"Beyonc\xE9 \x93Halo\x94"), "Beyoncé “Halo”");
+    }
+}
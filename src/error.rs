@@ -0,0 +1,291 @@
+use std::{fmt, io, sync::Arc};
+
+use crate::Cue;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// A parse error along with the cue up to it, returned with
+    /// [`ParseOptions::keep_partial`](crate::ParseOptions::keep_partial). Holds every track
+    /// before the one the error is in, or before the final lead-out track.
+    #[error("{cause}")]
+    Partial { parsed: Box<Cue>, cause: ParseError },
+    #[error(transparent)]
+    Io(Arc<io::Error>),
+}
+
+impl Error {
+    /// A code identifying the kind of error, such as `CUE002`, which stays the same across
+    /// versions so that tools can look it up or match on it
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Parse(e) | Self::Partial { cause: e, .. } => e.code(),
+            Self::Io(_) => "CUE001",
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(Arc::new(e))
+    }
+}
+
+/// Why and where a cue couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError(Box<Details>);
+
+/// Boxed so that results carrying an [`Error`] stay small
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Details {
+    kind: ParseErrorKind,
+    message: String,
+    location: Location,
+    line_text: String,
+    command: Option<String>,
+    expected: Vec<String>,
+    suggestion: Option<String>,
+}
+
+impl ParseError {
+    pub(crate) fn new(
+        kind: ParseErrorKind,
+        message: String,
+        location: Location,
+        line_text: String,
+        command: Option<String>,
+        expected: Vec<String>,
+        suggestion: Option<String>,
+    ) -> Self {
+        Self(Box::new(Details {
+            kind,
+            message,
+            location,
+            line_text,
+            command,
+            expected,
+            suggestion,
+        }))
+    }
+
+    pub fn kind(&self) -> ParseErrorKind {
+        self.0.kind
+    }
+
+    /// The stable code of the error's kind, see [`Error::code`]
+    pub fn code(&self) -> &'static str {
+        self.0.kind.code()
+    }
+
+    pub fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    pub fn location(&self) -> Location {
+        self.0.location
+    }
+
+    /// The whole line the error is on, without its line ending
+    pub fn line_text(&self) -> &str {
+        &self.0.line_text
+    }
+
+    /// The command on the line, if it starts with a known one, e.g. `INDEX`
+    pub fn command(&self) -> Option<&str> {
+        self.0.command.as_deref()
+    }
+
+    /// What could have come at the error's location instead, such as command names or
+    /// descriptions like "a time". Empty for invalid values.
+    pub fn expected(&self) -> &[String] {
+        &self.0.expected
+    }
+
+    /// The known command an unknown one is probably a typo of, e.g. `PERFORMER` for `PERFROMER`
+    pub fn suggestion(&self) -> Option<&str> {
+        self.0.suggestion.as_deref()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Location { line, column, .. } = self.0.location;
+
+        write!(f, "{line}:{column}: ")?;
+
+        if let Some(command) = &self.0.command {
+            write!(f, "{command}: ")?;
+        }
+
+        f.write_str(&self.0.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Labels the offending span of the input. The input isn't kept, so attach it to show the span,
+/// e.g. with `miette::Report::with_source_code`.
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for ParseError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(self.code()))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        if let Some(suggestion) = &self.0.suggestion {
+            return Some(Box::new(format!("did you mean {suggestion}?")));
+        }
+
+        let help = match (self.0.kind, &self.0.command) {
+            (ParseErrorKind::Syntax, Some(command)) => {
+                format!("check the arguments of this {command} command")
+            }
+            (ParseErrorKind::Syntax | ParseErrorKind::UnknownCommand, None) => {
+                "each line should start with a command, such as TRACK or INDEX".into()
+            }
+            (ParseErrorKind::MisplacedCommand, Some(command)) => {
+                format!("move this {command} after the TRACK it belongs to")
+            }
+            (_, Some(command)) => format!("check the value given to {command}"),
+            (_, None) => return None,
+        };
+
+        Some(Box::new(help))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let Location { start, end, .. } = self.0.location;
+        let label =
+            miette::LabeledSpan::new_primary_with_span(Some(self.0.message.clone()), start..end);
+
+        Some(Box::new(std::iter::once(label)))
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(self.code()))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        match self {
+            Self::Parse(e) | Self::Partial { cause: e, .. } => e.help(),
+            Self::Io(_) => None,
+        }
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        match self {
+            Self::Parse(e) | Self::Partial { cause: e, .. } => e.labels(),
+            Self::Io(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input isn't laid out as a cue, e.g. a missing argument
+    Syntax,
+    /// A command is well formed, but its value can't be used, e.g. a time that's too long
+    InvalidValue,
+    /// A line doesn't start with a known command
+    UnknownCommand,
+    /// A track's command comes before the first `TRACK`
+    MisplacedCommand,
+    /// The input is longer than [`ParseOptions::max_input_len`](crate::ParseOptions::max_input_len)
+    TooLarge,
+}
+
+impl ParseErrorKind {
+    /// The stable code of the kind, see [`Error::code`]
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Syntax => "CUE002",
+            Self::InvalidValue => "CUE003",
+            Self::UnknownCommand => "CUE004",
+            Self::MisplacedCommand => "CUE005",
+            Self::TooLarge => "CUE006",
+        }
+    }
+}
+
+/// Where in the input an error was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// Byte offset of the start of the problem
+    pub start: usize,
+    /// Byte offset of the end of the problem, the same as `start` if it's at a single position
+    pub end: usize,
+    /// Line of `start`, counting from 1
+    pub line: usize,
+    /// Column of `start` in characters, counting from 1
+    pub column: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cue, Error, ParseErrorKind};
+
+    #[test]
+    fn parse_errors() {
+        let Err(Error::Parse(e)) = Cue::from_str("TRACK 01 AUDIO\nINDEX 01 100:00:00\n") else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(e.kind(), ParseErrorKind::InvalidValue);
+        assert_eq!((e.location().line, e.location().column), (2, 10));
+        assert_eq!(e.location().start, 24);
+        assert!(e.to_string().starts_with("2:10: INDEX: "));
+        assert_eq!(e.line_text(), "INDEX 01 100:00:00");
+        assert_eq!(e.command(), Some("INDEX"));
+
+        let Err(Error::Parse(e)) = Cue::from_str("TRACK 01 AUDIO\nBOGUS\n") else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(e.kind(), ParseErrorKind::UnknownCommand);
+        assert_eq!(e.location().line, 2);
+        assert_eq!(e.command(), None);
+        assert!(e.expected().iter().any(|rule| rule == "INDEX"));
+        assert_eq!(e.message(), "unknown command BOGUS");
+
+        let Err(Error::Parse(e)) = Cue::from_str("TACK 01 AUDIO\n") else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(e.suggestion(), Some("TRACK"));
+        assert_eq!(e.message(), "unknown command TACK, did you mean TRACK?");
+
+        let Err(Error::Parse(e)) = Cue::from_str("INDEX 01 00:00:00\n") else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(e.kind(), ParseErrorKind::MisplacedCommand);
+        assert_eq!(e.to_string(), "1:1: INDEX: Only allowed inside a TRACK");
+        assert_eq!(Error::Parse(e).code(), "CUE005");
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn diagnostic_labels() {
+        use miette::Diagnostic;
+
+        let Err(Error::Parse(e)) = Cue::from_str("FILE \"a\" WAVE\nTRACK 01 AUDIO\nINDEX 01 1:2\n")
+        else {
+            panic!("expected a parse error");
+        };
+
+        let labels: Vec<_> = e.labels().unwrap().collect();
+
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].offset(), 38);
+        assert_eq!(labels[0].label(), Some(e.message()));
+        assert_eq!(
+            e.help().unwrap().to_string(),
+            "check the value given to INDEX"
+        );
+    }
+}
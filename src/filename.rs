@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+
+use crate::{Cue, Template, Track};
+
+/// Characters Windows doesn't allow in file names, which covers those of macOS and Linux
+const RESERVED_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Names of devices on Windows, which can't be used as file names even with an extension
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// How [`Track::safe_filename`] names a track
+#[derive(Debug, Clone)]
+pub struct FilenameOptions {
+    /// The name before it's made safe, `%tracknumber:2% - %title|"Untitled"%` by default
+    pub template: Template,
+    /// Added to the name, such as `.flac`
+    pub extension: String,
+    /// What characters that aren't allowed are replaced with
+    pub replacement: char,
+    /// The longest name in bytes, extension included. Most file systems allow 255.
+    pub max_len: usize,
+}
+
+impl Default for FilenameOptions {
+    fn default() -> Self {
+        Self {
+            template: "%tracknumber:2% - %title|\"Untitled\"%".parse().unwrap(),
+            extension: String::new(),
+            replacement: '_',
+            max_len: 255,
+        }
+    }
+}
+
+impl Track {
+    /// A name for the track's file that's valid on Windows, macOS and Linux: characters that
+    /// aren't allowed replaced, device names such as `CON` avoided, no trailing dots or spaces,
+    /// and cut to [`FilenameOptions::max_len`]. Use [`Cue::safe_filenames`] to also keep the
+    /// names of the tracks apart.
+    pub fn safe_filename(&self, cue: &Cue, options: &FilenameOptions) -> String {
+        let name = options.template.render(cue, self);
+        let mut stem: String = name
+            .trim_start()
+            .chars()
+            .map(|c| match c.is_control() || RESERVED_CHARS.contains(&c) {
+                true => options.replacement,
+                false => c,
+            })
+            .collect();
+
+        truncate(
+            &mut stem,
+            options.max_len.saturating_sub(options.extension.len()),
+        );
+
+        let device = stem.split('.').next().unwrap_or_default();
+
+        if stem.is_empty() || RESERVED_NAMES.contains(&device.to_ascii_uppercase().as_str()) {
+            stem.insert(0, options.replacement);
+            truncate(
+                &mut stem,
+                options.max_len.saturating_sub(options.extension.len()),
+            );
+        }
+
+        stem + &options.extension
+    }
+}
+
+impl Cue {
+    /// [`Track::safe_filename`] for every track, with ` (2)`, ` (3)` and so on added to names
+    /// already taken, ignoring case as Windows and macOS do
+    pub fn safe_filenames(&self, options: &FilenameOptions) -> Vec<String> {
+        let mut taken = HashSet::new();
+
+        self.tracks
+            .iter()
+            .map(|track| {
+                let name = track.safe_filename(self, options);
+                let stem = &name[..name.len() - options.extension.len()];
+                let mut candidate = name.clone();
+
+                for n in 2.. {
+                    if taken.insert(candidate.to_lowercase()) {
+                        break;
+                    }
+
+                    let suffix = format!(" ({n})");
+                    let mut stem = stem.to_string();
+
+                    truncate(
+                        &mut stem,
+                        options
+                            .max_len
+                            .saturating_sub(options.extension.len() + suffix.len()),
+                    );
+                    candidate = format!("{stem}{suffix}{}", options.extension);
+                }
+
+                candidate
+            })
+            .collect()
+    }
+}
+
+/// Cuts the name to at most `len` bytes on a character boundary, then removes the trailing
+/// dots and spaces Windows doesn't allow
+fn truncate(name: &mut String, len: usize) {
+    if name.len() > len {
+        let end = (0..=len).rev().find(|&i| name.is_char_boundary(i)).unwrap();
+
+        name.truncate(end);
+    }
+
+    let trimmed = name.trim_end_matches(['.', ' ']).len();
+
+    name.truncate(trimmed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_names() {
+        let cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"AC/DC: What?...\"\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nTITLE \"Con\"\nINDEX 01 01:00:00\nTRACK 03 AUDIO\nTITLE \"Same\"\nINDEX 01 02:00:00\nTRACK 04 AUDIO\nTITLE \"same\"\nINDEX 01 03:00:00\n",
+        )
+        .unwrap();
+        let options = FilenameOptions {
+            template: "%title%".parse().unwrap(),
+            extension: ".flac".into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            cue.safe_filenames(&options),
+            ["AC_DC_ What_", "_Con", "Same", "same (2)"].map(|name| format!("{name}.flac"))
+        );
+
+        let long = FilenameOptions {
+            max_len: 9,
+            ..options
+        };
+
+        assert_eq!(cue.tracks[0].safe_filename(&cue, &long), "AC_D.flac");
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{Cue, Frames, TrackIndex};
+
+/// A change [`Cue::repair`] made to a cue, or would have made if it were safe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repair {
+    pub kind: RepairKind,
+    /// The number of the track that was changed, if one was, before any renumbering
+    pub track: Option<u8>,
+    pub message: String,
+    /// Whether the change was made. Changes that could be wrong are only made when forced.
+    pub applied: bool,
+}
+
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.track {
+            Some(track) => write!(f, "track {track:02}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RepairKind {
+    /// Tracks were numbered consecutively from the first track's number
+    TrackNumbering,
+    /// The first track was given an `INDEX 01` at the start of its file
+    FirstTrackStart,
+    /// Backslashes in a `FILE` path were replaced with forward slashes
+    PathSeparators,
+    /// A later track without an `INDEX 01` was given one, at its `INDEX 00` or at the start of
+    /// its file
+    MissingStart,
+}
+
+impl Cue {
+    /// Repairs common mistakes, returning what was changed. Some repairs are only certain to be
+    /// right in simple cases, and elsewhere are reported without being made unless `force` is set:
+    /// renumbering tracks that are out of order, adding an `INDEX 01` to a first track that has
+    /// other indices, and adding one at the `INDEX 00` of a later track.
+    pub fn repair(&mut self, force: bool) -> Vec<Repair> {
+        let mut repairs = Vec::new();
+        let mut repair = |kind, track, message: String, safe: bool| {
+            let applied = safe || force;
+
+            repairs.push(Repair {
+                kind,
+                track,
+                message,
+                applied,
+            });
+
+            applied
+        };
+
+        let mut fixed_paths = Vec::new();
+
+        if let Some(path) = self.path.as_mut().filter(|path| has_backslash(path)) {
+            let fixed = forward_slashes(path);
+
+            repair(
+                RepairKind::PathSeparators,
+                None,
+                format!("FILE {} becomes {}", path.display(), fixed.display()),
+                true,
+            );
+            fixed_paths.push(fixed.clone());
+            *path = fixed;
+        }
+
+        for track in &mut self.tracks {
+            let Some(path) = track.file.as_mut().filter(|path| has_backslash(path)) else {
+                continue;
+            };
+
+            let fixed = forward_slashes(path);
+
+            // tracks share their FILE, so it's only reported once
+            if !fixed_paths.contains(&fixed) {
+                fixed_paths.push(fixed.clone());
+                repair(
+                    RepairKind::PathSeparators,
+                    Some(track.track_index),
+                    format!("FILE {} becomes {}", path.display(), fixed.display()),
+                    true,
+                );
+            }
+
+            *path = fixed;
+        }
+
+        for i in 0..self.tracks.len() {
+            let starts_file = i == 0 || self.tracks[i - 1].file != self.tracks[i].file;
+            let track = &mut self.tracks[i];
+
+            if track.index(1).is_some() {
+                continue;
+            }
+
+            // the first track keeps its own repair, which only adds 00:00:00
+            let (kind, start, safe) = if i == 0 {
+                (
+                    RepairKind::FirstTrackStart,
+                    Frames::ZERO,
+                    track.indices.is_empty(),
+                )
+            } else if let Some(pregap) = track.index(0).and_then(|index| index.time()) {
+                // the pregap may not have been meant as part of the track
+                (RepairKind::MissingStart, pregap, false)
+            } else if starts_file && track.indices.is_empty() {
+                (RepairKind::MissingStart, Frames::ZERO, true)
+            } else {
+                continue;
+            };
+
+            let (m, s, f) = start.to_msf();
+
+            if repair(
+                kind,
+                Some(track.track_index),
+                format!("added INDEX 01 {m:02}:{s:02}:{f:02}"),
+                safe,
+            ) {
+                let position = track
+                    .indices
+                    .iter()
+                    .position(|index| index.index() > 1)
+                    .unwrap_or(track.indices.len());
+
+                track
+                    .indices
+                    .insert(position, TrackIndex::new(1, Some(start)));
+            }
+        }
+
+        let first = self.tracks.first().map_or(1, |track| track.track_index);
+        let numbered = self
+            .tracks
+            .iter()
+            .zip(usize::from(first)..)
+            .all(|(track, number)| usize::from(track.track_index) == number);
+
+        if !numbered {
+            let in_order = self
+                .tracks
+                .windows(2)
+                .all(|pair| pair[0].track_index < pair[1].track_index);
+            let last = usize::from(first) + self.tracks.len() - 1;
+
+            if last <= 99
+                && repair(
+                    RepairKind::TrackNumbering,
+                    None,
+                    format!("renumbered tracks {first:02} to {last:02}"),
+                    in_order,
+                )
+            {
+                self.renumber_tracks();
+            }
+        }
+
+        repairs
+    }
+}
+
+fn has_backslash(path: &Path) -> bool {
+    path.to_string_lossy().contains('\\')
+}
+
+fn forward_slashes(path: &Path) -> PathBuf {
+    path.to_string_lossy().replace('\\', "/").into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_and_forced_repairs() {
+        let text = "FILE \"CD1\\a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 00 00:00:00\nTRACK 03 AUDIO\nINDEX 01 01:00:00\nTRACK 02 AUDIO\nINDEX 01 02:00:00\n";
+        let mut cue = Cue::from_str(text).unwrap();
+
+        let repairs = cue.repair(false);
+
+        assert_eq!(
+            repairs.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "FILE CD1\\a.wav becomes CD1/a.wav",
+                "track 01: added INDEX 01 00:00:00",
+                "renumbered tracks 01 to 03"
+            ]
+        );
+        assert_eq!(
+            repairs.iter().map(|r| r.applied).collect::<Vec<_>>(),
+            [true, false, false]
+        );
+        assert_eq!(cue.tracks[2].file, Some("CD1/a.wav".into()));
+        assert_eq!(cue.tracks[1].track_index, 3);
+
+        let mut cue = Cue::from_str(text).unwrap();
+
+        assert!(cue.repair(true).iter().all(|r| r.applied));
+        assert_eq!(cue.tracks[0].start(), Some(Frames::new(0)));
+        assert_eq!(cue.tracks[1].track_index, 2);
+    }
+
+    #[test]
+    fn missing_starts() {
+        let mut cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 01:00:00\nFILE \"b.wav\" WAVE\nTRACK 03 AUDIO\nTRACK 04 AUDIO\nINDEX 00 02:00:00\n",
+        )
+        .unwrap();
+
+        let repairs = cue.repair(false);
+
+        assert_eq!(
+            repairs
+                .iter()
+                .map(|r| (r.to_string(), r.applied))
+                .collect::<Vec<_>>(),
+            [
+                ("track 03: added INDEX 01 00:00:00".into(), true),
+                ("track 04: added INDEX 01 02:00:00".into(), false),
+            ]
+        );
+        assert_eq!(cue.tracks[2].start(), Some(Frames::new(0)));
+
+        cue.repair(true);
+
+        assert_eq!(
+            cue.tracks[3].start(),
+            Some(Frames::from_msf(2, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn tracks_numbered_past_255() {
+        let mut cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 255 AUDIO\nINDEX 01 00:00:00\nTRACK 01 AUDIO\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+
+        assert!(cue.repair(false).is_empty());
+        assert_eq!(cue.tracks[0].track_index, 255);
+    }
+}
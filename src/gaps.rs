@@ -0,0 +1,83 @@
+use crate::{Cue, Frames};
+
+/// The pregap of a track, from [`Cue::gap_report`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TrackGap {
+    pub track: u8,
+    /// From `INDEX 00` to `INDEX 01`, the gap stored in the audio. `None` if the track has no
+    /// `INDEX 00`, or if it's at the end of the file before, whose length isn't known.
+    pub indexed: Option<Frames>,
+    /// Given by a `PREGAP` command, silence that isn't stored in the audio
+    pub pregap: Option<Frames>,
+}
+
+impl TrackGap {
+    /// The whole gap, stored and not, or `None` if it's longer than a time can be
+    pub fn length(&self) -> Option<Frames> {
+        let indexed = self.indexed.map_or(0, Frames::frames);
+        let pregap = self.pregap.map_or(0, Frames::frames);
+
+        Frames::try_from(indexed.checked_add(pregap)?).ok()
+    }
+
+    /// Whether the gap is given both by `INDEX 00` and `PREGAP`. Rippers write one or the
+    /// other, so having both usually means the cue was edited by hand or merged wrongly.
+    pub fn is_conflicting(&self) -> bool {
+        self.indexed.is_some_and(|gap| gap.frames() > 0)
+            && self.pregap.is_some_and(|gap| gap.frames() > 0)
+    }
+}
+
+impl Cue {
+    /// The pregap of each track, for comparing with the gaps of a rip log
+    pub fn gap_report(&self) -> Vec<TrackGap> {
+        self.tracks
+            .iter()
+            .map(|track| {
+                let indexed = track
+                    .index(0)
+                    .and_then(|index| index.time())
+                    .zip(track.start())
+                    .and_then(|(pregap, start)| start.frames().checked_sub(pregap.frames()))
+                    .map(Frames::new);
+
+                TrackGap {
+                    track: track.track_index,
+                    indexed,
+                    pregap: track.pregap,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_gaps() {
+        let cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nPREGAP 00:02:00\nINDEX 00 03:00:00\nINDEX 01 03:01:00\nTRACK 03 AUDIO\nINDEX 00 05:00:00\nFILE \"b.wav\" WAVE\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        let gaps = cue.gap_report();
+
+        assert_eq!(gaps[0].length(), Some(Frames::ZERO));
+        assert_eq!(gaps[1].indexed, Some(Frames::new(75)));
+        assert_eq!(gaps[1].length(), Some(Frames::new(225)));
+        assert_eq!(
+            TrackGap {
+                track: 1,
+                indexed: Some(Frames::from_msf(50, 0, 0).unwrap()),
+                pregap: Some(Frames::from_msf(90, 0, 0).unwrap()),
+            }
+            .length(),
+            None
+        );
+        assert!(gaps[1].is_conflicting());
+        // in the file before, so its length isn't known
+        assert_eq!(gaps[2].indexed, None);
+    }
+}
@@ -0,0 +1,577 @@
+//! Creating a cue for audio that doesn't have one
+//!
+//! - [`from_files`] and [`from_dir`]: a track for each file, as in a rip split into tracks
+//! - [`from_tracklist`]: tracks at the times listed in a text file, within one image
+//! - [`from_tracklist_with`]: the same for tracklists copied from elsewhere, such as the
+//!   description of a mix, read with [`TracklistPattern`]s
+//! - [`from_audacity_labels`]: tracks at the labels exported from Audacity
+//! - [`from_m3u`]: a track for each file of an M3U playlist, in its order
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{Cue, FileFormat, Frames, Track, TrackIndex, TrackMode};
+
+/// File extensions [`from_dir`] treats as audio
+pub const AUDIO_EXTENSIONS: [&str; 10] = [
+    "flac", "wav", "mp3", "ogg", "opus", "m4a", "ape", "wv", "aiff", "aif",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TracklistError {
+    #[error("line {0}: expected a time such as 03:45 followed by a title")]
+    Line(usize),
+    #[error("line {0}: the track starts before the one above it")]
+    OutOfOrder(usize),
+    #[error("the tracklist has no tracks")]
+    Empty,
+    #[error("more than 99 tracks")]
+    TooManyTracks,
+    #[error("line {0}: where the track starts isn't known, as the one above has no length")]
+    UnknownStart(usize),
+    #[error("line {0}: expected a label's start and end in seconds, then its text")]
+    Label(usize),
+    #[error("line {0}: the length of the file isn't known")]
+    UnknownLength(usize),
+}
+
+/// A track read from a line of a tracklist by a [`TracklistPattern`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TracklistEntry {
+    /// Where the track starts in the image
+    pub start: Option<Frames>,
+    /// How long the track is, which gives the start of the next one when that has none
+    pub length: Option<Frames>,
+    pub performer: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Reads a line of a tracklist, or returns `None` if it isn't a track in this form
+pub type TracklistPattern = fn(&str) -> Option<TracklistEntry>;
+
+/// The patterns [`from_tracklist_with`] is usually given, [`timestamped`] and [`numbered`]
+pub const TRACKLIST_PATTERNS: [TracklistPattern; 2] = [timestamped, numbered];
+
+/// A cue with a track for each file, in order, each starting at the start of its file. Titles are
+/// taken from the file names without any leading track number, e.g. `One` for `01 - One.flac`.
+///
+/// # Panics
+///
+/// If there are more than 99 files
+pub fn from_files(paths: &[PathBuf]) -> Cue {
+    assert!(paths.len() <= 99, "a cue can't have more than 99 tracks");
+
+    let tracks = paths.iter().zip(1..).map(|(path, number)| {
+        let mut track = Track::new(number, TrackMode::Audio);
+
+        track.set_file(path, file_format(path));
+        track.title = path.file_stem().map(|stem| title(&stem.to_string_lossy()));
+        track.set_indices([TrackIndex::new(1, Some(Frames::ZERO))]);
+        track
+    });
+
+    Cue::new(tracks.collect())
+}
+
+/// [`from_files`] with the audio files in a directory, sorted by name. The cue refers to them by
+/// name alone, to be saved in the same directory.
+pub fn from_dir(dir: impl AsRef<Path>) -> io::Result<Cue> {
+    let mut names = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let name = PathBuf::from(entry?.file_name());
+        let is_audio = name
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                AUDIO_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+            });
+
+        if is_audio {
+            names.push(name);
+        }
+    }
+
+    names.sort();
+
+    if names.len() > 99 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a cue can't have more than 99 tracks",
+        ));
+    }
+
+    Ok(from_files(&names))
+}
+
+/// A cue for a single image, with a track for each line of `tracklist` giving where it starts
+/// and its title, such as `03:45 Title` or `[01:02:03] Title`. Times are `MM:SS`, `MM:SS:FF`, or
+/// with hours `H:MM:SS`, and blank lines are skipped.
+pub fn from_tracklist(image: impl Into<PathBuf>, tracklist: &str) -> Result<Cue, TracklistError> {
+    let image = image.into();
+    let format = file_format(&image);
+    let mut tracks: Vec<Track> = Vec::new();
+
+    for (i, line) in tracklist.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let (time, title) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .or_else(|| line.split_once(char::is_whitespace))
+            .unwrap_or((line, ""));
+        let start = parse_time(time).ok_or(TracklistError::Line(i + 1))?;
+
+        if tracks.last().and_then(Track::start) > Some(start) {
+            return Err(TracklistError::OutOfOrder(i + 1));
+        }
+
+        let number = u8::try_from(tracks.len() + 1)
+            .ok()
+            .filter(|number| *number <= 99)
+            .ok_or(TracklistError::TooManyTracks)?;
+        let mut track = Track::new(number, TrackMode::Audio);
+        let title = title.trim().trim_start_matches(['-', '–']).trim();
+
+        track.set_file(&image, format.clone());
+        track.title = (!title.is_empty()).then(|| title.into());
+        track.set_indices([TrackIndex::new(1, Some(start))]);
+        tracks.push(track);
+    }
+
+    if tracks.is_empty() {
+        return Err(TracklistError::Empty);
+    }
+
+    Ok(Cue::new(tracks))
+}
+
+/// A cue for a single image from a tracklist in whatever form it was published, trying each of
+/// `patterns` on each line in turn. Lines none of them match, such as headings, are skipped. A
+/// track without a start time starts where the one before it ends, or at the start of the image
+/// if it's the first.
+pub fn from_tracklist_with(
+    image: impl Into<PathBuf>,
+    tracklist: &str,
+    patterns: &[TracklistPattern],
+) -> Result<Cue, TracklistError> {
+    let image = image.into();
+    let format = file_format(&image);
+    let mut tracks: Vec<Track> = Vec::new();
+    let mut next_start = Some(Frames::ZERO);
+
+    for (i, line) in tracklist.lines().enumerate() {
+        let Some(entry) = patterns.iter().find_map(|pattern| pattern(line.trim())) else {
+            continue;
+        };
+        let start = entry
+            .start
+            .or(next_start)
+            .ok_or(TracklistError::UnknownStart(i + 1))?;
+
+        if tracks.last().and_then(Track::start) > Some(start) {
+            return Err(TracklistError::OutOfOrder(i + 1));
+        }
+
+        let number = u8::try_from(tracks.len() + 1)
+            .ok()
+            .filter(|number| *number <= 99)
+            .ok_or(TracklistError::TooManyTracks)?;
+        let mut track = Track::new(number, TrackMode::Audio);
+
+        track.set_file(&image, format.clone());
+        track.performer = entry.performer.map(Into::into);
+        track.title = entry.title;
+        track.set_indices([TrackIndex::new(1, Some(start))]);
+        tracks.push(track);
+        next_start = entry
+            .length
+            .and_then(|length| Frames::try_from(start.frames() + length.frames()).ok());
+    }
+
+    if tracks.is_empty() {
+        return Err(TracklistError::Empty);
+    }
+
+    Ok(Cue::new(tracks))
+}
+
+/// A line starting with the time the track starts, such as `[00:00] Title` or
+/// `03:45 Artist - Title`
+pub fn timestamped(line: &str) -> Option<TracklistEntry> {
+    let (time, rest) = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .or_else(|| line.split_once(char::is_whitespace))?;
+
+    Some(TracklistEntry {
+        start: Some(parse_time(time)?),
+        ..artist_and_title(rest)
+    })
+}
+
+/// A line starting with the track's number and ending with its length, such as
+/// `01. Artist - Title (3:45)`. The length may be left out.
+pub fn numbered(line: &str) -> Option<TracklistEntry> {
+    let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+
+    if rest.len() == line.len() {
+        return None;
+    }
+
+    let rest = rest.strip_prefix(['.', ')']).unwrap_or(rest);
+
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let length = rest
+        .trim_end()
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once('('))
+        .and_then(|(rest, time)| Some((rest, parse_time(time.trim())?)));
+
+    Some(match length {
+        Some((rest, length)) => TracklistEntry {
+            length: Some(length),
+            ..artist_and_title(rest)
+        },
+        None => artist_and_title(rest),
+    })
+}
+
+/// `Artist - Title`, or just a title
+fn artist_and_title(text: &str) -> TracklistEntry {
+    let text = text.trim().trim_start_matches(['-', '–']).trim();
+    let (performer, title) = match text.split_once(" - ").or_else(|| text.split_once(" – ")) {
+        Some((performer, title)) => (Some(performer.trim().into()), title.trim()),
+        None => (None, text),
+    };
+
+    TracklistEntry {
+        performer,
+        title: (!title.is_empty()).then(|| title.into()),
+        ..Default::default()
+    }
+}
+
+/// A cue for `audio` with a track at each label of an Audacity label file, as exported with
+/// File > Export > Export Labels. Each label's start is where its track starts and its text the
+/// title. Their ends are ignored, as are the frequency lines of spectral labels.
+pub fn from_audacity_labels(
+    audio: impl Into<PathBuf>,
+    format: FileFormat,
+    labels: &str,
+) -> Result<Cue, TracklistError> {
+    let audio = audio.into();
+    let mut starts = Vec::new();
+
+    for (i, line) in labels.lines().enumerate() {
+        // spectral labels are followed by a line of their frequencies, starting with a backslash
+        if line.trim().is_empty() || line.starts_with('\\') {
+            continue;
+        }
+
+        let mut fields = line.splitn(3, '\t');
+        let start = fields
+            .next()
+            .and_then(|start| start.trim().parse::<f64>().ok())
+            .filter(|start| start.is_finite() && *start >= 0.0)
+            .and_then(|start| Frames::try_from((start * 75.0).round() as u32).ok())
+            .ok_or(TracklistError::Label(i + 1))?;
+        let title = fields.nth(1).unwrap_or_default().trim();
+
+        starts.push((start, title));
+    }
+
+    // labels are exported in the order they were added, not by time
+    starts.sort_by_key(|&(start, _)| start);
+
+    if starts.len() > 99 {
+        return Err(TracklistError::TooManyTracks);
+    }
+
+    let tracks: Vec<Track> = starts
+        .into_iter()
+        .zip(1..)
+        .map(|((start, title), number)| {
+            let mut track = Track::new(number, TrackMode::Audio);
+
+            track.set_file(&audio, format.clone());
+            track.title = (!title.is_empty()).then(|| title.into());
+            track.set_indices([TrackIndex::new(1, Some(start))]);
+            track
+        })
+        .collect();
+
+    if tracks.is_empty() {
+        return Err(TracklistError::Empty);
+    }
+
+    Ok(Cue::new(tracks))
+}
+
+/// A cue with a track for each file of an M3U or M3U8 playlist, in the playlist's order, to be
+/// saved beside it as paths are kept as written. Titles come from `#EXTINF` lines, split into
+/// performer and title at ` - `, and otherwise from the file names as in [`from_files`].
+///
+/// `file_length` gives the length of each file, from a map or by probing the audio, and entries it
+/// doesn't know, such as streams, are rejected. The lengths are returned along with the cue, one
+/// for each track, for laying it out with [`DiscLayout::new`](crate::DiscLayout::new) and the
+/// like. A playlist of one file has its length as the lead-out.
+pub fn from_m3u(
+    playlist: &str,
+    mut file_length: impl FnMut(&Path) -> Option<Frames>,
+) -> Result<(Cue, Vec<Frames>), TracklistError> {
+    let mut tracks: Vec<Track> = Vec::new();
+    let mut lengths = Vec::new();
+    let mut info = None;
+
+    for (i, line) in playlist.lines().enumerate() {
+        let line = line.trim_start_matches('\u{feff}').trim();
+
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            // the duration, maybe followed by attributes, then a comma and the display title
+            info = extinf
+                .split_once(',')
+                .map(|(_, title)| artist_and_title(title));
+            continue;
+        }
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let path = PathBuf::from(line);
+        let length = file_length(&path).ok_or(TracklistError::UnknownLength(i + 1))?;
+
+        let number = u8::try_from(tracks.len() + 1)
+            .ok()
+            .filter(|number| *number <= 99)
+            .ok_or(TracklistError::TooManyTracks)?;
+        let mut track = Track::new(number, TrackMode::Audio);
+        let info = info.take().unwrap_or_default();
+
+        track.set_file(&path, file_format(&path));
+        track.performer = info.performer.map(Into::into);
+        track.title = info
+            .title
+            .or_else(|| path.file_stem().map(|stem| title(&stem.to_string_lossy())));
+        track.set_indices([TrackIndex::new(1, Some(Frames::ZERO))]);
+        tracks.push(track);
+        lengths.push(length);
+    }
+
+    if tracks.is_empty() {
+        return Err(TracklistError::Empty);
+    }
+
+    let mut cue = Cue::new(tracks);
+
+    if let [length] = lengths[..] {
+        cue.leadout = Some(length);
+    }
+
+    Ok((cue, lengths))
+}
+
+/// `MM:SS`, `H:MM:SS` or `MM:SS:FF`, with hours told apart by being a single digit
+fn parse_time(time: &str) -> Option<Frames> {
+    let hours = time.find(':') == Some(1) && time.matches(':').count() == 2;
+    let parts: Vec<u32> = time
+        .split(':')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+
+    let (minutes, s, f) = match parts[..] {
+        [m, s] if s < 60 => (m, s, 0),
+        [h, m, s] if hours && m < 60 && s < 60 => (h * 60 + m, s, 0),
+        [m, s, f] if s < 60 && f < 75 => (m, s, f),
+        _ => return None,
+    };
+    let frames = minutes.checked_mul(60 * 75)?.checked_add(s * 75 + f)?;
+
+    Frames::try_from(frames).ok()
+}
+
+/// How the file is usually declared, with lossless formats other than AIFF as `WAVE`
+fn file_format(path: &Path) -> FileFormat {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("bin" | "img" | "iso") => FileFormat::Binary,
+        Some("mp3") => FileFormat::Mp3,
+        Some("aif" | "aiff") => FileFormat::Aiff,
+        _ => FileFormat::Wave,
+    }
+}
+
+/// The file name without a leading track number, unless that's all there is
+fn title(stem: &str) -> String {
+    let title = stem
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim_start_matches([' ', '-', '.', '_']);
+
+    if title.is_empty() {
+        stem.into()
+    } else {
+        title.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_from_files() {
+        let cue = from_files(&["01 - One.flac".into(), "02. Two.mp3".into()]);
+
+        assert_eq!(cue.tracks[0].title.as_deref(), Some("One"));
+        assert_eq!(cue.tracks[1].title.as_deref(), Some("Two"));
+        assert_eq!(cue.tracks[1].format, FileFormat::Mp3);
+        assert_eq!(cue.tracks[1].track_index, 2);
+    }
+
+    #[test]
+    fn tracks_from_tracklist() {
+        let cue = from_tracklist(
+            "mix.flac",
+            "[00:00] Intro\n\n03:45 - Second\n1:02:03 Third\n",
+        )
+        .unwrap();
+
+        let starts: Vec<_> = cue
+            .tracks
+            .iter()
+            .map(|t| t.start().unwrap().frames())
+            .collect();
+
+        assert_eq!(starts, [0, 225 * 75, 3723 * 75]);
+        assert_eq!(cue.tracks[1].title.as_deref(), Some("Second"));
+        assert_eq!(
+            from_tracklist("mix.flac", "03:00 A\n01:00 B").unwrap_err(),
+            TracklistError::OutOfOrder(2)
+        );
+        assert_eq!(
+            from_tracklist("a.flac", "99999999:00 X").unwrap_err(),
+            TracklistError::Line(1)
+        );
+    }
+
+    #[test]
+    fn tracklist_patterns() {
+        let cue = from_tracklist_with(
+            "mix.flac",
+            "Tracklist:\n01. Artist - First (3:45)\n02. Other – Second (58:15)\n[1:02:00] Third\n",
+            &TRACKLIST_PATTERNS,
+        )
+        .unwrap();
+
+        let tracks: Vec<_> = cue
+            .tracks
+            .iter()
+            .map(|t| {
+                (
+                    t.start().unwrap().frames() / 75,
+                    t.performer.as_deref(),
+                    t.title.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            tracks,
+            [
+                (0, Some("Artist"), Some("First")),
+                (225, Some("Other"), Some("Second")),
+                (3720, None, Some("Third")),
+            ]
+        );
+        assert_eq!(
+            from_tracklist_with("mix.flac", "1. A\n2. B\n", &[numbered]).unwrap_err(),
+            TracklistError::UnknownStart(2)
+        );
+    }
+
+    #[test]
+    fn tracks_from_audacity_labels() {
+        let labels =
+            "185.500000\t185.500000\tSecond\n\\\t100.0\t2000.0\n0.000000\t0.000000\tFirst\n";
+        let cue = from_audacity_labels("mix.wav", FileFormat::Wave, labels).unwrap();
+
+        let tracks: Vec<_> = cue
+            .tracks
+            .iter()
+            .map(|t| {
+                (
+                    t.track_index,
+                    t.start().unwrap().frames(),
+                    t.title.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(tracks, [(1, 0, Some("First")), (2, 13913, Some("Second"))]);
+        assert_eq!(
+            from_audacity_labels("mix.wav", FileFormat::Wave, "start\tend\n").unwrap_err(),
+            TracklistError::Label(1)
+        );
+    }
+
+    #[test]
+    fn tracks_from_m3u() {
+        let playlist = "#EXTM3U\n#EXTINF:185,Artist - First\n01 - first.flac\n\n# a comment\n02 - Second.mp3\n";
+        let (cue, lengths) = from_m3u(playlist, |path| {
+            Some(Frames::new(if path.extension()? == "flac" {
+                75
+            } else {
+                150
+            }))
+        })
+        .unwrap();
+
+        let tracks: Vec<_> = cue
+            .tracks
+            .iter()
+            .map(|t| {
+                (
+                    t.file.as_deref(),
+                    t.performer.as_deref(),
+                    t.title.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            tracks,
+            [
+                (
+                    Some(Path::new("01 - first.flac")),
+                    Some("Artist"),
+                    Some("First")
+                ),
+                (Some(Path::new("02 - Second.mp3")), None, Some("Second")),
+            ]
+        );
+        assert_eq!(lengths, [Frames::SECOND, Frames::new(150)]);
+        assert_eq!(cue.leadout, None);
+
+        let (single, _) = from_m3u("mix.flac\n", |_| Some(Frames::SECOND)).unwrap();
+        assert_eq!(single.leadout, Some(Frames::SECOND));
+        assert_eq!(
+            from_m3u(playlist, |path| (path.extension()? == "flac")
+                .then_some(Frames::SECOND))
+            .unwrap_err(),
+            TracklistError::UnknownLength(6)
+        );
+    }
+}
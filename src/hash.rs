@@ -0,0 +1,146 @@
+//! Per-track CRC32, MD5 and SHA-1 hashes of image data, as listed by Redump and compared by
+//! CUETools
+
+use std::{
+    io::{self, ErrorKind, Read},
+    path::Path,
+};
+
+use md5::{Digest, Md5};
+use sha1::Sha1;
+
+use crate::{Cue, SectorRange};
+
+const BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Hashes of one track's data, as if it had been split into its own file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackHashes {
+    pub track_index: u8,
+    /// Size in bytes of the hashed data
+    pub size: u64,
+    pub crc32: u32,
+    pub md5: [u8; 16],
+    pub sha1: [u8; 20],
+}
+
+/// Hashes every track's [`SectorRange`] in a single streaming pass over each file.
+///
+/// `open` is called once for each `FILE` referenced by the cue, in order, and must return a reader
+/// over its contents exactly as stored in the image, e.g. a `BINARY` file as-is.
+pub fn track_hashes<F, R>(cue: &Cue, mut open: F) -> io::Result<Vec<TrackHashes>>
+where
+    F: FnMut(&Path) -> io::Result<R>,
+    R: Read,
+{
+    let ranges = cue.sector_ranges();
+    let mut hashes = Vec::with_capacity(ranges.len());
+
+    for file in ranges.chunk_by(|a, b| a.file == b.file) {
+        let mut reader = open(file[0].file)?;
+        let mut hashers: Vec<Hasher> = file.iter().map(Hasher::new).collect();
+        let mut buf = vec![0; BUFFER_SIZE];
+        let mut position = 0;
+
+        loop {
+            let read = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => read as u64,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            for hasher in &mut hashers {
+                let start = hasher.start.max(position);
+                let end = hasher.end.min(position + read);
+
+                if start < end {
+                    hasher.update(&buf[(start - position) as usize..(end - position) as usize]);
+                }
+            }
+
+            position += read;
+        }
+
+        hashes.extend(hashers.into_iter().map(Hasher::finish));
+    }
+
+    Ok(hashes)
+}
+
+struct Hasher {
+    track_index: u8,
+    start: u64,
+    end: u64,
+    size: u64,
+    crc32: crc32fast::Hasher,
+    md5: Md5,
+    sha1: Sha1,
+}
+
+impl Hasher {
+    fn new(range: &SectorRange) -> Self {
+        Self {
+            track_index: range.track_index,
+            start: range.byte_start,
+            end: range.byte_end().unwrap_or(u64::MAX),
+            size: 0,
+            crc32: crc32fast::Hasher::new(),
+            md5: Md5::new(),
+            sha1: Sha1::new(),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.size += data.len() as u64;
+        self.crc32.update(data);
+        self.md5.update(data);
+        self.sha1.update(data);
+    }
+
+    fn finish(self) -> TrackHashes {
+        TrackHashes {
+            track_index: self.track_index,
+            size: self.size,
+            crc32: self.crc32.finalize(),
+            md5: self.md5.finalize().into(),
+            sha1: self.sha1.finalize().into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_track_regions() {
+        let cue = Cue::from_str(
+            "FILE \"disc.bin\" BINARY\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 00:00:01\n",
+        )
+        .unwrap();
+
+        let mut data = vec![0; 2352];
+        data.extend_from_slice(b"123456789");
+
+        let hashes = track_hashes(&cue, |_| Ok(&data[..])).unwrap();
+
+        assert_eq!(hashes[0].size, 2352);
+        assert_eq!(hashes[1].size, 9);
+        assert_eq!(hashes[1].crc32, 0xCBF43926);
+        assert_eq!(
+            hashes[1].md5,
+            [
+                0x25, 0xf9, 0xe7, 0x94, 0x32, 0x3b, 0x45, 0x38, 0x85, 0xf5, 0x18, 0x1f, 0x1b, 0x62,
+                0x4d, 0x0b
+            ]
+        );
+        assert_eq!(
+            hashes[1].sha1,
+            [
+                0xf7, 0xc3, 0xbc, 0x1d, 0x80, 0x8e, 0x04, 0x73, 0x2a, 0xdf, 0x67, 0x99, 0x65, 0xcc,
+                0xc3, 0x4c, 0xa7, 0xae, 0x34, 0x41
+            ]
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Classifying the text of a cue for syntax highlighting
+//!
+//! [`tokenize`] works line by line and never fails, so that text being edited, which often
+//! doesn't parse, can still be highlighted. For the exact structure of a cue that does, see
+//! [`SyntaxNode`](crate::SyntaxNode).
+
+use std::ops::Range;
+
+use crate::parser::COMMANDS;
+
+/// Words after a command that have a fixed meaning: track modes, file formats and flags
+const VALUES: &[&str] = &[
+    "AUDIO",
+    "CDG",
+    "MODE1/2048",
+    "MODE1/2352",
+    "MODE2/2336",
+    "MODE2/2352",
+    "CDI/2336",
+    "CDI/2352",
+    "BINARY",
+    "MOTOROLA",
+    "AIFF",
+    "WAVE",
+    "MP3",
+    "DCP",
+    "4CH",
+    "PRE",
+    "SCMS",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TokenKind {
+    /// A command, or a word with a fixed meaning such as a track mode
+    Keyword,
+    /// Quoted or unquoted text
+    String,
+    Number,
+    /// A time such as `03:45:12`
+    Time,
+    /// A `REM` line, from `REM` to the end of the line
+    Comment,
+    /// A line that doesn't start with a known command
+    Unknown,
+}
+
+/// A classified piece of a cue, with its byte range in the text. Whitespace isn't included.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub range: Range<usize>,
+}
+
+/// The tokens of a cue, in order
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        tokenize_line(line.trim_end_matches(['\r', '\n']), offset, &mut tokens);
+        offset += line.len();
+    }
+
+    tokens
+}
+
+fn tokenize_line(line: &str, offset: usize, tokens: &mut Vec<Token>) {
+    let mut words = Words { line, position: 0 };
+    let Some((command, range)) = words.next() else {
+        return;
+    };
+    let mut push = |kind, range: Range<usize>| {
+        tokens.push(Token {
+            kind,
+            range: offset + range.start..offset + range.end,
+        })
+    };
+
+    if command == "REM" || command.starts_with(';') || command.starts_with("//") {
+        push(TokenKind::Comment, range.start..line.trim_end().len());
+        return;
+    }
+
+    let kind = match COMMANDS.contains(&command) {
+        true => TokenKind::Keyword,
+        false => TokenKind::Unknown,
+    };
+
+    push(kind, range);
+
+    for (word, range) in words {
+        let kind = if word.starts_with('"') {
+            TokenKind::String
+        } else if VALUES.contains(&word) {
+            TokenKind::Keyword
+        } else if is_time(word) {
+            TokenKind::Time
+        } else if word.bytes().all(|b| b.is_ascii_digit()) {
+            TokenKind::Number
+        } else {
+            TokenKind::String
+        };
+
+        push(kind, range);
+    }
+}
+
+/// `MM:SS:FF`, or any other digits separated by colons
+fn is_time(word: &str) -> bool {
+    word.contains(':')
+        && word
+            .split(':')
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// The words of a line with their ranges, a quoted string counting as one word up to its closing
+/// quote or the end of the line
+struct Words<'a> {
+    line: &'a str,
+    position: usize,
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = (&'a str, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.line[self.position..];
+        let start = self.position + rest.find(|c| c != ' ' && c != '\t')?;
+        let rest = &self.line[start..];
+
+        let len = match rest.strip_prefix('"') {
+            Some(quoted) => quoted.find('"').map_or(rest.len(), |end| end + 2),
+            None => rest.find([' ', '\t']).unwrap_or(rest.len()),
+        };
+
+        self.position = start + len;
+
+        Some((&rest[..len], start..start + len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_tokens() {
+        let input = "REM GENRE Rock\r\nTITLE \"An Album\"\nFILE a.wav WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:02:00\nTITEL \"Unterminated\n";
+        let tokens = tokenize(input);
+        let classified: Vec<_> = tokens
+            .iter()
+            .map(|token| (token.kind, &input[token.range.clone()]))
+            .collect();
+
+        assert_eq!(
+            classified,
+            [
+                (TokenKind::Comment, "REM GENRE Rock"),
+                (TokenKind::Keyword, "TITLE"),
+                (TokenKind::String, "\"An Album\""),
+                (TokenKind::Keyword, "FILE"),
+                (TokenKind::String, "a.wav"),
+                (TokenKind::Keyword, "WAVE"),
+                (TokenKind::Keyword, "TRACK"),
+                (TokenKind::Number, "01"),
+                (TokenKind::Keyword, "AUDIO"),
+                (TokenKind::Keyword, "INDEX"),
+                (TokenKind::Number, "01"),
+                (TokenKind::Time, "00:02:00"),
+                (TokenKind::Unknown, "TITEL"),
+                (TokenKind::String, "\"Unterminated"),
+            ]
+        );
+    }
+}
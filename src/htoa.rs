@@ -0,0 +1,97 @@
+use std::{ops::Range, path::Path, path::PathBuf};
+
+use crate::{Cue, FileFormat, Frames, Track, TrackIndex, TrackMode};
+
+const SAMPLES_PER_FRAME: u64 = 588;
+const BYTES_PER_FRAME: u64 = 2352;
+
+/// Hidden track one audio (HTOA), audio stored in the pregap of track 1 that players skip over
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HiddenTrack<'a> {
+    pub file: &'a Path,
+    pub format: FileFormat,
+    pub start: Frames,
+    pub end: Frames,
+}
+
+impl HiddenTrack<'_> {
+    pub fn len(&self) -> Frames {
+        Frames::new(self.end.frames().saturating_sub(self.start.frames()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Range of stereo samples in the file's decoded 44.1kHz audio
+    pub fn sample_range(&self) -> Range<u64> {
+        u64::from(self.start) * SAMPLES_PER_FRAME..u64::from(self.end) * SAMPLES_PER_FRAME
+    }
+
+    /// Range of bytes in the file's raw 16-bit stereo PCM, excluding any container header
+    pub fn byte_range(&self) -> Range<u64> {
+        u64::from(self.start) * BYTES_PER_FRAME..u64::from(self.end) * BYTES_PER_FRAME
+    }
+
+    /// Builds a `TRACK 00` entry for a generated cue, once the hidden track has been extracted
+    /// to its own file
+    pub fn to_track(&self, path: impl Into<PathBuf>, format: FileFormat) -> Track {
+        let mut track = Track::new(0, TrackMode::Audio);
+
+        track.set_file(path, format);
+        track.indices.push(TrackIndex::new(1, Some(Frames::ZERO)));
+
+        track
+    }
+}
+
+impl Cue {
+    /// Detects audio before `INDEX 01` of the first track, starting at its `INDEX 00` (or the
+    /// start of the file if it has none) in the same file.
+    ///
+    /// This is purely structural: a pregap holding only silence is reported as well.
+    pub fn hidden_track(&self) -> Option<HiddenTrack<'_>> {
+        let track = self.tracks.first()?;
+
+        if track.mode != TrackMode::Audio {
+            return None;
+        }
+
+        let start = track
+            .index(0)
+            .map_or(Some(Frames::ZERO), TrackIndex::time)?;
+        let end = track.start()?;
+        let file = track.file.as_deref()?;
+
+        (start < end).then_some(HiddenTrack {
+            file,
+            format: track.format.clone(),
+            start,
+            end,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_hidden_track() {
+        let cue = Cue::from_str(
+            "FILE \"disc.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 00 00:00:00\nINDEX 01 00:32:00\nTRACK 02 AUDIO\nINDEX 01 04:00:00\n",
+        )
+        .unwrap();
+
+        let hidden = cue.hidden_track().unwrap();
+
+        assert_eq!(hidden.file, Path::new("disc.wav"));
+        assert_eq!(hidden.len(), Frames::from_msf(0, 32, 0).unwrap());
+        assert_eq!(hidden.sample_range(), 0..32 * 44100);
+
+        let track = hidden.to_track("00 - Hidden Track.wav", FileFormat::Wave);
+
+        assert_eq!(track.track_index, 0);
+        assert_eq!(track.start(), Some(Frames::new(0)));
+    }
+}
@@ -0,0 +1,95 @@
+use std::{fmt, str::FromStr};
+
+use crate::{InvalidValue, Track};
+
+/// An International Standard Recording Code, such as `USABC9900001`: a country code, the code of
+/// the registrant, the year of reference, and the recording's designation code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Isrc([u8; 12]);
+
+impl Isrc {
+    /// The ISO 3166 country code, such as `US`
+    pub fn country(&self) -> &str {
+        self.part(0..2)
+    }
+
+    pub fn registrant(&self) -> &str {
+        self.part(2..5)
+    }
+
+    /// The last two digits of the year the code was assigned
+    pub fn year(&self) -> u8 {
+        self.part(5..7).parse().unwrap()
+    }
+
+    pub fn designation(&self) -> u32 {
+        self.part(7..12).parse().unwrap()
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.part(0..12)
+    }
+
+    fn part(&self, range: std::ops::Range<usize>) -> &str {
+        // only ASCII is ever stored
+        std::str::from_utf8(&self.0[range]).unwrap()
+    }
+}
+
+/// Parses an ISRC with or without the hyphens it's often displayed with, as in
+/// `US-ABC-99-00001`, and in either case
+impl FromStr for Isrc {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0; 12];
+        let mut len = 0;
+
+        for byte in s.bytes().filter(|b| *b != b'-') {
+            *bytes.get_mut(len).ok_or(InvalidValue::Isrc)? = byte.to_ascii_uppercase();
+            len += 1;
+        }
+
+        let valid = len == 12
+            && bytes[..2].iter().all(u8::is_ascii_uppercase)
+            && bytes[2..5].iter().all(u8::is_ascii_alphanumeric)
+            && bytes[5..].iter().all(u8::is_ascii_digit);
+
+        if valid {
+            Ok(Self(bytes))
+        } else {
+            Err(InvalidValue::Isrc)
+        }
+    }
+}
+
+impl fmt::Display for Isrc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Track {
+    /// The `ISRC`, if it's a valid one
+    pub fn isrc_code(&self) -> Option<Isrc> {
+        self.isrc.as_deref()?.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isrc_parts() {
+        let isrc: Isrc = "us-abc-99-00001".parse().unwrap();
+
+        assert_eq!(isrc.country(), "US");
+        assert_eq!(isrc.registrant(), "ABC");
+        assert_eq!(isrc.year(), 99);
+        assert_eq!(isrc.designation(), 1);
+        assert_eq!(isrc.to_string(), "USABC9900001");
+        assert_eq!("USABC990000".parse::<Isrc>(), Err(InvalidValue::Isrc));
+        assert_eq!("1SABC9900001".parse::<Isrc>(), Err(InvalidValue::Isrc));
+    }
+}
@@ -0,0 +1,433 @@
+use std::path::Path;
+
+use crate::{convert::ConvertError, Cue, Frames, Lba, TrackFlags, TrackMode};
+
+/// The track number of the lead-out in a TOC
+const LEADOUT_TRACK: u8 = 0xAA;
+
+/// Between the sessions of a multisession disc: the first session's lead-out, the next one's
+/// lead-in, and the pregap of its first track
+const SESSION_GAP: u32 = 11_400;
+
+/// Where everything on a disc is, in logical block addresses (LBAs): sectors counted from the
+/// start of track 1, after the 2 second pregap every disc begins with. Built by
+/// [`DiscLayout::new`] from a cue and the lengths of its files, for disc IDs, TOCs and burning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscLayout {
+    pub sessions: Vec<SessionLayout>,
+    /// Where the lead-out of the last session starts
+    pub leadout: u32,
+}
+
+/// Where the offsets of [`DiscLayout::track_offsets`] count from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetOrigin {
+    /// LBA 0, the start of track 1 on most discs, as times in a cue do
+    Lba,
+    /// MSF 00:00:00, before the 2 second pregap of track 1, as drives' TOCs and disc IDs do
+    DiscStart,
+}
+
+impl OffsetOrigin {
+    fn offset(self, lba: u32) -> u32 {
+        match self {
+            Self::Lba => lba,
+            Self::DiscStart => lba + Lba::LEAD_IN,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionLayout {
+    pub number: u8,
+    pub tracks: Vec<TrackLayout>,
+    /// Where the session's lead-out starts, the end of its last track
+    pub leadout: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackLayout {
+    pub number: u8,
+    pub mode: TrackMode,
+    pub flags: TrackFlags,
+    /// The gap before `INDEX 01`, both stored from `INDEX 00` and added by `PREGAP`. Track 1's
+    /// includes the 2 second pregap every disc starts with.
+    pub pregap: Frames,
+    /// Every timed index, in order of number
+    pub indices: Vec<IndexPoint>,
+}
+
+impl TrackLayout {
+    /// Where `INDEX 01` is
+    pub fn start(&self) -> u32 {
+        self.indices
+            .iter()
+            .find(|index| index.number == 1)
+            .map_or(0, |index| index.lba)
+    }
+
+    /// The control nibble of the track's subchannel Q, from its flags and whether it's data
+    pub fn control(&self) -> u8 {
+        let mut control = 0;
+
+        for (flag, bit) in [
+            (TrackFlags::PRE_EMPHASIS_ENABLED, 0x1),
+            (TrackFlags::DIGITAL_COPY_PERMITTED, 0x2),
+            (TrackFlags::FOUR_CHANNEL, 0x8),
+        ] {
+            if self.flags.contains(flag) {
+                control |= bit;
+            }
+        }
+
+        if !matches!(self.mode, TrackMode::Audio | TrackMode::Cdg) {
+            control |= 0x4;
+        }
+
+        control
+    }
+}
+
+/// A track descriptor as a drive returns for the READ TOC command, in format 0, from
+/// [`DiscLayout::to_toc_entries`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TocEntry {
+    /// The track number, or `0xAA` for the lead-out
+    pub track: u8,
+    /// The kind of information in subchannel Q, 1 for positions
+    pub adr: u8,
+    /// Whether the track is data, allows copying, has pre-emphasis or four channels
+    pub control: u8,
+    pub start: Lba,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IndexPoint {
+    pub number: usize,
+    pub lba: u32,
+}
+
+impl DiscLayout {
+    /// Lays out the cue's files end to end, with `file_length` giving the length of each, from a
+    /// map or by probing the audio. The silence `PREGAP` and `POSTGAP` add is counted, as a
+    /// burner writes it, and so is the gap between sessions. A single file image with a known
+    /// lead-out doesn't need its length. Tracks numbered above 99, or starting before the track
+    /// before them, can't be on a disc and are rejected.
+    ///
+    /// Some tools write the pregap before track 1 as a `PREGAP 00:02:00`. As every disc has
+    /// it before LBA 0, only a longer one moves the tracks.
+    pub fn new(
+        cue: &Cue,
+        mut file_length: impl FnMut(&Path) -> Option<Frames>,
+    ) -> Result<Self, ConvertError> {
+        let files: Vec<_> = cue.tracks_by_file().collect();
+        let mut length_of = |path: Option<&Path>| {
+            path.and_then(&mut file_length)
+                .map(Frames::frames)
+                .ok_or_else(|| {
+                    ConvertError::UnknownFileLength(path.unwrap_or(Path::new("")).into())
+                })
+        };
+
+        // where each track's file starts, laying the files before it end to end
+        let mut file_starts = Vec::with_capacity(cue.tracks.len());
+        let mut end = 0;
+
+        for (i, &(path, tracks)) in files.iter().enumerate() {
+            file_starts.extend(std::iter::repeat_n(end, tracks.len()));
+
+            if i + 1 < files.len() {
+                end += length_of(path)?;
+            }
+        }
+
+        end += match (files.last(), cue.leadout) {
+            (None, _) => 0,
+            (Some(_), Some(leadout)) if files.len() == 1 => leadout.frames(),
+            (Some(&(path, _)), None) if files.len() == 1 => {
+                length_of(path).map_err(|_| ConvertError::MissingLeadout)?
+            }
+            (Some(&(path, _)), _) => length_of(path)?,
+        };
+
+        let mut sessions = Vec::new();
+        let mut position = 0;
+        let mut previous_start = 0;
+        // silence and session gaps added so far
+        let mut added = 0;
+
+        for (i, session) in cue.sessions().into_iter().enumerate() {
+            if i > 0 {
+                added += SESSION_GAP;
+            }
+
+            let mut tracks = Vec::with_capacity(session.tracks.len());
+
+            for track in session.tracks {
+                if track.track_index > 99 {
+                    return Err(ConvertError::TrackNumberTooLarge(track.track_index));
+                }
+
+                let mut pregap = track.pregap.map_or(0, Frames::frames);
+
+                if position == 0 {
+                    added += pregap.saturating_sub(Lba::LEAD_IN);
+                    pregap = pregap.max(Lba::LEAD_IN);
+                } else {
+                    added += pregap;
+                }
+
+                let mut indices: Vec<_> = track
+                    .indices
+                    .iter()
+                    .filter_map(|index| {
+                        Some(IndexPoint {
+                            number: index.index(),
+                            lba: file_position(cue, &file_starts, position, index.index())? + added,
+                        })
+                    })
+                    .collect();
+                indices.sort_by_key(|index| index.number);
+
+                let start = indices
+                    .iter()
+                    .find(|index| index.number == 1)
+                    .ok_or(ConvertError::MissingIndex(track.track_index))?
+                    .lba;
+
+                if start < previous_start {
+                    return Err(ConvertError::TrackBeforePrevious(track.track_index));
+                }
+
+                previous_start = start;
+
+                let stored = indices
+                    .first()
+                    .filter(|index| index.number == 0)
+                    .map_or(0, |index| start.saturating_sub(index.lba));
+
+                tracks.push(TrackLayout {
+                    number: track.track_index,
+                    mode: track.mode,
+                    flags: track.flags,
+                    pregap: Frames::try_from(pregap + stored)
+                        .map_err(|_| ConvertError::TooLong(track.track_index))?,
+                    indices,
+                });
+
+                added += track.postgap.map_or(0, Frames::frames);
+                position += 1;
+            }
+
+            // a session ends where the next one's first track starts, or the disc does
+            let next = cue.tracks.get(position).and_then(|next| {
+                next.indices
+                    .iter()
+                    .filter_map(|index| file_position(cue, &file_starts, position, index.index()))
+                    .min()
+            });
+
+            let leadout = next.unwrap_or(end) + added;
+
+            if let Some(last) = tracks.last().filter(|last| last.start() > leadout) {
+                return Err(ConvertError::LeadoutBeforeTrack(last.number));
+            }
+
+            sessions.push(SessionLayout {
+                number: session.number,
+                tracks,
+                leadout,
+            });
+        }
+
+        Ok(Self {
+            leadout: end + added,
+            sessions,
+        })
+    }
+
+    /// Every track on the disc, in order
+    pub fn tracks(&self) -> impl Iterator<Item = &TrackLayout> {
+        self.sessions.iter().flat_map(|session| &session.tracks)
+    }
+
+    /// Where each track's `INDEX 01` is, counting from `origin`
+    pub fn track_offsets(&self, origin: OffsetOrigin) -> Vec<u32> {
+        self.tracks()
+            .map(|track| origin.offset(track.start()))
+            .collect()
+    }
+
+    /// The TOC a drive would read from the burned disc, each track then the lead-out, to compare
+    /// against one read from a drive. The lead-out has the control of the last track.
+    pub fn to_toc_entries(&self) -> Vec<TocEntry> {
+        let mut entries: Vec<_> = self
+            .tracks()
+            .map(|track| TocEntry {
+                track: track.number,
+                adr: 1,
+                control: track.control(),
+                start: Lba(track.start() as i32),
+            })
+            .collect();
+
+        entries.push(TocEntry {
+            track: LEADOUT_TRACK,
+            adr: 1,
+            control: entries.last().map_or(0, |entry| entry.control),
+            start: Lba(self.leadout as i32),
+        });
+
+        entries
+    }
+
+    /// Where the lead-out is, counting from `origin`
+    pub fn leadout_offset(&self, origin: OffsetOrigin) -> u32 {
+        origin.offset(self.leadout)
+    }
+}
+
+/// Where an index of the track at `position` is, with the files laid end to end. An `INDEX 00`
+/// before a track's first `FILE`, at the end of the file before, is counted from that file.
+fn file_position(cue: &Cue, file_starts: &[u32], position: usize, number: usize) -> Option<u32> {
+    let track = cue.tracks.get(position)?;
+    let time = track.index(number)?.time()?;
+
+    let file_start = if number == 0 && gap_in_previous_file(cue, position) {
+        file_starts[position - 1]
+    } else {
+        file_starts[position]
+    };
+
+    Some(file_start + time.frames())
+}
+
+/// Whether the `INDEX 00` of the track at `position` is at the end of the file before, with the
+/// track's `FILE` between it and `INDEX 01`, as EAC writes gaps appended to the previous track
+pub(crate) fn gap_in_previous_file(cue: &Cue, position: usize) -> bool {
+    let Some(track) = cue.tracks.get(position) else {
+        return false;
+    };
+    let Some((gap, start)) = track
+        .index(0)
+        .and_then(|index| index.time())
+        .zip(track.start())
+    else {
+        return false;
+    };
+
+    position > 0
+        && cue.tracks[position - 1].file != track.file
+        && (start == Frames::ZERO || gap > start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lays_out_discs() {
+        let cue = Cue::from_str(
+            "FILE \"01.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 00 02:58:00\nFILE \"02.wav\" WAVE\nINDEX 01 00:00:00\nPOSTGAP 00:01:00\nREM SESSION 02\nFILE \"data.bin\" BINARY\nTRACK 03 MODE2/2352\nPREGAP 00:02:00\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        let length = |path: &Path| {
+            let seconds = match path.to_str()? {
+                "01.wav" => 180,
+                "02.wav" => 120,
+                _ => 60,
+            };
+
+            Some(Frames::new(seconds * 75))
+        };
+
+        let layout = DiscLayout::new(&cue, length).unwrap();
+        let starts: Vec<_> = layout.tracks().map(TrackLayout::start).collect();
+
+        assert_eq!(starts, [0, 180 * 75, 301 * 75 + 150 + SESSION_GAP]);
+        assert_eq!(layout.tracks().nth(1).unwrap().pregap, Frames::PREGAP_2S);
+        assert_eq!(layout.sessions[0].leadout, 301 * 75);
+        assert_eq!(layout.leadout, 363 * 75 + SESSION_GAP);
+        assert_eq!(layout.tracks().nth(2).unwrap().pregap, Frames::PREGAP_2S);
+        assert_eq!(
+            DiscLayout::new(&cue, |_| None),
+            Err(ConvertError::UnknownFileLength("01.wav".into()))
+        );
+    }
+
+    #[test]
+    fn first_track_pregap() {
+        let layout = |pregap| {
+            let cue = Cue::from_str(format!(
+                "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nPREGAP {pregap}\nINDEX 01 00:00:00\nREM LEAD-OUT 01:00:00\n"
+            ))
+            .unwrap();
+
+            DiscLayout::new(&cue, |_| None).unwrap()
+        };
+
+        let standard = layout("00:02:00");
+
+        assert_eq!(standard.track_offsets(OffsetOrigin::Lba), [0]);
+        assert_eq!(standard.track_offsets(OffsetOrigin::DiscStart), [150]);
+        assert_eq!(standard.tracks().next().unwrap().pregap, Frames::PREGAP_2S);
+
+        let extended = layout("00:05:00");
+
+        assert_eq!(extended.track_offsets(OffsetOrigin::DiscStart), [375]);
+        assert_eq!(extended.leadout_offset(OffsetOrigin::Lba), 4725);
+
+        let early = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 01:00:00\nREM LEAD-OUT 00:30:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            DiscLayout::new(&early, |_| None),
+            Err(ConvertError::LeadoutBeforeTrack(1))
+        );
+
+        let long = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nPREGAP 90:00:00\nINDEX 00 00:00:00\nINDEX 01 50:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            DiscLayout::new(&long, |_| Some(Frames::from_msf(60, 0, 0).unwrap())),
+            Err(ConvertError::TooLong(2))
+        );
+    }
+
+    #[test]
+    fn toc_entries() {
+        let cue = Cue::from_str(
+            "FILE \"disc.bin\" BINARY\nTRACK 01 MODE1/2352\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nFLAGS DCP PRE\nINDEX 01 10:00:00\nREM LEAD-OUT 20:00:00\n",
+        )
+        .unwrap();
+        let entries = DiscLayout::new(&cue, |_| None).unwrap().to_toc_entries();
+
+        assert_eq!(
+            entries,
+            [
+                TocEntry {
+                    track: 1,
+                    adr: 1,
+                    control: 0x4,
+                    start: Lba(0)
+                },
+                TocEntry {
+                    track: 2,
+                    adr: 1,
+                    control: 0x3,
+                    start: Lba(45_000)
+                },
+                TocEntry {
+                    track: 0xAA,
+                    adr: 1,
+                    control: 0x3,
+                    start: Lba(90_000)
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+use crate::Frames;
+
+/// A logical block address, the number of a sector counted from LBA 0 where the program area
+/// starts, usually track 1's `INDEX 01`. The 2 second pregap before it has negative addresses.
+///
+/// Times in a cue are [`Frames`] from the start of the audio, which are LBAs. The MSF times in
+/// a disc's TOC and subchannels are absolute, counted from before the pregap, so they're 150
+/// frames ahead: LBA 0 is MSF 00:02:00. Mixing the two up is the usual cause of disc IDs and
+/// TOCs off by 150.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lba(pub i32);
+
+impl Lba {
+    /// Sectors between absolute MSF 00:00:00 and LBA 0
+    pub const LEAD_IN: u32 = Frames::PREGAP_2S.frames();
+
+    /// The address of a time in the audio, such as an `INDEX` time of a single file image
+    pub const fn from_frames(frames: Frames) -> Self {
+        Self(frames.frames() as i32)
+    }
+
+    /// The time in the audio at this address, `None` in the pregap before LBA 0
+    pub fn to_frames(self) -> Option<Frames> {
+        u32::try_from(self.0).ok()?.try_into().ok()
+    }
+
+    /// The address of an absolute MSF time, as read from a TOC, or `None` if it's too far out to
+    /// be one
+    pub fn from_msf(m: u32, s: u32, f: u32) -> Option<Self> {
+        let absolute = m
+            .checked_mul(60)?
+            .checked_add(s)?
+            .checked_mul(75)?
+            .checked_add(f)?;
+
+        Some(Self(i32::try_from(absolute).ok()? - Self::LEAD_IN as i32))
+    }
+
+    /// The absolute MSF time of the address, as written in a TOC. `None` before MSF 00:00:00.
+    pub fn to_msf(self) -> Option<(u32, u32, u32)> {
+        let absolute = self.absolute()?;
+
+        Some((absolute / 75 / 60, absolute / 75 % 60, absolute % 75))
+    }
+
+    /// Sectors from MSF 00:00:00, the offsets disc IDs such as CDDB's and MusicBrainz's are
+    /// computed from
+    pub fn absolute(self) -> Option<u32> {
+        self.0
+            .checked_add(Self::LEAD_IN as i32)
+            .and_then(|absolute| u32::try_from(absolute).ok())
+    }
+}
+
+impl From<Frames> for Lba {
+    fn from(frames: Frames) -> Self {
+        Self::from_frames(frames)
+    }
+}
+
+impl fmt::Display for Lba {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_addresses() {
+        let start = Lba::from(Frames::from_msf(3, 0, 0).unwrap());
+
+        assert_eq!(start, Lba(13_500));
+        assert_eq!(start.to_msf(), Some((3, 2, 0)));
+        assert_eq!(Lba::from_msf(3, 2, 0), Some(start));
+        assert_eq!(start.absolute(), Some(13_650));
+        assert_eq!(Lba::from_msf(0, 0, 0), Some(Lba(-150)));
+        assert_eq!(Lba::from_msf(u32::MAX, 0, 0), None);
+        assert_eq!(Lba(-150).to_frames(), None);
+        assert_eq!(Lba(-151).to_msf(), None);
+    }
+}
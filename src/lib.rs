@@ -1,46 +1,335 @@
-#![forbid(unsafe_code)]
-
-use std::{path::PathBuf, time::Duration};
-
+#![cfg_attr(
+    not(any(
+        feature = "capi",
+        feature = "wasm",
+        feature = "python",
+        feature = "uniffi",
+        feature = "node"
+    )),
+    forbid(unsafe_code)
+)]
+#![cfg_attr(
+    any(
+        feature = "capi",
+        feature = "wasm",
+        feature = "python",
+        feature = "uniffi",
+        feature = "node"
+    ),
+    deny(unsafe_code)
+)]
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use smallvec::SmallVec;
+
+pub mod accuraterip;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+pub mod burn;
+mod capacity;
+#[cfg(feature = "capi")]
+#[allow(unsafe_code)]
+pub mod capi;
+mod catalog;
+mod comments;
+pub mod convert;
+pub mod ddp;
+mod diagnostic;
+mod diff;
+pub mod discid;
+mod duration;
+mod edit;
+mod encoding;
+mod error;
+mod filename;
+mod fix;
+mod gaps;
+pub mod generate;
+#[cfg(feature = "hash")]
+pub mod hash;
+pub mod highlight;
+mod htoa;
+mod isrc;
+mod layout;
+mod lba;
+mod lint;
+mod merge;
+#[cfg(feature = "uniffi")]
+#[allow(unsafe_code)]
+pub mod mobile;
+#[cfg(feature = "node")]
+#[allow(unsafe_code)]
+pub mod node;
+mod options;
+mod order;
 mod parser;
-
-#[derive(Debug, Clone, Default)]
+mod playback;
+#[cfg(feature = "symphonia")]
+pub mod player;
+#[cfg(feature = "python")]
+#[allow(unsafe_code)]
+pub mod python;
+mod replaygain;
+mod resolve;
+#[cfg(feature = "schemars")]
+mod schema;
+mod sectors;
+mod seek;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod sessions;
+mod span;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+mod style;
+#[cfg(feature = "lofty")]
+pub mod tag;
+mod tag_map;
+mod template;
+mod timeline;
+mod tokens;
+#[cfg(feature = "toml")]
+mod toml_impls;
+mod tracks;
+mod unicode;
+mod utf16;
+mod validate;
+mod warning;
+#[cfg(feature = "wasm")]
+#[allow(unsafe_code)]
+pub mod wasm;
+mod writer;
+#[cfg(feature = "yaml")]
+mod yaml_impls;
+
+pub use capacity::DiscCapacity;
+pub use catalog::Catalog;
+pub use comments::{CommentPosition, CueCommand};
+pub use diagnostic::{Diagnostic, Severity};
+pub use diff::{CueChange, DiffOptions};
+pub use duration::TrackDuration;
+pub use edit::{CueEdit, CueField, TrackEdit, TrackField};
+pub use encoding::decode;
+pub use error::{Error, Location, ParseError, ParseErrorKind};
+pub use filename::FilenameOptions;
+pub use fix::{Repair, RepairKind};
+pub use gaps::TrackGap;
+pub use htoa::HiddenTrack;
+pub use isrc::Isrc;
+pub use layout::{DiscLayout, IndexPoint, OffsetOrigin, SessionLayout, TocEntry, TrackLayout};
+pub use lba::Lba;
+pub use lint::{Lint, LintKind, LintProfile};
+pub use merge::MergeError;
+pub use options::{MsfOverflow, ParseOptions};
+pub use parser::{CueParserHandle, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TrackIter};
+pub use playback::VirtualTrack;
+pub use replaygain::ReplayGain;
+pub use resolve::{find_file, FileMatch, MatchKind};
+#[cfg(feature = "schemars")]
+pub use schema::json_schema;
+pub use sectors::SectorRange;
+pub use seek::SeekPoint;
+#[cfg(feature = "serde")]
+pub use serde_impls::msf as serde_msf;
+pub use sessions::{DiscKind, Session};
+pub use span::FrameSpan;
+pub use style::{CueStyle, FileLayout, GapStyle};
+pub use template::{Template, TemplateError};
+pub use timeline::{TimelineEvent, TimelineEventKind};
+pub use tracks::TooManyTracks;
+#[cfg(feature = "unicode-normalization")]
+pub use unicode::NormalizationForm;
+pub use unicode::TextNormalization;
+pub use utf16::{Utf16Position, Utf16Range};
+pub use validate::{InvalidValue, MAX_TEXT_LENGTH};
+pub use warning::{ParseOutcome, Warning, WarningKind};
+
+// Has to be at the root, where the scaffolding for `mobile` looks for it
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!("cueparse");
+
+/// Cues are equal when every field is, including comments, which often hold metadata such as
+/// `REM DISCID`. Use [`Cue::diff`] to see what differs.
+///
+/// New fields may be added in minor versions, so outside this crate a cue is built with
+/// [`Cue::new`] or [`Default`] and its fields set afterwards.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Cue {
-    pub catalog: Option<String>,
+    pub catalog: Option<Catalog>,
     pub cd_text_file: Option<PathBuf>,
     pub path: Option<PathBuf>,
     pub format: FileFormat,
-    pub performer: Option<String>,
-    pub songwriter: Option<String>,
-    pub arranger: Option<String>,
+    pub performer: Option<Arc<str>>,
+    pub songwriter: Option<Arc<str>>,
+    pub arranger: Option<Arc<str>>,
     pub title: Option<String>,
     pub tracks: Vec<Track>,
     pub comments: Vec<String>,
+    /// What the global `comments` came before, for those that weren't last in the section
+    pub comment_positions: Vec<CommentPosition>,
+    /// Start of the lead-out, i.e. the length of the disc. Read from `REM LEAD-OUT` or a final
+    /// dummy track when present, but can also be set from an external source such as a rip log.
+    pub leadout: Option<Frames>,
+    /// `REM GENRE`, as written by most rippers
+    pub genre: Option<String>,
+    /// `REM DATE`, usually just the year
+    pub date: Option<String>,
+    /// `REM DISCNUMBER`, the disc's position in a set of several
+    pub disc_number: Option<u8>,
+    /// `REM TOTALDISCS`
+    pub total_discs: Option<u8>,
+    /// The album's `REM REPLAYGAIN_ALBUM_GAIN` and `REM REPLAYGAIN_ALBUM_PEAK`
+    pub replay_gain: Option<ReplayGain>,
 }
 
 impl Cue {
+    /// A cue of the given tracks, with nothing else set
+    pub fn new(tracks: Vec<Track>) -> Self {
+        Self {
+            tracks,
+            ..Default::default()
+        }
+    }
+
+    /// Parses a cue sheet. One without global commands, whose first `TRACK` may be indented, or
+    /// with no commands at all, is accepted, as [writing](std::fmt::Display) such a cue gives.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: impl AsRef<str>) -> Result<Self, Error> {
-        parser::parse_cue(input)
+        parser::parse_cue(input, &ParseOptions::default())
+    }
+
+    pub fn from_str_with(input: impl AsRef<str>, options: &ParseOptions) -> Result<Self, Error> {
+        parser::parse_cue(input, options)
+    }
+
+    /// Parses a cue, also returning the problems that were worked around. Most are only worked
+    /// around with [`ParseOptions::lenient`], and are errors otherwise.
+    pub fn from_str_with_warnings(
+        input: impl AsRef<str>,
+        options: &ParseOptions,
+    ) -> Result<ParseOutcome, Error> {
+        parser::parse_outcome(input, options)
+    }
+
+    /// Parses the global section of a cue up front, then its tracks one at a time as the returned
+    /// iterator is advanced. Iteration stops after the first error.
+    pub fn parse_tracks_iter(input: &str) -> Result<TrackIter<'_>, Error> {
+        TrackIter::new(input, &ParseOptions::default())
+    }
+
+    /// Longest input accepted by [`Cue::from_bytes`], far longer than any real cue
+    pub const MAX_UNTRUSTED_LEN: usize = 1024 * 1024;
+
+    /// Parses a cue from bytes that may not be a cue at all, such as an upload or a fuzzer's
+    /// input, detecting the text encoding. This never panics, and fails on input longer than
+    /// [`Cue::MAX_UNTRUSTED_LEN`] without reading it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let options = ParseOptions {
+            max_input_len: Some(Self::MAX_UNTRUSTED_LEN),
+            ..Default::default()
+        };
+
+        parser::parse_bytes(bytes, &options)
+    }
+
+    /// Reads and parses a cue file, detecting its text encoding
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let bytes = std::fs::read(path)?;
+
+        parser::parse_cue(encoding::decode(&bytes), &ParseOptions::default())
+    }
+
+    /// Length of the disc, if the lead-out is known
+    pub fn duration(&self) -> Option<Frames> {
+        self.leadout
+    }
+
+    /// Length of a track from its `INDEX 01` to the next track's, or to the lead-out for the last
+    /// track of a single file image. Tracks in different files can't be measured, as the file
+    /// lengths aren't known.
+    pub fn track_length(&self, track_index: u8) -> Option<Frames> {
+        self.track_span(track_index)
+            .filter(|span| span.end >= span.start)
+            .map(|span| span.len())
+    }
+
+    /// Looks up a track by its number
+    pub fn track(&self, track_index: u8) -> Option<&Track> {
+        self.track_position(track_index).map(|i| &self.tracks[i])
+    }
+
+    /// Groups consecutive tracks stored in the same file, in order
+    pub fn tracks_by_file(&self) -> impl Iterator<Item = (Option<&Path>, &[Track])> {
+        self.tracks
+            .chunk_by(|a, b| a.file == b.file)
+            .map(|tracks| (tracks[0].file.as_deref(), tracks))
+    }
+
+    /// Tracks are almost always numbered consecutively, so the number usually gives the position
+    /// directly. Otherwise they're at least in order, and only a malformed cue needs a scan.
+    fn track_position(&self, track_index: u8) -> Option<usize> {
+        let first = self.tracks.first()?.track_index;
+        let guess = usize::from(track_index.wrapping_sub(first));
+
+        if self
+            .tracks
+            .get(guess)
+            .is_some_and(|track| track.track_index == track_index)
+        {
+            return Some(guess);
+        }
+
+        self.tracks
+            .binary_search_by_key(&track_index, |track| track.track_index)
+            .ok()
+            .or_else(|| {
+                self.tracks
+                    .iter()
+                    .position(|track| track.track_index == track_index)
+            })
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// Like [`Cue`], tracks are equal when every field is, including comments, and are built with
+/// [`Track::new`] as fields may be added
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Track {
     pub track_index: u8,
-    pub indices: Vec<TrackIndex>,
+    /// Stored inline, as tracks rarely have more than an `INDEX 00` and `INDEX 01`
+    indices: SmallVec<[TrackIndex; 2]>,
     pub time: Option<Frames>,
     pub flags: TrackFlags,
     pub mode: TrackMode,
     pub file: Option<PathBuf>,
     pub format: FileFormat,
-    pub performer: Option<String>,
-    pub songwriter: Option<String>,
+    pub performer: Option<Arc<str>>,
+    pub songwriter: Option<Arc<str>>,
     pub title: Option<String>,
     pub isrc: Option<String>,
     pub pregap: Option<Frames>,
     pub postgap: Option<Frames>,
-    pub comments: Vec<String>,
-    pub arranger: Option<String>,
+    comments: SmallVec<[String; 2]>,
+    /// What the track's `comments` came before, for those that weren't last in the track
+    pub comment_positions: Vec<CommentPosition>,
+    pub arranger: Option<Arc<str>>,
+    /// The disc the track is from, in a cue [merged](Cue::merge) from several. Read from and
+    /// written as a `REM DISCNUMBER` inside the track.
+    pub disc_number: Option<u8>,
+    /// `REM REPLAYGAIN_TRACK_GAIN` and `REM REPLAYGAIN_TRACK_PEAK`
+    pub replay_gain: Option<ReplayGain>,
 }
 
 impl Track {
@@ -56,9 +345,54 @@ impl Track {
         self.file = Some(path.into());
         self.format = format;
     }
+
+    /// The track's `INDEX` entries, in the order they were read or added
+    pub fn indices(&self) -> &[TrackIndex] {
+        &self.indices
+    }
+
+    pub fn indices_mut(&mut self) -> &mut [TrackIndex] {
+        &mut self.indices
+    }
+
+    pub fn push_index(&mut self, index: TrackIndex) {
+        self.indices.push(index);
+    }
+
+    pub fn set_indices(&mut self, indices: impl IntoIterator<Item = TrackIndex>) {
+        self.indices = indices.into_iter().collect();
+    }
+
+    /// The `REM` comments of the track, without the `REM`
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// Adds a comment after the track's other commands
+    pub fn push_comment(&mut self, comment: impl Into<String>) {
+        self.comments.push(comment.into());
+    }
+
+    /// Replaces the track's comments, which are then written after its other commands as
+    /// `comment_positions` is cleared
+    pub fn set_comments(&mut self, comments: impl IntoIterator<Item = String>) {
+        self.comments = comments.into_iter().collect();
+        self.comment_positions.clear();
+    }
+
+    /// Looks up the `INDEX` entry with the given number
+    pub fn index(&self, number: usize) -> Option<&TrackIndex> {
+        self.indices.iter().find(|index| index.index == number)
+    }
+
+    /// Start of the track proper (`INDEX 01`) within its file
+    pub fn start(&self) -> Option<Frames> {
+        self.index(1).and_then(TrackIndex::time)
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Serialized as its name in a cue, e.g. `"WAVE"`, or `"UNSPECIFIED"`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum FileFormat {
     #[default]
     Unspecified,
@@ -67,6 +401,8 @@ pub enum FileFormat {
     Aiff,
     Wave,
     Mp3,
+    /// A format the cue sheet specification doesn't have, such as `FLAC`, as written
+    Other(String),
 }
 
 bitflags::bitflags! {
@@ -80,7 +416,7 @@ bitflags::bitflags! {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum TrackMode {
     #[default]
     Audio,
@@ -93,32 +429,91 @@ pub enum TrackMode {
     Cdi_2352,
 }
 
-#[derive(Debug, Clone)]
+impl TrackMode {
+    /// Size in bytes of one sector of this mode, as stored in a disc image
+    pub fn sector_size(self) -> usize {
+        use TrackMode::*;
+
+        match self {
+            Audio | Mode1_2352 | Mode2_2352 | Cdi_2352 => 2352,
+            Cdg => 2448,
+            Mode1_2048 => 2048,
+            Mode2_2336 | Cdi_2336 => 2336,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TrackIndex {
     index: usize,
     time: Option<Frames>,
 }
 
+impl TrackIndex {
+    pub fn new(index: usize, time: Option<Frames>) -> Self {
+        Self { index, time }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn time(&self) -> Option<Frames> {
+        self.time
+    }
+}
+
 /// [`Frames`] is a struct representing a count of 1/75th of a second frames used in CDs
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Frames(usize);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u32", into = "u32"))]
+pub struct Frames(u32);
 
 impl Frames {
     // 1 frame = 1/75th of a second
     const FRAME_LENGTH_F32: f32 = 1.0 / 75.0;
     const FRAME_LENGTH_F64: f64 = 1.0 / 75.0;
 
-    pub fn new(frames: usize) -> Self {
+    pub const ZERO: Self = Self(0);
+
+    pub const SECOND: Self = Self(75);
+
+    /// The 2 second pregap before the first track of every disc, and the usual gap between
+    /// tracks
+    pub const PREGAP_2S: Self = Self(150);
+
+    /// 99:00:00, the capacity of the longest discs made, which only some burners can overburn
+    /// to. No real disc is longer.
+    pub const MAX_DISC: Self = Self(99 * 60 * 75);
+
+    /// 99:59:74, the longest time MM:SS:FF can express, well past even overburned discs
+    pub const MAX: Self = Self((99 * 60 + 59) * 75 + 74);
+
+    /// # Panics
+    ///
+    /// If `frames` is above [`Frames::MAX`]. Use `Frames::try_from` for counts that aren't known
+    /// to be in range.
+    pub const fn new(frames: u32) -> Self {
+        assert!(frames <= Self::MAX.0, "frames is above Frames::MAX");
+
         Self(frames)
     }
 
-    /// From MM:SS:FF (Minutes/Seconds/Frames) format
-    pub fn from_msf(m: usize, s: usize, f: usize) -> Self {
-        let frames = ((m * 60) + s) * 75 + f;
-        Self(frames)
+    /// From MM:SS:FF (Minutes/Seconds/Frames) format. Seconds and frames past the end of their
+    /// unit carry over into the next, but the total must be within [`Frames::MAX`].
+    pub fn from_msf(m: u32, s: u32, f: u32) -> Result<Self, FramesError> {
+        Self::try_from((u64::from(m) * 60 + u64::from(s)) * 75 + u64::from(f))
+    }
+
+    /// Number of frames in this count
+    pub const fn frames(self) -> u32 {
+        self.0
     }
 
-    fn to_msf(&self) -> (usize, usize, usize) {
+    /// To MM:SS:FF (Minutes/Seconds/Frames) format
+    pub fn to_msf(&self) -> (u32, u32, u32) {
         let mut frames = self.0;
 
         let f = frames % 75;
@@ -147,10 +542,74 @@ impl Frames {
     }
 }
 
-#[derive(Debug, Clone, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    ParseError(#[from] pest::error::Error<parser::Rule>),
+impl TryFrom<u64> for Frames {
+    type Error = FramesError;
+
+    fn try_from(frames: u64) -> Result<Self, FramesError> {
+        if frames > u64::from(Self::MAX.0) {
+            return Err(FramesError::TooLong(frames));
+        }
+
+        Ok(Self(frames as u32))
+    }
+}
+
+impl TryFrom<u32> for Frames {
+    type Error = FramesError;
+
+    fn try_from(frames: u32) -> Result<Self, FramesError> {
+        Self::try_from(u64::from(frames))
+    }
+}
+
+impl TryFrom<usize> for Frames {
+    type Error = FramesError;
+
+    fn try_from(frames: usize) -> Result<Self, FramesError> {
+        Self::try_from(frames as u64)
+    }
+}
+
+impl From<Frames> for u32 {
+    fn from(frames: Frames) -> Self {
+        frames.0
+    }
+}
+
+impl From<Frames> for u64 {
+    fn from(frames: Frames) -> Self {
+        frames.0.into()
+    }
+}
+
+/// A time that can't be represented as [`Frames`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum FramesError {
+    #[error("{0} frames is past 99:59:74, the longest time a cue can hold")]
+    TooLong(u64),
+}
+
+/// Parses many cue files, using all cores when the `rayon` feature is enabled. Results are in the
+/// same order as `paths`.
+pub fn parse_many<P: AsRef<Path> + Sync>(paths: &[P]) -> Vec<Result<Cue, Error>> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        paths
+            .par_iter()
+            .map_init(CueParserHandle::default, |parser, path| {
+                parser.parse_file(path)
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut parser = CueParserHandle::default();
+
+        paths.iter().map(|path| parser.parse_file(path)).collect()
+    }
 }
 
 #[cfg(test)]
@@ -163,7 +622,7 @@ mod tests {
 
     #[test]
     fn parse_example() {
-        let res = parse_cue(CUE_EXAMPLE);
+        let res = parse_cue(CUE_EXAMPLE, &ParseOptions::default());
 
         match res {
             Ok(ref cue) => println!("{:#?}", cue),
@@ -172,4 +631,146 @@ mod tests {
 
         assert!(res.is_ok())
     }
+
+    #[test]
+    fn structural_equality() {
+        use std::collections::HashSet;
+
+        let text = "TITLE Album\n";
+        let cue = Cue::from_str(text).unwrap();
+        let mut commented = cue.clone();
+
+        commented.comments.push("GENRE Rock".into());
+
+        assert_eq!(cue, Cue::from_str(text).unwrap());
+        assert_ne!(cue, commented);
+        assert_eq!(
+            HashSet::from([cue.clone(), Cue::from_str(text).unwrap(), commented]).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn frames_range() {
+        assert_eq!(Frames::from_msf(99, 59, 74), Ok(Frames::MAX));
+        assert_eq!(
+            Frames::from_msf(100, 0, 0),
+            Err(FramesError::TooLong(450000))
+        );
+        assert_eq!(
+            Frames::from_msf(99, 59, 75),
+            Err(FramesError::TooLong(450000))
+        );
+        assert_eq!(
+            Frames::try_from(u64::MAX),
+            Err(FramesError::TooLong(u64::MAX))
+        );
+        assert_eq!(Frames::MAX.to_msf(), (99, 59, 74));
+        assert_eq!(Frames::MAX_DISC.to_msf(), (99, 0, 0));
+        assert_eq!(Frames::PREGAP_2S, Frames::from_msf(0, 2, 0).unwrap());
+
+        assert!(Cue::from_str("TRACK 01 AUDIO\nINDEX 01 100:00:00\n").is_err());
+        assert!(Cue::from_str("TRACK 01 AUDIO\nINDEX 01 99999999999:00:00\n").is_err());
+    }
+
+    #[test]
+    fn untrusted_input() {
+        assert!(Cue::from_bytes(b"TRACK 300 AUDIO\n").is_err());
+        assert!(Cue::from_bytes(b"TRACK 01 AUDIO\nINDEX 01 99999999999:00:00\n").is_err());
+
+        let Err(Error::Parse(e)) = Cue::from_bytes(&vec![b'A'; Cue::MAX_UNTRUSTED_LEN + 1]) else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(e.kind(), ParseErrorKind::TooLarge);
+
+        // cues assembled from random pieces, checking that nothing derived from them panics
+        let pieces: [&[u8]; 14] = [
+            b"FILE \"a\" WAVE\n",
+            b"FILE b BINARY\n",
+            b"TRACK 01 AUDIO\n",
+            b"TRACK 00 MODE1/2352\n",
+            b"TRACK 255 AUDIO\n",
+            b"INDEX 00 00:00:00\n",
+            b"INDEX 01 99:59:74\n",
+            b"INDEX 01 4294967295\n",
+            b"PREGAP 00:02:00\n",
+            b"REM LEAD-OUT 00:00:01\n",
+            b"TITLE \"\xFF\xFE\"\n",
+            b"\xEF\xBB\xBF",
+            b"\r\n",
+            b"\"",
+        ];
+        let mut state: u32 = 1;
+
+        for _ in 0..2000 {
+            let mut input = b"FILE \"a\" WAVE\nTRACK 01 AUDIO\n".to_vec();
+
+            for _ in 0..8 {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                input.extend_from_slice(pieces[(state >> 16) as usize % pieces.len()]);
+            }
+
+            if let Ok(cue) = Cue::from_bytes(&input) {
+                for range in cue.sector_ranges() {
+                    let _ = range.byte_end();
+                }
+
+                for track in &cue.tracks {
+                    let _ = cue.track_length(track.track_index);
+                }
+
+                let _ = cue.hidden_track().map(|hidden| hidden.len());
+            }
+        }
+    }
+
+    #[test]
+    fn track_lookup() {
+        let cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 02 AUDIO\nINDEX 01 00:00:00\nTRACK 03 AUDIO\nINDEX 01 01:00:00\nFILE \"b.wav\" WAVE\nTRACK 05 AUDIO\nINDEX 01 00:00:00\nTRACK 01 AUDIO\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            cue.track(3).unwrap().start(),
+            Some(Frames::from_msf(1, 0, 0).unwrap())
+        );
+        assert_eq!(cue.track(5).unwrap().file, Some(PathBuf::from("b.wav")));
+        assert_eq!(cue.track(1).unwrap().track_index, 1);
+        assert!(cue.track(4).is_none());
+
+        let files: Vec<(Option<&Path>, usize)> = cue
+            .tracks_by_file()
+            .map(|(file, tracks)| (file, tracks.len()))
+            .collect();
+
+        assert_eq!(
+            files,
+            vec![(Some(Path::new("a.wav")), 2), (Some(Path::new("b.wav")), 2)]
+        );
+    }
+
+    #[test]
+    fn track_lengths() {
+        let mut cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 01:00:00\nREM LEAD-OUT 03:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(cue.track_length(1), Some(Frames::new(60 * 75)));
+        assert_eq!(cue.track_length(2), Some(Frames::new(2 * 60 * 75)));
+
+        cue.tracks[1].set_file("b.wav", FileFormat::Wave);
+
+        assert_eq!(cue.track_length(2), None);
+    }
+
+    #[test]
+    fn parse_files() {
+        let results = parse_many(&["test_files/example.cue", "test_files/missing.cue"]);
+
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(Error::Io(_))));
+    }
 }
@@ -0,0 +1,353 @@
+use std::fmt;
+
+use crate::{layout, Cue, Diagnostic, DiscCapacity, Severity, TrackMode};
+
+/// Which rules [`Cue::lint`] checks a cue against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum LintProfile {
+    /// Problems that make a cue wrong for any use
+    #[default]
+    Default,
+    /// Also the limits of the Red Book audio CD standard, for cues that will be burned
+    RedBook,
+}
+
+/// A problem with a cue that parsed, but doesn't describe a usable disc
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub kind: LintKind,
+    /// The number of the track the problem is in, if it's in one
+    pub track: Option<u8>,
+    pub message: String,
+}
+
+impl Lint {
+    /// The stable code of the lint's kind, see [`LintKind::code`]
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.track {
+            Some(track) => write!(f, "track {track:02}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LintKind {
+    /// A track has no `INDEX 01`, so where it starts isn't known
+    MissingStart,
+    /// A track's indices aren't in order of number and time. An `INDEX 00` at the end of the
+    /// file before, as EAC writes gaps appended to the previous track, is only checked by number.
+    IndicesOutOfOrder,
+    /// Tracks aren't numbered consecutively
+    TrackNumbering,
+    /// A track starts after the lead-out
+    PastLeadout,
+    /// A track is shorter than the 4 seconds the Red Book allows
+    TrackTooShort,
+    /// The disc is longer than fits on the CD, 80 minutes unless another
+    /// [capacity](DiscCapacity) is given
+    DiscTooLong,
+    /// The `CATALOG` isn't the 13 digits of a Red Book media catalog number, or its check digit
+    /// is wrong
+    Catalog,
+    /// A track isn't audio, which an audio CD can't hold
+    NotAudio,
+    /// A `TITLE`, `PERFORMER`, `SONGWRITER` or `ARRANGER` is empty, as in `PERFORMER ""`
+    EmptyValue,
+    /// A track is numbered above 99, the most a cue is meant to hold, as generated cues for
+    /// long mixes sometimes are
+    TrackNumberTooLarge,
+    /// A track starts before the track before it in the same file
+    StartBeforePrevious,
+}
+
+impl LintKind {
+    /// A code identifying the kind of lint, such as `CUE201`, which stays the same across
+    /// versions. Lints use codes from `CUE200`, above those of errors and warnings.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::MissingStart => "CUE201",
+            Self::IndicesOutOfOrder => "CUE202",
+            Self::TrackNumbering => "CUE203",
+            Self::PastLeadout => "CUE204",
+            Self::EmptyValue => "CUE205",
+            Self::TrackNumberTooLarge => "CUE206",
+            Self::StartBeforePrevious => "CUE207",
+            Self::TrackTooShort => "CUE211",
+            Self::DiscTooLong => "CUE212",
+            Self::Catalog => "CUE213",
+            Self::NotAudio => "CUE214",
+        }
+    }
+
+    /// Lints that leave a cue unusable are errors, and the rest warnings
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::MissingStart
+            | Self::IndicesOutOfOrder
+            | Self::PastLeadout
+            | Self::StartBeforePrevious => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// 4 seconds
+const MIN_TRACK_LENGTH: u32 = 4 * 75;
+
+impl Cue {
+    /// Checks that the cue describes a usable disc, and with [`LintProfile::RedBook`] one that
+    /// can be burned as an audio CD
+    pub fn lint(&self, profile: LintProfile) -> Vec<Lint> {
+        self.lint_with_capacity(profile, DiscCapacity::default())
+    }
+
+    /// Like [`Cue::lint`], checking the length of the disc against `capacity` rather than an 80
+    /// minute CD, for burning to larger media or overburning
+    pub fn lint_with_capacity(&self, profile: LintProfile, capacity: DiscCapacity) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        let mut lint = |kind, track, message: String| {
+            lints.push(Lint {
+                kind,
+                track,
+                message,
+            })
+        };
+
+        let texts = std::iter::once((
+            None,
+            [
+                ("TITLE", self.title.as_deref()),
+                ("PERFORMER", self.performer.as_deref()),
+                ("SONGWRITER", self.songwriter.as_deref()),
+                ("ARRANGER", self.arranger.as_deref()),
+            ],
+        ))
+        .chain(self.tracks.iter().map(|track| {
+            (
+                Some(track.track_index),
+                [
+                    ("TITLE", track.title.as_deref()),
+                    ("PERFORMER", track.performer.as_deref()),
+                    ("SONGWRITER", track.songwriter.as_deref()),
+                    ("ARRANGER", track.arranger.as_deref()),
+                ],
+            )
+        }));
+
+        for (track, texts) in texts {
+            for (command, _) in texts.into_iter().filter(|(_, text)| *text == Some("")) {
+                lint(LintKind::EmptyValue, track, format!("{command} is empty"));
+            }
+        }
+
+        for (i, track) in self.tracks.iter().enumerate() {
+            let number = Some(track.track_index);
+
+            if i > 0 && track.track_index != self.tracks[i - 1].track_index.wrapping_add(1) {
+                lint(
+                    LintKind::TrackNumbering,
+                    number,
+                    format!("follows track {:02}", self.tracks[i - 1].track_index),
+                );
+            }
+
+            if track.track_index > 99 {
+                lint(
+                    LintKind::TrackNumberTooLarge,
+                    number,
+                    "is numbered above 99, which players and burners may not accept".into(),
+                );
+            }
+
+            if track.start().is_none() {
+                lint(LintKind::MissingStart, number, "has no INDEX 01".into());
+            }
+
+            // an INDEX 00 in the file before is timed from that file's start
+            let gap_elsewhere = layout::gap_in_previous_file(self, i);
+            let in_order = track.indices.windows(2).all(|pair| {
+                pair[0].index() < pair[1].index()
+                    && (gap_elsewhere && pair[0].index() == 0
+                        || pair[0]
+                            .time()
+                            .zip(pair[1].time())
+                            .is_none_or(|(a, b)| a <= b))
+            });
+
+            if !in_order {
+                lint(
+                    LintKind::IndicesOutOfOrder,
+                    number,
+                    "indices aren't in order".into(),
+                );
+            }
+
+            if let Some(previous) = i.checked_sub(1).map(|i| &self.tracks[i]) {
+                let backwards = previous.file == track.file
+                    && previous
+                        .start()
+                        .zip(track.start())
+                        .is_some_and(|(previous, start)| start < previous);
+
+                if backwards {
+                    lint(
+                        LintKind::StartBeforePrevious,
+                        number,
+                        format!("starts before track {:02}", previous.track_index),
+                    );
+                }
+            }
+
+            if let Some((start, leadout)) = track.start().zip(self.leadout) {
+                if start > leadout {
+                    lint(
+                        LintKind::PastLeadout,
+                        number,
+                        "starts after the lead-out".into(),
+                    );
+                }
+            }
+
+            if profile != LintProfile::RedBook {
+                continue;
+            }
+
+            if track.mode != TrackMode::Audio {
+                lint(LintKind::NotAudio, number, "isn't an audio track".into());
+            }
+
+            if let Some(length) = self.track_length(track.track_index) {
+                if length.frames() < MIN_TRACK_LENGTH {
+                    lint(
+                        LintKind::TrackTooShort,
+                        number,
+                        "is shorter than 4 seconds".into(),
+                    );
+                }
+            }
+        }
+
+        if profile == LintProfile::RedBook {
+            if let Some(duration) = self.duration().filter(|&d| !capacity.fits(d)) {
+                let (m, s, f) = duration.to_msf();
+                let length = format!("{m:02}:{s:02}:{f:02}");
+                let (minutes, ..) = capacity.length().to_msf();
+
+                lint(
+                    LintKind::DiscTooLong,
+                    None,
+                    format!("the disc is {length} long, more than fits on a {minutes} minute CD"),
+                );
+            }
+
+            if self
+                .catalog
+                .as_ref()
+                .is_some_and(|catalog| catalog.len() != 13 || !catalog.is_valid())
+            {
+                lint(
+                    LintKind::Catalog,
+                    None,
+                    "CATALOG should be 13 digits with a valid check digit".into(),
+                );
+            }
+        }
+
+        lints
+    }
+}
+
+impl From<&Lint> for Diagnostic {
+    fn from(lint: &Lint) -> Self {
+        Self {
+            code: lint.code(),
+            severity: lint.kind.severity(),
+            message: lint.to_string(),
+            location: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn red_book_limits() {
+        let cue = Cue::from_str(
+            "CATALOG 123456789012\nFILE \"a.bin\" BINARY\nTRACK 01 MODE1/2352\nINDEX 01 00:00:00\nTRACK 03 AUDIO\nINDEX 01 00:02:00\nTRACK 04 AUDIO\nINDEX 01 01:00:00\nINDEX 02 00:30:00\nTRACK 05 AUDIO\nREM LEAD-OUT 90:00:00\n",
+        )
+        .unwrap();
+
+        let codes = |profile| -> Vec<_> { cue.lint(profile).iter().map(Lint::code).collect() };
+
+        assert_eq!(codes(LintProfile::Default), ["CUE203", "CUE202", "CUE201"]);
+        assert_eq!(
+            codes(LintProfile::RedBook),
+            ["CUE214", "CUE211", "CUE203", "CUE202", "CUE201", "CUE212", "CUE213"]
+        );
+        assert_eq!(
+            cue.lint(LintProfile::Default)[0].to_string(),
+            "track 03: follows track 01"
+        );
+        assert!(!cue
+            .lint_with_capacity(LintProfile::RedBook, DiscCapacity::Minutes99)
+            .iter()
+            .any(|lint| lint.kind == LintKind::DiscTooLong));
+    }
+
+    #[test]
+    fn empty_values() {
+        let cue = Cue::from_str(
+            "PERFORMER \"\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"\"\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        let lints: Vec<_> = cue
+            .lint(LintProfile::Default)
+            .iter()
+            .map(Lint::to_string)
+            .collect();
+
+        assert_eq!(lints, ["PERFORMER is empty", "track 01: TITLE is empty"]);
+
+        let cue =
+            Cue::from_str("FILE \"mix.wav\" WAVE\nTRACK 120 AUDIO\nINDEX 01 00:00:00\n").unwrap();
+
+        assert_eq!(
+            cue.lint(LintProfile::Default)[0].kind,
+            LintKind::TrackNumberTooLarge
+        );
+        assert!(
+            Cue::from_str("FILE \"mix.wav\" WAVE\nTRACK 300 AUDIO\nINDEX 01 00:00:00\n").is_err()
+        );
+    }
+
+    #[test]
+    fn track_starts() {
+        let appended = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 00 03:00:00\nFILE \"b.wav\" WAVE\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        assert!(appended.lint(LintProfile::Default).is_empty());
+
+        let backwards = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 01:00:00\nTRACK 02 AUDIO\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        let lints = backwards.lint(LintProfile::Default);
+
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].kind, LintKind::StartBeforePrevious);
+        assert_eq!(lints[0].to_string(), "track 02: starts before track 01");
+    }
+}
@@ -0,0 +1,144 @@
+use crate::Cue;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MergeError {
+    #[error("the discs have {0} tracks between them, more than the 99 a cue can hold")]
+    TooManyTracks(usize),
+}
+
+impl Cue {
+    /// Joins the cues of several discs into one, such as the discs of a box set, with the tracks
+    /// of each disc after those of the one before. Tracks are numbered from 1 across the discs,
+    /// keep the `FILE` they're in, and have their [`disc_number`](crate::Track::disc_number) set
+    /// to the position of their disc, counting from 1.
+    ///
+    /// The title, performer and other global fields are those of the first disc. The lead-out and
+    /// disc number are left out, as the discs have one each.
+    pub fn merge(discs: impl IntoIterator<Item = Cue>) -> Result<Cue, MergeError> {
+        let mut merged: Option<Cue> = None;
+        let mut tracks = Vec::new();
+
+        for (mut cue, disc) in discs.into_iter().zip(1..) {
+            for track in &mut cue.tracks {
+                track.disc_number = Some(disc);
+            }
+
+            tracks.append(&mut cue.tracks);
+            merged.get_or_insert(cue);
+        }
+
+        if tracks.len() > 99 {
+            return Err(MergeError::TooManyTracks(tracks.len()));
+        }
+
+        for (track, number) in tracks.iter_mut().zip(1..) {
+            track.track_index = number;
+        }
+
+        let mut merged = merged.unwrap_or_default();
+
+        merged.leadout = None;
+        merged.disc_number = None;
+        merged.tracks = tracks;
+
+        Ok(merged)
+    }
+
+    /// Splits the cue into one for each `FILE`, the opposite of [`Cue::merge`] for discs in a
+    /// file each. Each has the global fields of this cue with the `FILE` as its own, and its
+    /// tracks numbered from 1 without a [`disc_number`](crate::Track::disc_number). The lead-out
+    /// is kept only if there's a single `FILE`, which it belongs to.
+    pub fn split_by_file(&self) -> Vec<Cue> {
+        let single = self.tracks_by_file().nth(1).is_none();
+
+        self.tracks_by_file()
+            .map(|(path, tracks)| {
+                let mut tracks = tracks.to_vec();
+
+                for (track, number) in tracks.iter_mut().zip(1..) {
+                    track.track_index = number;
+                    track.disc_number = None;
+                }
+
+                Cue {
+                    path: path.map(Into::into),
+                    format: tracks[0].format.clone(),
+                    leadout: self.leadout.filter(|_| single),
+                    tracks,
+                    ..self.clone_globals()
+                }
+            })
+            .collect()
+    }
+
+    /// The cue without its tracks
+    fn clone_globals(&self) -> Cue {
+        Cue {
+            catalog: self.catalog.clone(),
+            cd_text_file: self.cd_text_file.clone(),
+            path: self.path.clone(),
+            format: self.format.clone(),
+            performer: self.performer.clone(),
+            songwriter: self.songwriter.clone(),
+            arranger: self.arranger.clone(),
+            title: self.title.clone(),
+            tracks: Vec::new(),
+            comments: self.comments.clone(),
+            comment_positions: self.comment_positions.clone(),
+            leadout: self.leadout,
+            genre: self.genre.clone(),
+            date: self.date.clone(),
+            disc_number: self.disc_number,
+            total_discs: self.total_discs,
+            replay_gain: self.replay_gain,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Frames;
+
+    use super::*;
+
+    #[test]
+    fn merges_discs() {
+        let disc = |file: &str| {
+            Cue::from_str(format!(
+                "TITLE \"Box\"\nFILE \"{file}\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 03:00:00\nREM LEAD-OUT 06:00:00\n"
+            ))
+            .unwrap()
+        };
+
+        let merged = Cue::merge([disc("CD1.wav"), disc("CD2.wav")]).unwrap();
+        let tracks: Vec<_> = merged
+            .tracks
+            .iter()
+            .map(|t| (t.track_index, t.disc_number, t.file.clone().unwrap()))
+            .collect();
+
+        assert_eq!(
+            tracks,
+            [
+                (1, Some(1), "CD1.wav".into()),
+                (2, Some(1), "CD1.wav".into()),
+                (3, Some(2), "CD2.wav".into()),
+                (4, Some(2), "CD2.wav".into()),
+            ]
+        );
+        assert_eq!(merged.title.as_deref(), Some("Box"));
+        assert_eq!(merged.leadout, None);
+        assert_eq!(Cue::from_str(merged.to_string()).unwrap(), merged);
+        let mut discs = merged.split_by_file();
+
+        for disc in &mut discs {
+            disc.leadout = Some(Frames::new(6 * 60 * 75));
+        }
+
+        assert_eq!(discs, [disc("CD1.wav"), disc("CD2.wav")]);
+        assert_eq!(
+            Cue::merge((0..50).map(|_| disc("CD.wav"))),
+            Err(MergeError::TooManyTracks(100))
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Bindings for Swift and Kotlin, generated with UniFFI
+//!
+//! Build a library with `cargo rustc --release --features uniffi --crate-type cdylib` (or
+//! `staticlib` for iOS), then generate the bindings from it with `uniffi-bindgen generate
+//! --library`. Cues cross over as plain records, copied out of the parsed [`Cue`].
+
+use crate::{Cue, Diagnostic, Error, Frames, ParseOptions, Track};
+
+/// A parsed cue, with times in frames of 1/75th of a second
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct CueRecord {
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub songwriter: Option<String>,
+    pub catalog: Option<String>,
+    pub comments: Vec<String>,
+    /// Length of the disc, if the lead-out is known
+    pub duration: Option<u32>,
+    pub tracks: Vec<TrackRecord>,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct TrackRecord {
+    pub number: u8,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub songwriter: Option<String>,
+    pub isrc: Option<String>,
+    pub file: Option<String>,
+    /// Start of the track proper (`INDEX 01`) within its file
+    pub start: Option<u32>,
+    /// See [`Cue::track_length`]
+    pub length: Option<u32>,
+    pub pregap: Option<u32>,
+    pub postgap: Option<u32>,
+}
+
+/// A problem found by [`validate_cue`]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct Problem {
+    /// The stable code of the problem, see [`Error::code`]
+    pub code: String,
+    /// `"error"` or `"warning"`
+    pub severity: String,
+    pub message: String,
+    /// Counting from 1
+    pub line: Option<u64>,
+    /// In characters, counting from 1
+    pub column: Option<u64>,
+}
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum CueError {
+    #[error("{line}:{column}: {message}")]
+    Parse {
+        code: String,
+        message: String,
+        line: u64,
+        column: u64,
+    },
+    #[error("{message}")]
+    Io { message: String },
+}
+
+impl From<Error> for CueError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Parse(e) | Error::Partial { cause: e, .. } => {
+                let location = e.location();
+
+                Self::Parse {
+                    code: e.code().into(),
+                    message: e.message().into(),
+                    line: location.line as u64,
+                    column: location.column as u64,
+                }
+            }
+            e => Self::Io {
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<&Diagnostic> for Problem {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        Self {
+            code: diagnostic.code.into(),
+            severity: diagnostic.severity.as_str().into(),
+            message: diagnostic.message.clone(),
+            line: diagnostic.location.map(|location| location.line as u64),
+            column: diagnostic.location.map(|location| location.column as u64),
+        }
+    }
+}
+
+fn frames(frames: Option<Frames>) -> Option<u32> {
+    frames.map(Frames::frames)
+}
+
+fn track_record(cue: &Cue, track: &Track) -> TrackRecord {
+    TrackRecord {
+        number: track.track_index,
+        title: track.title.clone(),
+        performer: track.performer.as_deref().map(Into::into),
+        songwriter: track.songwriter.as_deref().map(Into::into),
+        isrc: track.isrc.clone(),
+        file: track
+            .file
+            .as_deref()
+            .map(|file| file.to_string_lossy().into_owned()),
+        start: frames(track.start()),
+        length: frames(cue.track_length(track.track_index)),
+        pregap: frames(track.pregap),
+        postgap: frames(track.postgap),
+    }
+}
+
+impl From<&Cue> for CueRecord {
+    fn from(cue: &Cue) -> Self {
+        Self {
+            title: cue.title.clone(),
+            performer: cue.performer.as_deref().map(Into::into),
+            songwriter: cue.songwriter.as_deref().map(Into::into),
+            catalog: cue.catalog.as_deref().map(Into::into),
+            comments: cue.comments.clone(),
+            duration: frames(cue.duration()),
+            tracks: cue
+                .tracks
+                .iter()
+                .map(|track| track_record(cue, track))
+                .collect(),
+        }
+    }
+}
+
+/// Parses a cue. `lenient` works around common mistakes instead of failing, see
+/// [`ParseOptions::lenient`].
+#[uniffi::export]
+pub fn parse_cue(text: String, lenient: bool) -> Result<CueRecord, CueError> {
+    let options = ParseOptions {
+        lenient,
+        ..Default::default()
+    };
+
+    Ok(CueRecord::from(&Cue::from_str_with(text, &options)?))
+}
+
+/// Reads and parses a cue file, detecting its text encoding
+#[uniffi::export]
+pub fn parse_cue_file(path: String) -> Result<CueRecord, CueError> {
+    Ok(CueRecord::from(&Cue::from_file(path)?))
+}
+
+/// Checks a cue, returning every problem with it, or nothing for a valid cue
+#[uniffi::export]
+pub fn validate_cue(text: String) -> Vec<Problem> {
+    let options = ParseOptions {
+        lenient: true,
+        ..Default::default()
+    };
+
+    match Cue::from_str_with_warnings(text, &options) {
+        Ok(outcome) => outcome
+            .warnings
+            .iter()
+            .map(|warning| Problem::from(&Diagnostic::from(warning)))
+            .collect(),
+        Err(e) => vec![Problem::from(&Diagnostic::from(&e))],
+    }
+}
+
+/// Formats a number of frames as `MM:SS:FF`, or nothing if it's past 99:59:74
+#[uniffi::export]
+pub fn frames_to_msf(frames: u32) -> Option<String> {
+    let (m, s, f) = Frames::try_from(frames).ok()?.to_msf();
+
+    Some(format!("{m:02}:{s:02}:{f:02}"))
+}
+
+/// The number of frames in `MM:SS:FF`, or nothing if it isn't a valid time
+#[uniffi::export]
+pub fn msf_to_frames(msf: String) -> Option<u32> {
+    let mut fields = msf.split(':').map(|field| field.parse().ok());
+
+    match (fields.next(), fields.next(), fields.next(), fields.next()) {
+        (Some(m), Some(s), Some(f), None) => Frames::from_msf(m?, s?, f?).ok().map(Frames::frames),
+        _ => None,
+    }
+}
+
+#[uniffi::export]
+pub fn frames_to_seconds(frames: u32) -> f64 {
+    f64::from(frames) / 75.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_and_times() {
+        let cue = parse_cue(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 01:00:00\n"
+                .into(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(cue.tracks[0].length, Some(4500));
+        assert_eq!(cue.tracks[1].file.as_deref(), Some("a.wav"));
+        assert!(matches!(
+            parse_cue("TRACK".into(), false),
+            Err(CueError::Parse { line: 1, .. })
+        ));
+        assert_eq!(validate_cue("FILE a b.wav WAVE\n".into())[0].code, "CUE102");
+        assert_eq!(frames_to_msf(4650).as_deref(), Some("01:02:00"));
+        assert_eq!(msf_to_frames("01:02:00".into()), Some(4650));
+        assert_eq!(msf_to_frames("1:2".into()), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Bindings for Node.js, built with napi-rs
+//!
+//! Build an addon with `napi build --release --features node`.
+
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use crate::{Cue, Diagnostic, LintProfile, ParseOptions};
+
+/// A problem found by [`validate`]
+#[napi(object)]
+pub struct Problem {
+    /// The stable code of the problem, see [`crate::Error::code`]
+    pub code: String,
+    /// `"error"` or `"warning"`
+    pub severity: String,
+    pub message: String,
+    /// Counting from 1
+    pub line: Option<u32>,
+    /// In characters, counting from 1
+    pub column: Option<u32>,
+}
+
+impl From<&Diagnostic> for Problem {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        Self {
+            code: diagnostic.code.into(),
+            severity: diagnostic.severity.as_str().into(),
+            message: diagnostic.message.clone(),
+            line: diagnostic.location.map(|location| location.line as u32),
+            column: diagnostic.location.map(|location| location.column as u32),
+        }
+    }
+}
+
+fn parse_cue(text: &str, lenient: Option<bool>) -> Result<Cue> {
+    let options = ParseOptions {
+        lenient: lenient.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    Cue::from_str_with(text, &options).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Parses a cue into a plain object in the same shape as the `serde` JSON, throwing on failure.
+/// `lenient` works around common mistakes instead, see [`ParseOptions::lenient`].
+#[napi]
+pub fn parse(text: String, lenient: Option<bool>) -> Result<serde_json::Value> {
+    let cue = parse_cue(&text, lenient)?;
+
+    serde_json::to_value(cue).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Parses a cue straight to JSON text, e.g. to send on without building objects for it
+#[napi]
+pub fn parse_to_json(text: String, lenient: Option<bool>) -> Result<String> {
+    let cue = parse_cue(&text, lenient)?;
+
+    serde_json::to_string(&cue).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Writes a cue from a plain object in the shape [`parse`] returns, as a cue sheet
+#[napi]
+pub fn serialize(cue: serde_json::Value) -> Result<String> {
+    let cue: Cue = serde_json::from_value(cue).map_err(|e| Error::from_reason(e.to_string()))?;
+
+    Ok(cue.to_string())
+}
+
+/// Checks a cue, returning every problem with it, both worked around when parsing and found by
+/// [`Cue::lint`], or an empty array for a valid cue
+#[napi]
+pub fn validate(text: String) -> Vec<Problem> {
+    let options = ParseOptions {
+        lenient: true,
+        ..Default::default()
+    };
+
+    match Cue::from_str_with_warnings(text, &options) {
+        Ok(outcome) => outcome
+            .warnings
+            .iter()
+            .map(Diagnostic::from)
+            .chain(
+                outcome
+                    .cue
+                    .lint(LintProfile::Default)
+                    .iter()
+                    .map(Diagnostic::from),
+            )
+            .map(|diagnostic| Problem::from(&diagnostic))
+            .collect(),
+        Err(e) => vec![Problem::from(&Diagnostic::from(&e))],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates() {
+        let cue = parse("FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\n".into(), None).unwrap();
+
+        assert_eq!(cue["tracks"][0]["file"], "a.wav");
+        assert!(parse("FILE a b.wav WAVE\n".into(), None).is_err());
+        assert!(parse_to_json("FILE a b.wav WAVE\n".into(), Some(true)).is_ok());
+        assert_eq!(validate("FILE a b.wav WAVE\n".into())[0].code, "CUE102");
+        assert_eq!(
+            validate("FILE a.wav WAVE\nTRACK 01 AUDIO\n".into())[0].code,
+            "CUE201"
+        );
+        assert_eq!(
+            serialize(cue).unwrap(),
+            "FILE \"a.wav\" WAVE\n  TRACK 01 AUDIO\n"
+        );
+    }
+}
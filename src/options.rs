@@ -0,0 +1,48 @@
+use crate::TextNormalization;
+
+/// Options controlling how a cue is parsed, for use with [`Cue::from_str_with`](crate::Cue::from_str_with)
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Share a single allocation between equal `PERFORMER`, `SONGWRITER` and `ARRANGER` values,
+    /// which album rips repeat on every track
+    pub intern_strings: bool,
+    /// Skip unknown commands and accept unquoted text containing spaces, instead of failing.
+    /// Each is reported as a [`Warning`](crate::Warning) by
+    /// [`Cue::from_str_with_warnings`](crate::Cue::from_str_with_warnings).
+    pub lenient: bool,
+    /// On failure, return the global section and the tracks before the error as
+    /// [`Error::Partial`](crate::Error::Partial), so that what could be read can still be used
+    pub keep_partial: bool,
+    /// Accept `PERFORMER`, `SONGWRITER`, `TITLE` and `ARRANGER` without a value, leaving the
+    /// field unset, instead of failing. Each is reported as a [`Warning`](crate::Warning).
+    pub allow_missing_values: bool,
+    /// Accept lines starting with `;` or `//` as comments, kept with those of `REM` and written
+    /// back as `REM`
+    pub alternative_comments: bool,
+    /// Fail on input longer than this many bytes, before parsing any of it. Always set by
+    /// [`Cue::from_bytes`](crate::Cue::from_bytes).
+    pub max_input_len: Option<usize>,
+    /// Normalize titles, performers, songwriters and arrangers as they're read, as
+    /// [`Cue::normalize_text`](crate::Cue::normalize_text) does
+    pub text_normalization: TextNormalization,
+    /// Accept times written as HH:MM:SS:FF, as some mixing tools do for long programs, instead
+    /// of failing. The hours are folded into the minutes.
+    pub hour_times: bool,
+    /// What to do with times whose seconds or frames are out of range, such as `03:60:00`
+    pub msf_overflow: MsfOverflow,
+}
+
+/// How times such as `03:60:00` or `02:15:80`, with 60 or more seconds or 75 or more frames, are
+/// parsed, or `01:60:00:00` with 60 or more minutes after the hours. Buggy exporters write them
+/// instead of carrying into the next unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MsfOverflow {
+    /// Fail to parse
+    Reject,
+    /// Carry the excess into the next unit, so `03:60:00` is `04:00:00`, and report a
+    /// [`Warning`](crate::Warning)
+    #[default]
+    Normalize,
+    /// Carry the excess into the next unit without a warning
+    Accept,
+}
@@ -0,0 +1,55 @@
+//! The order the commands of a cue are written in, and which of them are, shared by the writer
+//! and the parser, which places comments so that writing the cue keeps them where they were
+
+use crate::{Cue, CueCommand, Track};
+
+/// The commands of the global section, in the order they're written
+const GLOBAL_COMMANDS: [CueCommand; 8] = [
+    CueCommand::Catalog,
+    CueCommand::CdTextFile,
+    CueCommand::Performer,
+    CueCommand::Songwriter,
+    CueCommand::Arranger,
+    CueCommand::Title,
+    CueCommand::File,
+    CueCommand::Track,
+];
+
+/// The first command written in the global section. Comments before it are written along with
+/// those that have no position, so parsing them back leaves them without one.
+pub(crate) fn first_global_written(cue: &Cue) -> Option<CueCommand> {
+    GLOBAL_COMMANDS
+        .into_iter()
+        .find(|&command| global_written(cue, command))
+}
+
+/// Whether a global command is written, so that comments before it can be
+pub(crate) fn global_written(cue: &Cue, command: CueCommand) -> bool {
+    match command {
+        CueCommand::Catalog => cue.catalog.is_some(),
+        CueCommand::CdTextFile => cue.cd_text_file.is_some(),
+        CueCommand::File => cue.path.is_some(),
+        CueCommand::Performer => cue.performer.is_some(),
+        CueCommand::Songwriter => cue.songwriter.is_some(),
+        CueCommand::Arranger => cue.arranger.is_some(),
+        CueCommand::Title => cue.title.is_some(),
+        CueCommand::Track => !cue.tracks.is_empty(),
+        _ => false,
+    }
+}
+
+/// Whether a command of the track is written
+pub(crate) fn track_written(track: &Track, command: CueCommand) -> bool {
+    match command {
+        CueCommand::Performer => track.performer.is_some(),
+        CueCommand::Songwriter => track.songwriter.is_some(),
+        CueCommand::Arranger => track.arranger.is_some(),
+        CueCommand::Title => track.title.is_some(),
+        CueCommand::Flags => !track.flags.is_empty(),
+        CueCommand::Isrc => track.isrc.is_some(),
+        CueCommand::Pregap => track.pregap.is_some(),
+        CueCommand::Index(number) => track.index(number).is_some(),
+        CueCommand::Postgap => track.postgap.is_some(),
+        _ => false,
+    }
+}
@@ -0,0 +1,355 @@
+use std::{fmt, ops::Range};
+
+use pest::{iterators::Pair, Parser};
+
+use super::{parse_error, CueParser, Rule};
+use crate::Error;
+
+/// What a [`SyntaxNode`] or [`SyntaxToken`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SyntaxKind {
+    // nodes
+    Cue,
+    GlobalSection,
+    TrackList,
+    /// A `TRACK` with the commands after it
+    Track,
+    /// The `TRACK` command itself
+    TrackCommand,
+    Catalog,
+    CdTextFile,
+    File,
+    Flags,
+    Performer,
+    Songwriter,
+    Title,
+    Arranger,
+    Index,
+    Isrc,
+    Pregap,
+    Postgap,
+    /// `REM`, or a `;` or `//` comment
+    Rem,
+    /// `REM LEAD-OUT`
+    Leadout,
+
+    // tokens
+    /// The name of a command, such as `TITLE`, and other fixed words such as `LEAD-OUT`
+    Keyword,
+    /// Spaces and tabs
+    Whitespace,
+    Newline,
+    ByteOrderMark,
+    /// Text in quotes, including the quotes
+    QuotedString,
+    /// Text or a path without quotes
+    UnquotedString,
+    Integer,
+    Time,
+    TrackNumber,
+    TrackMode,
+    FileFormat,
+    Flag,
+    CatalogNumber,
+    IsrcCode,
+    /// The text of a `REM` after its name
+    RemText,
+    /// A whole line that isn't a known command
+    UnknownCommand,
+}
+
+/// A node of the lossless syntax tree of a cue, holding every byte of its text, including
+/// whitespace and comments. Writing the tree out gives back the text exactly, which makes it
+/// the basis for formatters and editors that must leave the rest of a cue untouched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode<'a> {
+    kind: SyntaxKind,
+    range: Range<usize>,
+    children: Vec<SyntaxElement<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement<'a> {
+    Node(SyntaxNode<'a>),
+    Token(SyntaxToken<'a>),
+}
+
+/// A piece of text at a leaf of the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntaxToken<'a> {
+    kind: SyntaxKind,
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> SyntaxNode<'a> {
+    /// Parses the syntax tree of a cue. This only checks the syntax, so that unknown commands,
+    /// which [`Cue::from_str`](crate::Cue::from_str) rejects outside lenient mode, are kept as
+    /// [`SyntaxKind::UnknownCommand`].
+    pub fn parse(input: &'a str) -> Result<Self, Error> {
+        let pair = CueParser::parse(Rule::cue, input)
+            .map_err(|e| crate::Error::Parse(parse_error(e)))?
+            .next()
+            .unwrap();
+        let mut root = Self {
+            kind: SyntaxKind::Cue,
+            range: 0..input.len(),
+            children: Vec::new(),
+        };
+        let mut position = 0;
+
+        push_children(input, pair, &mut root.children, &mut position);
+        push_trivia(input, position..input.len(), &mut root.children);
+
+        Ok(root)
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// The byte range of the node's text in the input
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    pub fn children(&self) -> &[SyntaxElement<'a>] {
+        &self.children
+    }
+
+    /// The child nodes, without tokens
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode<'a>> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// Every token under the node, in order
+    pub fn tokens(&self) -> Vec<SyntaxToken<'a>> {
+        let mut tokens = Vec::new();
+
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens(&self, tokens: &mut Vec<SyntaxToken<'a>>) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(tokens),
+                SyntaxElement::Token(token) => tokens.push(*token),
+            }
+        }
+    }
+}
+
+/// Writes the node's text exactly as it was parsed
+impl fmt::Display for SyntaxNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => write!(f, "{node}")?,
+                SyntaxElement::Token(token) => f.write_str(token.text)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> SyntaxToken<'a> {
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// The byte range of the token in the input
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.text.len()
+    }
+}
+
+/// Adds the children of `pair` to `children`, with the text between them as trivia, starting
+/// from `position` and leaving it at the end of the last child
+fn push_children<'a>(
+    input: &'a str,
+    pair: Pair<'a, Rule>,
+    children: &mut Vec<SyntaxElement<'a>>,
+    position: &mut usize,
+) {
+    for child in pair.into_inner() {
+        let span = child.as_span();
+
+        push_trivia(input, *position..span.start(), children);
+        *position = span.start();
+
+        match kind(child.as_rule()) {
+            Some(kind) if is_token(kind) => {
+                if !span.as_str().is_empty() {
+                    children.push(SyntaxElement::Token(SyntaxToken {
+                        kind,
+                        text: span.as_str(),
+                        offset: span.start(),
+                    }));
+                }
+            }
+            Some(kind) => {
+                let mut node = SyntaxNode {
+                    kind,
+                    range: span.start()..span.end(),
+                    children: Vec::new(),
+                };
+
+                push_children(input, child, &mut node.children, position);
+                push_trivia(input, *position..span.end(), &mut node.children);
+                children.push(SyntaxElement::Node(node));
+            }
+            // wrappers such as `string` are replaced by what they wrap
+            None => {
+                push_children(input, child, children, position);
+                push_trivia(input, *position..span.end(), children);
+            }
+        }
+
+        *position = span.end();
+    }
+}
+
+/// Splits text the grammar matches without a rule of its own into whitespace, newlines and
+/// keywords
+fn push_trivia<'a>(input: &'a str, range: Range<usize>, children: &mut Vec<SyntaxElement<'a>>) {
+    let mut rest = &input[range.clone()];
+    let mut offset = range.start;
+
+    while let Some(c) = rest.chars().next() {
+        let (kind, len) = match c {
+            ' ' | '\t' => (
+                SyntaxKind::Whitespace,
+                rest.find(|c| c != ' ' && c != '\t').unwrap_or(rest.len()),
+            ),
+            '\r' if rest.starts_with("\r\n") => (SyntaxKind::Newline, 2),
+            '\r' | '\n' => (SyntaxKind::Newline, 1),
+            '\u{FEFF}' => (SyntaxKind::ByteOrderMark, c.len_utf8()),
+            _ => (
+                SyntaxKind::Keyword,
+                rest.find([' ', '\t', '\r', '\n']).unwrap_or(rest.len()),
+            ),
+        };
+
+        children.push(SyntaxElement::Token(SyntaxToken {
+            kind,
+            text: &rest[..len],
+            offset,
+        }));
+        rest = &rest[len..];
+        offset += len;
+    }
+}
+
+fn kind(rule: Rule) -> Option<SyntaxKind> {
+    let kind = match rule {
+        Rule::global_section => SyntaxKind::GlobalSection,
+        Rule::track_list => SyntaxKind::TrackList,
+        Rule::track => SyntaxKind::Track,
+        Rule::track_command => SyntaxKind::TrackCommand,
+        Rule::catalog => SyntaxKind::Catalog,
+        Rule::cd_text_file => SyntaxKind::CdTextFile,
+        Rule::file => SyntaxKind::File,
+        Rule::flags => SyntaxKind::Flags,
+        Rule::performer => SyntaxKind::Performer,
+        Rule::songwriter => SyntaxKind::Songwriter,
+        Rule::title => SyntaxKind::Title,
+        Rule::arranger => SyntaxKind::Arranger,
+        Rule::index => SyntaxKind::Index,
+        Rule::isrc => SyntaxKind::Isrc,
+        Rule::pregap => SyntaxKind::Pregap,
+        Rule::postgap => SyntaxKind::Postgap,
+        Rule::rem | Rule::alt_comment => SyntaxKind::Rem,
+        Rule::leadout => SyntaxKind::Leadout,
+        Rule::quoted_string | Rule::curly_string => SyntaxKind::QuotedString,
+        Rule::unquoted_string | Rule::unquoted_path => SyntaxKind::UnquotedString,
+        Rule::integer => SyntaxKind::Integer,
+        Rule::time => SyntaxKind::Time,
+        Rule::track_number => SyntaxKind::TrackNumber,
+        Rule::track_mode => SyntaxKind::TrackMode,
+        Rule::file_format => SyntaxKind::FileFormat,
+        Rule::flag => SyntaxKind::Flag,
+        Rule::catalog_number => SyntaxKind::CatalogNumber,
+        Rule::isrc_code => SyntaxKind::IsrcCode,
+        Rule::rem_text => SyntaxKind::RemText,
+        Rule::unknown_command => SyntaxKind::UnknownCommand,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+/// Whether the rule of this kind is matched atomically, as a leaf of the tree
+fn is_token(kind: SyntaxKind) -> bool {
+    use SyntaxKind::*;
+
+    matches!(
+        kind,
+        QuotedString
+            | UnquotedString
+            | Integer
+            | Time
+            | TrackNumber
+            | TrackMode
+            | FileFormat
+            | Flag
+            | CatalogNumber
+            | IsrcCode
+            | RemText
+            | UnknownCommand
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_byte() {
+        let input = "\u{FEFF}REM COMMENT \"x\"\r\nTITLE   Album\r\n\r\nFILE \"a b.wav\" WAVE\n  TRACK 01 AUDIO\n\tFLAGS DCP PRE \n    INDEX 01 00:00:00\nUNKNOWN thing";
+        let tree = SyntaxNode::parse(input).unwrap();
+
+        assert_eq!(tree.to_string(), input);
+
+        let kinds: Vec<_> = tree
+            .tokens()
+            .into_iter()
+            .filter(|token| token.kind() != SyntaxKind::Whitespace)
+            .map(|token| (token.kind(), token.text()))
+            .take(6)
+            .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                (SyntaxKind::ByteOrderMark, "\u{FEFF}"),
+                (SyntaxKind::Keyword, "REM"),
+                (SyntaxKind::RemText, "COMMENT \"x\""),
+                (SyntaxKind::Newline, "\r\n"),
+                (SyntaxKind::Keyword, "TITLE"),
+                (SyntaxKind::UnquotedString, "Album"),
+            ]
+        );
+
+        let track = tree
+            .child_nodes()
+            .nth(1)
+            .unwrap()
+            .child_nodes()
+            .next()
+            .unwrap();
+
+        assert_eq!(track.kind(), SyntaxKind::Track);
+        assert!(track.to_string().starts_with("TRACK 01 AUDIO\n\tFLAGS"));
+        assert!(SyntaxNode::parse("TRACK").is_err());
+    }
+}
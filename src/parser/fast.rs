@@ -0,0 +1,324 @@
+//! A parser for plain cues, reading each line by dispatching on its first word instead of trying
+//! the grammar's commands in turn. It gives up on anything it isn't sure of, from an unknown
+//! command or a time out of range to text it would have to guess the quoting of, and leaves the
+//! cue to the grammar, which reports the errors and warnings. The commands it reads are built
+//! into a cue by the same sections as the grammar's, so both give the same cue.
+
+use std::{path::PathBuf, str::FromStr};
+
+use super::{assemble, Command, Context, CueFile, GlobalSection, TrackSection};
+use crate::{Cue, FileFormat, Frames, Track, TrackFlags, TrackIndex};
+
+/// Parses a cue made only of plain commands, or returns `None` to leave it to the grammar
+pub(super) fn parse(input: &str, context: &Context) -> Option<Cue> {
+    let mut global = GlobalSection::default();
+    let mut tracks = Vec::new();
+    let mut track: Option<TrackSection> = None;
+
+    for line in input.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let line = line.trim_start_matches(is_space);
+
+        if line.trim_end_matches(is_space).is_empty() {
+            continue;
+        }
+
+        // a lone carriage return also ends a line for the grammar
+        if line.contains('\r') {
+            return None;
+        }
+
+        let (keyword, rest) = line.split_once(is_space)?;
+        let rest = rest.trim_start_matches(is_space);
+
+        if keyword == "TRACK" {
+            tracks.extend(track.take().map(TrackSection::finish));
+            track = Some(TrackSection::new(track_command(rest)?));
+
+            continue;
+        }
+
+        let command = command(keyword, rest, track.is_some(), context)?;
+
+        match &mut track {
+            Some(track) => track.add(command),
+            None => global.add(command),
+        }
+    }
+
+    tracks.extend(track.map(TrackSection::finish));
+
+    Some(assemble(global.finish(), tracks))
+}
+
+/// Reads a command other than `TRACK`, in a track or the global section
+fn command(keyword: &str, rest: &str, in_track: bool, context: &Context) -> Option<Command> {
+    // only a comment keeps the spaces at the end of the line
+    let value = rest.trim_end_matches(is_space);
+
+    let command = match keyword {
+        // a lead-out is only read as such when a time follows
+        "REM" if !rest.is_empty() && !rest.starts_with("LEAD") => Command::Rem(rest.into()),
+        "TITLE" => Command::Title(Some(
+            context
+                .options
+                .text_normalization
+                .apply(text(value)?)
+                .into_owned(),
+        )),
+        "PERFORMER" => Command::Performer(Some(context.text(text(value)?))),
+        "SONGWRITER" => Command::Songwriter(Some(context.text(text(value)?))),
+        "ARRANGER" => Command::Arranger(Some(context.text(text(value)?))),
+        "FILE" => Command::File(file(value)?),
+        "CATALOG" | "UPC_EAN" if !in_track => {
+            let digits = (12..=13).contains(&value.len()) && is_digits(value);
+
+            Command::Catalog(digits.then(|| value.into())?)
+        }
+        "CDTEXTFILE" if !in_track => Command::CdTextFile(PathBuf::from(text(value)?)),
+        "INDEX" if in_track => {
+            let mut fields = value.split(is_space).filter(|field| !field.is_empty());
+            let (number, time) = (fields.next()?, time(fields.next()?)?);
+
+            if fields.next().is_some() {
+                return None;
+            }
+
+            Command::Index(TrackIndex::new(number_of(number)?, Some(time)))
+        }
+        "ISRC" if in_track => {
+            let code = value.len() == 12
+                && value.as_bytes()[..5].iter().all(u8::is_ascii_alphanumeric)
+                && is_digits(&value[5..]);
+
+            Command::Isrc(code.then(|| value.into())?)
+        }
+        "FLAGS" if in_track && !value.is_empty() => Command::Flags(
+            value
+                .split(is_space)
+                .filter(|flag| !flag.is_empty())
+                .map(TrackFlags::from_str)
+                .collect::<Result<_, _>>()
+                .ok()?,
+        ),
+        "PREGAP" if in_track => Command::Pregap(time(value)?),
+        "POSTGAP" if in_track => Command::Postgap(time(value)?),
+        _ => return None,
+    };
+
+    Some(command)
+}
+
+fn track_command(rest: &str) -> Option<Track> {
+    let mut fields = rest.split(is_space).filter(|field| !field.is_empty());
+    let (number, mode) = (number_of(fields.next()?)?, fields.next()?.parse().ok()?);
+
+    fields.next().is_none().then(|| Track::new(number, mode))
+}
+
+/// Text in quotes, or a single word without them. Text with spaces has to be quoted, and
+/// typographic quotes are only taken as quotes in lenient mode.
+fn text(value: &str) -> Option<&str> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        let text = quoted.strip_suffix('"')?;
+
+        return (!text.contains('"')).then_some(text);
+    }
+
+    let word = !value.is_empty()
+        && !value.contains(is_space)
+        && !value.starts_with(['\u{201C}', '\u{201E}']);
+
+    word.then_some(value)
+}
+
+/// A quoted path, followed by the file format if there is one
+fn file(value: &str) -> Option<CueFile> {
+    let (path, format) = value.strip_prefix('"')?.split_once('"')?;
+
+    let format = match format {
+        "" => FileFormat::Unspecified,
+        format => {
+            let token = format.strip_prefix(is_space)?.trim_start_matches(is_space);
+
+            if token.is_empty() {
+                return None;
+            }
+
+            token.parse().ok()?
+        }
+    };
+
+    Some(CueFile::new(path, format))
+}
+
+/// An MM:SS:FF time with its seconds and frames in range
+fn time(value: &str) -> Option<Frames> {
+    let mut fields = value.split(':');
+    let [m, s, f] = [fields.next()?, fields.next()?, fields.next()?];
+
+    if fields.next().is_some() {
+        return None;
+    }
+
+    let (m, s, f) = (number_of(m)?, number_of(s)?, number_of(f)?);
+
+    if s >= 60 || f >= 75 {
+        return None;
+    }
+
+    Frames::from_msf(m, s, f).ok()
+}
+
+/// Digits only, as `parse` would also take a sign
+fn number_of<T: FromStr>(digits: &str) -> Option<T> {
+    is_digits(digits).then(|| digits.parse().ok())?
+}
+
+fn is_digits(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_space(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{hint::black_box, time::Instant};
+
+    use super::*;
+    use crate::{
+        parser::{parse_as, CueParser, Rule},
+        ParseOptions, TextNormalization,
+    };
+
+    fn grammar(input: &str, options: &ParseOptions) -> Option<Cue> {
+        parse_as(Rule::cue, input, &Context::new(options), CueParser::cue).ok()
+    }
+
+    /// Whether the cue is read here rather than left to the grammar, checking that both agree
+    fn agrees(input: &str, options: &ParseOptions) -> bool {
+        let cue = parse(input, &Context::new(options));
+
+        if let Some(cue) = &cue {
+            assert_eq!(Some(cue), grammar(input, options).as_ref(), "{input:?}");
+        }
+
+        cue.is_some()
+    }
+
+    #[test]
+    fn agrees_with_the_grammar() {
+        let plain = [
+            "",
+            "REM GENRE Rock\r\nTITLE \"Album\"\r\nFILE \"a.wav\" WAVE\r\n\r\n  TRACK 01 AUDIO\r\n    INDEX 01 00:00:00\r\n",
+            "CATALOG 0123456789012\nCDTEXTFILE \"text.cdt\"\nREM before title\nTITLE Album\nREM COMMENT \"EAC\"\nFILE \"a b.flac\" FLAC\nTRACK 01 AUDIO\n\tREM trailing  \n\tFLAGS DCP   PRE\n\tISRC USABC2200001\n\tSONGWRITER \"Someone\"\n\tPREGAP 00:02:00\n\tINDEX 00 00:00:00\n\tINDEX 01 00:02:00\n\tREM REPLAYGAIN_TRACK_GAIN -7.50 dB\n\tFILE \"b.wav\" WAVE\nTRACK 02 MODE1/2352\n\tINDEX 01 00:00:00\n\tPOSTGAP 00:01:00\nTRACK 170 AUDIO\n\tINDEX 01 10:00:00",
+            "TRACK 01 AUDIO\n  TITLE \"  Se\u{301}   de\u{301}  \"\n  PERFORMER \"Sigur Ro\u{301}s\"\n  ARRANGER \"Someone\"\n  INDEX 01 00:00:00\nTRACK 02 AUDIO\n  PERFORMER \"Sigur Ro\u{301}s\"\n  INDEX 01 01:00:00\n",
+        ];
+        let left = [
+            include_str!("../../test_files/example.cue"),
+            "\u{FEFF}TITLE \"Album\"\n",
+            "TITLE \"Album\"\rFILE \"a.wav\" WAVE\n",
+            "REM LEAD-OUT 10:00:00\n",
+            "REMARK this\n",
+            "REM\n",
+            "TITLE\n",
+            "TITLE \"a\" b\n",
+            "TITLE a b\n",
+            "TITLE \u{201C}Song\u{201D}\n",
+            "TITLE \"multiple\nlines\"\n",
+            "; comment\n",
+            "FILE a.wav WAVE\n",
+            "FILE \"a.wav\"WAVE\n",
+            "FILE \"a.wav\" wave\n",
+            "UNKNOWN command\n",
+            "INDEX 01 00:00:00\n",
+            "TRACK 01 AUDIO\n  INDEX 01 1:2\n",
+            "TRACK 01 AUDIO\n  INDEX 01 00:00:00:00\n",
+            "TRACK 01 AUDIO\n  INDEX 01 +1:00:00\n",
+            "TRACK 01 AUDIO\n  INDEX 01 00:60:00\n",
+            "TRACK 01 AUDIO\n  INDEX 01 00:02:00 extra\n",
+            "TRACK 01 AUDIO\n  INDEX 01\n",
+            "TRACK 01 AUDIO\n  ISRC US ABC2200001\n",
+            "TRACK 01 AUDIO\n  ISRC USABC2200001X\n",
+            "TRACK 01 AUDIO\n  FLAGS DCPPRE\n",
+            "TRACK 01 AUDIO\n  CATALOG 0123456789012\n",
+            "TRACK 300 AUDIO\n",
+            "TRACK 01 audio\n",
+            "TRACK 01AUDIO\n",
+        ];
+        let options = [
+            ParseOptions::default(),
+            ParseOptions {
+                lenient: true,
+                intern_strings: true,
+                allow_missing_values: true,
+                alternative_comments: true,
+                hour_times: true,
+                ..Default::default()
+            },
+            ParseOptions {
+                text_normalization: TextNormalization {
+                    #[cfg(feature = "unicode-normalization")]
+                    form: Some(crate::NormalizationForm::Nfc),
+                    collapse_whitespace: true,
+                },
+                ..Default::default()
+            },
+        ];
+
+        for options in &options {
+            for input in plain {
+                assert!(agrees(input, options), "{input:?}");
+            }
+
+            for input in left {
+                assert!(!agrees(input, options), "{input:?}");
+            }
+        }
+    }
+
+    /// Fails if reading an album rip here gets less than twice as fast as with the grammar, which
+    /// is itself faster than the parser was before either. Both are timed in the same run, so
+    /// that the speed of the machine doesn't matter.
+    #[test]
+    fn faster_than_the_grammar() {
+        let mut album = String::from("PERFORMER \"Some Artist\"\nFILE \"album.wav\" WAVE\n");
+
+        for track in 1..=99 {
+            album += &format!(
+                "  TRACK {track:02} AUDIO\n    TITLE \"Track {track}\"\n    ISRC USABC22{track:05}\n    INDEX 01 {track:02}:00:00\n"
+            );
+        }
+
+        let options = ParseOptions::default();
+        let time = |parse: &dyn Fn() -> Option<Cue>| {
+            (0..5)
+                .map(|_| {
+                    let start = Instant::now();
+
+                    for _ in 0..10 {
+                        black_box(parse().unwrap());
+                    }
+
+                    start.elapsed()
+                })
+                .min()
+                .unwrap()
+        };
+
+        let fast = time(&|| parse(black_box(&album), &Context::new(&options)));
+        let grammar = time(&|| grammar(black_box(&album), &options));
+
+        assert!(fast * 2 < grammar, "{fast:?} against {grammar:?}");
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn agrees_on_written_cues(cue in crate::strategy::cue()) {
+            agrees(&cue.to_string(), &ParseOptions::default());
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::{fs::File, io::Read, path::Path};
+
+use super::{parse, Context};
+use crate::{encoding, Cue, Error, ParseOptions};
+
+/// A parser configured once and reused for many cues, keeping its buffers between them. Useful
+/// when scanning a library, where [`Cue::from_file`] would set everything up again for each file.
+pub struct CueParserHandle {
+    context: Context,
+    bytes: Vec<u8>,
+}
+
+impl CueParserHandle {
+    pub fn new(options: &ParseOptions) -> Self {
+        Self {
+            context: Context::new(options),
+            bytes: Vec::new(),
+        }
+    }
+
+    pub fn options(&self) -> &ParseOptions {
+        &self.context.options
+    }
+
+    pub fn parse(&mut self, input: &str) -> Result<Cue, Error> {
+        self.context.reset();
+
+        parse(input, &self.context)
+    }
+
+    /// Reads and parses a cue file, detecting its text encoding
+    pub fn parse_file(&mut self, path: impl AsRef<Path>) -> Result<Cue, Error> {
+        self.bytes.clear();
+        File::open(path)?.read_to_end(&mut self.bytes)?;
+        self.context.reset();
+
+        parse(&encoding::decode(&self.bytes), &self.context)
+    }
+}
+
+impl Default for CueParserHandle {
+    fn default() -> Self {
+        Self::new(&ParseOptions::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuse_across_cues() {
+        let mut parser = CueParserHandle::new(&ParseOptions {
+            intern_strings: true,
+            ..Default::default()
+        });
+
+        let first = parser
+            .parse("PERFORMER \"A\"\nTRACK 01 AUDIO\nPERFORMER \"A\"\nINDEX 01 00:00:00\n")
+            .unwrap();
+        let second = parser
+            .parse("PERFORMER \"B\"\nTRACK 01 AUDIO\nINDEX 01 00:00:00\n")
+            .unwrap();
+
+        assert_eq!(first.performer.as_deref(), Some("A"));
+        assert_eq!(second.performer.as_deref(), Some("B"));
+        assert_eq!(parser.context.strings.borrow().len(), 1);
+        assert!(parser.parse("TRACK").is_err());
+    }
+}
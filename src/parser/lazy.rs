@@ -0,0 +1,166 @@
+use super::{
+    is_leadout_dummy, parse_as, parse_cue, Context, CueParser, FileState, ParsedTrack, Rule,
+};
+use crate::{Cue, Error, ParseOptions, Track};
+
+/// Iterator over the tracks of a cue, parsing each one only when it's reached. Returned by
+/// [`Cue::parse_tracks_iter`].
+pub struct TrackIter<'i> {
+    input: &'i str,
+    rest: &'i str,
+    header: Cue,
+    files: FileState,
+    context: Context,
+    /// The number of the track returned last
+    previous: Option<u8>,
+}
+
+impl<'i> TrackIter<'i> {
+    pub(crate) fn new(input: &'i str, options: &ParseOptions) -> Result<Self, Error> {
+        let context = Context::new(options);
+        let (header, rest) = input.split_at(find_track(input, 0));
+        let header = parse_as(Rule::header, header, &context, CueParser::header)?;
+
+        Ok(Self {
+            input,
+            rest,
+            files: FileState::new(&header),
+            header,
+            context,
+            previous: None,
+        })
+    }
+
+    /// The global section of the cue, along with any lead-out declared in the tracks so far
+    pub fn header(&self) -> &Cue {
+        &self.header
+    }
+}
+
+impl Iterator for TrackIter<'_> {
+    type Item = Result<Track, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_track()? {
+            Ok(track) => Some(Ok(track)),
+            // positions in the block's error are relative to the block, so report the error from
+            // parsing the whole cue instead
+            Err(e) => Some(Err(parse_cue(self.input, &self.context.options)
+                .err()
+                .unwrap_or(e))),
+        }
+    }
+}
+
+impl TrackIter<'_> {
+    /// Parses the next track, stopping at the first error
+    fn next_track(&mut self) -> Option<Result<Track, Error>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let (block, rest) = self.rest.split_at(find_track(self.rest, 1));
+
+        self.rest = rest;
+
+        let parsed = match parse_track(block, &self.context) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.rest = "";
+                return Some(Err(e));
+            }
+        };
+
+        let (track, leadout) = self.files.resolve(parsed);
+
+        if leadout.is_some() {
+            self.header.leadout = leadout;
+        }
+
+        if self.rest.is_empty() && is_leadout_dummy(track.track_index, self.previous) {
+            self.header.leadout = track.start().or(self.header.leadout);
+            return None;
+        }
+
+        self.previous = Some(track.track_index);
+        Some(Ok(track))
+    }
+}
+
+/// The global section and the tracks of a cue up to the first one that can't be parsed, or
+/// `None` if the global section can't be
+pub(super) fn parse_prefix(input: &str, options: &ParseOptions) -> Option<Cue> {
+    let mut iter = TrackIter::new(input, options).ok()?;
+    let mut tracks = Vec::new();
+
+    while let Some(Ok(track)) = iter.next_track() {
+        tracks.push(track);
+    }
+
+    Some(Cue {
+        tracks,
+        ..iter.header
+    })
+}
+
+fn parse_track(block: &str, context: &Context) -> Result<ParsedTrack, Error> {
+    parse_as(Rule::track_block, block, context, CueParser::track_block)
+}
+
+/// Byte offset of the first line starting with a `TRACK` command, among those starting at or
+/// after byte `skip_bytes`
+fn find_track(input: &str, skip_bytes: usize) -> usize {
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        let is_track = line
+            .trim_start()
+            .strip_prefix("TRACK")
+            .is_some_and(|rest| rest.starts_with([' ', '\t']));
+
+        if is_track && offset >= skip_bytes {
+            return offset;
+        }
+
+        offset += line.len();
+    }
+
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static CUE_EXAMPLE: &str = include_str!("../../test_files/example.cue");
+
+    #[test]
+    fn matches_full_parse() {
+        let cue = Cue::from_str(CUE_EXAMPLE).unwrap();
+        let mut tracks = Cue::parse_tracks_iter(CUE_EXAMPLE).unwrap();
+
+        assert_eq!(tracks.header().title, cue.title);
+
+        let first = tracks.next().unwrap().unwrap();
+
+        assert_eq!(first.title, cue.tracks[0].title);
+        assert_eq!(first.file, cue.tracks[0].file);
+        assert_eq!(tracks.count(), cue.tracks.len() - 1);
+    }
+
+    #[test]
+    fn errors_point_into_full_input() {
+        let input =
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nBOGUS\n";
+        let mut tracks = Cue::parse_tracks_iter(input).unwrap();
+
+        assert!(tracks.next().unwrap().is_ok());
+
+        let Some(Err(Error::Parse(e))) = tracks.next() else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(e.location().line, 5);
+        assert!(tracks.next().is_none());
+    }
+}
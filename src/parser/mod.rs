@@ -1,12 +1,87 @@
-use std::path::PathBuf;
+use std::{cell::RefCell, collections::HashSet, path::PathBuf, sync::Arc};
 
+use pest::error::{ErrorVariant, InputLocation, LineColLocation};
 use pest_consume::{match_nodes, Error, Parser};
 
-use crate::{Cue, FileFormat, Frames, Track, TrackFlags, TrackIndex, TrackMode};
+use crate::{
+    comments, encoding, order, replaygain, Catalog, Cue, CueCommand, FileFormat, Frames, Location,
+    MsfOverflow, ParseError, ParseErrorKind, ParseOptions, ParseOutcome, Track, TrackFlags,
+    TrackIndex, TrackMode, Warning, WarningKind,
+};
+
+mod cst;
+mod fast;
+mod handle;
+mod lazy;
+
+pub use cst::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+pub use handle::CueParserHandle;
+pub use lazy::TrackIter;
+
+/// Track number of the lead-out in a disc's TOC
+const LEADOUT_TRACK: u8 = 0xAA;
+
+/// Whether the last track, numbered `number` after `previous`, is a dummy marking the lead-out
+/// as in the TOC, rather than the 170th track of a long cue
+fn is_leadout_dummy(number: u8, previous: Option<u8>) -> bool {
+    number == LEADOUT_TRACK && previous != Some(LEADOUT_TRACK - 1)
+}
 
 type Result<T> = std::result::Result<T, Error<Rule>>;
-type Node<'i> = pest_consume::Node<'i, Rule, ()>;
+type Node<'i> = pest_consume::Node<'i, Rule, &'i Context>;
+
+/// State shared by every node of a parse
+struct Context {
+    options: ParseOptions,
+    strings: RefCell<HashSet<Arc<str>>>,
+    warnings: RefCell<Vec<Warning>>,
+}
+
+impl Context {
+    fn new(options: &ParseOptions) -> Self {
+        Self {
+            options: options.clone(),
+            strings: RefCell::default(),
+            warnings: RefCell::default(),
+        }
+    }
+
+    /// Shares one allocation between equal values when interning is enabled
+    fn text(&self, value: &str) -> Arc<str> {
+        let value = &*self.options.text_normalization.apply(value);
+
+        if !self.options.intern_strings {
+            return Arc::from(value);
+        }
+
+        let mut strings = self.strings.borrow_mut();
+
+        if let Some(shared) = strings.get(value) {
+            return shared.clone();
+        }
+
+        let shared = Arc::<str>::from(value);
+        strings.insert(shared.clone());
+
+        shared
+    }
+
+    fn warn(&self, kind: WarningKind, message: impl Into<String>, span: pest::Span) {
+        self.warnings.borrow_mut().push(Warning {
+            kind,
+            message: message.into(),
+            location: location(span),
+        });
+    }
+
+    /// Forgets the strings and warnings of a previous parse, keeping the allocated capacity
+    fn reset(&self) {
+        self.strings.borrow_mut().clear();
+        self.warnings.borrow_mut().clear();
+    }
+}
 
+#[derive(Clone)]
 struct CueFile {
     path: PathBuf,
     format: FileFormat,
@@ -21,29 +96,47 @@ impl CueFile {
     }
 }
 
-enum GlobalProperty {
-    Catalog(String),
-    CdTextFile(PathBuf),
-    File(CueFile),
-    Performer(String),
-    Songwriter(String),
-    Title(String),
-    Rem(String),
-    Arranger(String),
+/// A track along with the global state that was declared inside it
+struct ParsedTrack {
+    track: Track,
+    /// A `FILE` following the track's `INDEX 01`, which belongs to the tracks after it
+    next_file: Option<CueFile>,
+    leadout: Option<Frames>,
 }
 
-enum TrackProperty {
-    File(CueFile),
-    Flags(TrackFlags),
-    Performer(String),
-    SongWriter(String),
-    Title(String),
-    Index(TrackIndex),
-    Isrc(String),
-    PreGap(Frames),
-    PostGap(Frames),
-    Rem(String),
-    Arranger(String),
+/// The `FILE` in effect, carried from one track to the next
+struct FileState(Option<CueFile>);
+
+impl FileState {
+    fn new(cue: &Cue) -> Self {
+        Self(
+            cue.path
+                .clone()
+                .map(|path| CueFile::new(path, cue.format.clone())),
+        )
+    }
+
+    /// Places a track without a `FILE` of its own in the last one declared, returning it along
+    /// with any lead-out declared inside it
+    fn resolve(&mut self, parsed: ParsedTrack) -> (Track, Option<Frames>) {
+        let ParsedTrack {
+            mut track,
+            next_file,
+            leadout,
+        } = parsed;
+
+        match (&track.file, &self.0) {
+            (Some(path), _) => self.0 = Some(CueFile::new(path.clone(), track.format.clone())),
+            (None, Some(current)) => track.set_file(current.path.clone(), current.format.clone()),
+            (None, None) => {}
+        }
+
+        if next_file.is_some() {
+            self.0 = next_file;
+        }
+
+        (track, leadout)
+    }
 }
 
 #[derive(Parser)]
@@ -56,25 +149,124 @@ impl CueParser {
         Ok(())
     }
 
-    fn string(i: Node) -> Result<String> {
-        Ok(i.as_str().trim_matches('"').to_string())
+    fn string(i: Node<'_>) -> Result<&str> {
+        match_nodes!(i.into_children();
+            [quoted_string(string)] => Ok(string),
+            [curly_string(string)] => Ok(string),
+            [unquoted_string(string)] => Ok(string),
+        )
     }
 
-    fn integer(i: Node) -> Result<usize> {
-        Ok(i.as_str().parse().map_err(|e| i.error(e))?)
+    /// Typographic quotes are only taken as quotes in lenient mode, and are otherwise part of
+    /// unquoted text as before
+    fn curly_string(i: Node<'_>) -> Result<&str> {
+        let context = *i.user_data();
+
+        if !context.options.lenient {
+            return unquoted(i);
+        }
+
+        context.warn(
+            WarningKind::TypographicQuotes,
+            "typographic quotes should be straight",
+            i.as_span(),
+        );
+
+        let text = i.as_str();
+        let open = text.chars().next().map_or(0, char::len_utf8);
+        let close = text.chars().next_back().map_or(0, char::len_utf8);
+
+        Ok(&text[open..text.len() - close])
     }
 
-    fn msf_time(i: Node) -> Result<Frames> {
+    fn quoted_string(i: Node<'_>) -> Result<&str> {
+        Ok(i.as_str().trim_matches('"'))
+    }
+
+    fn unquoted_string(i: Node<'_>) -> Result<&str> {
+        unquoted(i)
+    }
+
+    fn path(i: Node<'_>) -> Result<&str> {
         match_nodes!(i.into_children();
-            [integer(m), integer(s), integer(f)] => Ok(Frames::from_msf(m, s, f))
+            [quoted_string(path)] => Ok(path),
+            [unquoted_path(path)] => Ok(path),
         )
     }
 
+    fn unquoted_path(i: Node<'_>) -> Result<&str> {
+        unquoted(i)
+    }
+
+    /// Skips a line that isn't a known command, which is only allowed in lenient mode
+    fn unknown_command(i: Node) -> Result<()> {
+        let context = *i.user_data();
+        let word = i.as_str().split_whitespace().next().unwrap_or_default();
+
+        if !context.options.lenient {
+            return Err(i.error(format!("Unknown command {word}")));
+        }
+
+        let message = match suggest(word) {
+            Some(suggestion) => {
+                format!("skipped unknown command {word}, did you mean {suggestion}?")
+            }
+            None => format!("skipped unknown command {word}"),
+        };
+
+        context.warn(WarningKind::UnknownCommand, message, i.as_span());
+
+        Ok(())
+    }
+
+    fn integer(i: Node) -> Result<usize> {
+        Ok(i.as_str().parse().map_err(|e| i.error(e))?)
+    }
+
+    /// Numbers above 99 are accepted for the cues of long mixes, and flagged by
+    /// [`Cue::lint`](crate::Cue::lint)
+    fn track_number(i: Node) -> Result<u8> {
+        i.as_str()
+            .parse()
+            .map_err(|_| i.error("Track number is above 255, the most that can be read"))
+    }
+
+    /// Matched as a single token for speed, so split into its fields here. Some mixing tools
+    /// write HH:MM:SS:FF for long programs, which is folded into minutes with
+    /// [`ParseOptions::hour_times`].
     fn time(i: Node) -> Result<Frames> {
-        match_nodes!(i.into_children();
-            [msf_time(time)] => Ok(time),
-            [integer(frames)] => Ok(Frames::new(frames)),
-        )
+        let hour_times = i.user_data().options.hour_times;
+        let fields = i
+            .as_str()
+            .split(':')
+            .map(|field| field.parse().map_err(|e| i.error(e)))
+            .collect::<Result<Vec<u32>>>()?;
+
+        let (m, s, f) = match fields[..] {
+            [h, m, s, f] if hour_times => {
+                if m >= 60 {
+                    out_of_range(&i, "minutes")?;
+                }
+
+                (h.saturating_mul(60).saturating_add(m), s, f)
+            }
+            [m, s, f] => (m, s, f),
+            [frames] => return Frames::try_from(frames).map_err(|e| i.error(e)),
+            [_, _, _, _] => {
+                return Err(i.error(
+                    "Expected MM:SS:FF or a number of frames, as HH:MM:SS:FF needs hour times to be enabled",
+                ))
+            }
+            _ => return Err(i.error("Expected MM:SS:FF, HH:MM:SS:FF or a number of frames")),
+        };
+
+        if s >= 60 {
+            out_of_range(&i, "seconds")?;
+        } else if f >= 75 {
+            out_of_range(&i, "frames")?;
+        }
+
+        Frames::from_msf(m, s, f).map_err(|e| i.error(e))
     }
 
     fn catalog_number(i: Node) -> Result<String> {
@@ -82,46 +274,21 @@ impl CueParser {
     }
 
     fn file_format(i: Node) -> Result<FileFormat> {
-        let file_format = match i.as_str() {
-            "BINARY" => FileFormat::Binary,
-            "MOTOROLA" => FileFormat::Motorola,
-            "AIFF" => FileFormat::Aiff,
-            "WAVE" => FileFormat::Wave,
-            "MP3" => FileFormat::Mp3,
-            _ => FileFormat::Unspecified,
-        };
-
-        Ok(file_format)
+        i.as_str()
+            .parse()
+            .map_err(|_| i.error("Expected file format"))
     }
 
     fn flag(i: Node) -> Result<TrackFlags> {
-        let flag = match i.as_str() {
-            "DCP" => TrackFlags::DIGITAL_COPY_PERMITTED,
-            "4CH" => TrackFlags::FOUR_CHANNEL,
-            "PRE" => TrackFlags::PRE_EMPHASIS_ENABLED,
-            "SCMS" => TrackFlags::SERIAL_COPY_MANAGEMENT_SYSTEM,
-            _ => return Err(i.error("Expected track flag")),
-        };
-
-        Ok(flag)
+        i.as_str()
+            .parse()
+            .map_err(|_| i.error("Expected track flag"))
     }
 
     fn track_mode(i: Node) -> Result<TrackMode> {
-        use TrackMode::*;
-
-        let mode = match i.as_str() {
-            "AUDIO" => Audio,
-            "CDG" => Cdg,
-            "MODE1/2048" => Mode1_2048,
-            "MODE1/2352" => Mode1_2352,
-            "MODE2/2336" => Mode2_2336,
-            "MODE2/2352" => Mode2_2352,
-            "CDI/2336" => Cdi_2336,
-            "CDI/2352" => Cdi_2352,
-            _ => return Err(i.error("Expected track mode")),
-        };
-
-        Ok(mode)
+        i.as_str()
+            .parse()
+            .map_err(|_| i.error("Expected track mode"))
     }
 
     fn isrc_code(i: Node) -> Result<String> {
@@ -130,8 +297,8 @@ impl CueParser {
 
     fn file(i: Node) -> Result<CueFile> {
         match_nodes!(i.into_children();
-            [string(path), file_format(format)] => Ok(CueFile::new(path, format)),
-            [string(path)] => Ok(CueFile::new(path, FileFormat::Unspecified))
+            [path(path), file_format(format)] => Ok(CueFile::new(path, format)),
+            [path(path)] => Ok(CueFile::new(path, FileFormat::Unspecified)),
         )
     }
 
@@ -158,35 +325,37 @@ impl CueParser {
         )
     }
 
-    fn performer(i: Node) -> Result<String> {
-        match_nodes!(i.into_children();
-            [string(val)] => Ok(val)
+    fn performer(i: Node) -> Result<Option<Arc<str>>> {
+        let context = *i.user_data();
+
+        match_nodes!(i.children();
+            [string(val)] => Ok(Some(context.text(val))),
+            [] => missing_value(&i),
         )
     }
 
-    fn songwriter(i: Node) -> Result<String> {
-        match_nodes!(i.into_children();
-            [string(val)] => Ok(val)
+    fn songwriter(i: Node) -> Result<Option<Arc<str>>> {
+        let context = *i.user_data();
+
+        match_nodes!(i.children();
+            [string(val)] => Ok(Some(context.text(val))),
+            [] => missing_value(&i),
         )
     }
 
-    fn title(i: Node) -> Result<String> {
-        match_nodes!(i.into_children();
-            [string(val)] => Ok(val)
+    fn title(i: Node) -> Result<Option<String>> {
+        let context = *i.user_data();
+
+        match_nodes!(i.children();
+            [string(val)] => Ok(Some(context.options.text_normalization.apply(val).into_owned())),
+            [] => missing_value(&i),
         )
     }
 
     fn index(i: Node) -> Result<TrackIndex> {
         match_nodes!(i.into_children();
-            [integer(index), time(time)] => Ok(TrackIndex {
-                index,
-                time: Some(time),
-            }),
-
-            [integer(index)] => Ok(TrackIndex {
-                index,
-                time: None,
-            }),
+            [integer(index), time(time)] => Ok(TrackIndex::new(index, Some(time))),
+            [integer(index)] => Ok(TrackIndex::new(index, None)),
         )
     }
 
@@ -208,140 +377,1078 @@ impl CueParser {
         )
     }
 
+    fn leadout(i: Node) -> Result<Frames> {
+        match_nodes!(i.into_children();
+            [time(time)] => Ok(time)
+        )
+    }
+
     fn rem(i: Node) -> Result<String> {
         match_nodes!(i.into_children();
             [rem_text(comment)] => Ok(comment)
         )
     }
 
+    /// A `;` or `//` comment, kept like the text of a `REM`
+    fn alt_comment(i: Node) -> Result<String> {
+        let context = *i.user_data();
+
+        if !context.options.alternative_comments {
+            // the same error as before the grammar knew of them
+            let variant = ErrorVariant::ParsingError {
+                positives: vec![Rule::global_property],
+                negatives: vec![],
+            };
+
+            return Err(Error::new_from_span(variant, i.as_span()));
+        }
+
+        match_nodes!(i.into_children();
+            [rem_text(comment)] => Ok(comment)
+        )
+    }
+
     fn rem_text(i: Node) -> Result<String> {
         Ok(i.as_str().into())
     }
 
     // CDTEXT commands
-    fn arranger(i: Node) -> Result<String> {
-        match_nodes!(i.into_children();
-            [string(arranger)] => Ok(arranger)
+    fn arranger(i: Node) -> Result<Option<Arc<str>>> {
+        let context = *i.user_data();
+
+        match_nodes!(i.children();
+            [string(arranger)] => Ok(Some(context.text(arranger))),
+            [] => missing_value(&i),
         )
     }
 
     // global section
     fn global_section(i: Node) -> Result<Cue> {
-        use GlobalProperty::*;
+        let mut global = GlobalSection::default();
 
-        match_nodes!(i.into_children();
-            [global_property(properties)..] => {
-                let mut cue = Cue::default();
-
-                properties.for_each(|property| {
-                    match property {
-                        Catalog(catalog) => cue.catalog = Some(catalog),
-                        CdTextFile(path) => cue.cd_text_file = Some(path),
-                        File(file) => {
-                            cue.path = Some(file.path);
-                            cue.format = file.format;
-                        }
-                        Performer(performer) => cue.performer = Some(performer),
-                        Songwriter(songwriter) => cue.songwriter = Some(songwriter),
-                        Title(title) => cue.title = Some(title),
-                        Rem(comment) => cue.comments.push(comment),
-                        Arranger(arranger) => cue.arranger = Some(arranger),
-                    }
-                });
+        for property in i.into_children() {
+            if let Rule::index | Rule::isrc | Rule::flags | Rule::pregap | Rule::postgap =
+                property.as_rule()
+            {
+                return Err(property.error("Only allowed inside a TRACK"));
+            }
 
-                Ok(cue)
+            if let Some(command) = command(property)? {
+                global.add(command);
             }
+        }
+
+        Ok(global.finish())
+    }
+
+    // track section
+    fn track_list(i: Node) -> Result<Vec<ParsedTrack>> {
+        match_nodes!(i.into_children();
+            [track(tracks)..] => Ok(tracks.collect())
         )
     }
 
-    fn global_property(i: Node) -> Result<GlobalProperty> {
-        use GlobalProperty::*;
+    fn track(i: Node) -> Result<ParsedTrack> {
+        let mut properties = i.children();
+        let first = properties.next().ok_or_else(|| i.error("Expected TRACK"))?;
+        let mut track = TrackSection::new(Self::track_command(first)?);
 
-        let property = match_nodes!(i.into_children();
-            [catalog(catalog)] => Catalog(catalog),
-            [cd_text_file(cdtext)] => CdTextFile(cdtext),
-            [file(file)] => File(file),
-            [performer(performer)] => Performer(performer),
-            [songwriter(writer)] => Songwriter(writer),
-            [title(title)] => Title(title),
-            [rem(comment)] => Rem(comment),
-            [arranger(arranger)] => Arranger(arranger),
-        );
+        for property in properties {
+            match property.as_rule() {
+                // the last track's final command ends at the end of input
+                Rule::EOI => {}
+                Rule::catalog | Rule::cd_text_file | Rule::track_command => {
+                    return Err(property.error("Unexpected track command"))
+                }
+                _ => {
+                    if let Some(command) = command(property)? {
+                        track.add(command);
+                    }
+                }
+            }
+        }
 
-        Ok(property)
+        Ok(track.finish())
     }
 
-    // track section
-    fn track_list(i: Node) -> Result<Vec<Track>> {
+    fn track_command(i: Node) -> Result<Track> {
         match_nodes!(i.into_children();
-            [track(tracks)..] => Ok(tracks.collect())
+            [track_number(track_index), track_mode(mode)] => Ok(Track::new(track_index, mode)),
         )
     }
 
-    fn track(i: Node) -> Result<Track> {
-        use TrackProperty::*;
-
+    // entry point
+    fn cue(i: Node) -> Result<Cue> {
         match_nodes!(i.into_children();
-            [track_command(mut track), track_property(properties).., _] => {
-                properties.for_each(|property|
-                    match property {
-                        File(file) => track.set_file(file.path, file.format),
-                        Flags(flags) => track.flags |= flags,
-                        Performer(performer) => track.performer = Some(performer),
-                        SongWriter(songwriter) => track.songwriter = Some(songwriter),
-                        Title(title) => track.title = Some(title),
-                        Index(index) => track.indices.push(index),
-                        Isrc(isrc) => track.isrc = Some(isrc),
-                        PreGap(pregap) => track.pregap = Some(pregap),
-                        PostGap(postgap) => track.postgap = Some(postgap),
-                        Rem(comment) => track.comments.push(comment),
-                        Arranger(arranger) => track.arranger = Some(arranger)
-                    }
-                );
+            [global_section(cue), track_list(tracks), EOI(_)] => Ok(assemble(cue, tracks))
+        )
+    }
 
-                Ok(track)
-            }
+    // piecewise entry points
+    fn header(i: Node) -> Result<Cue> {
+        match_nodes!(i.into_children();
+            [global_section(cue), EOI(_)] => Ok(cue)
         )
     }
 
-    fn track_command(i: Node) -> Result<Track> {
+    fn track_block(i: Node) -> Result<ParsedTrack> {
         match_nodes!(i.into_children();
-            [integer(track_index), track_mode(mode)] => Ok(Track::new(track_index as u8, mode)),
+            [track(track), EOI(_)] => Ok(track)
         )
     }
+}
+
+/// A command read from a line of a cue, by the grammar or by [`fast`]
+enum Command {
+    Catalog(String),
+    CdTextFile(PathBuf),
+    File(CueFile),
+    Flags(TrackFlags),
+    Performer(Option<Arc<str>>),
+    Songwriter(Option<Arc<str>>),
+    Arranger(Option<Arc<str>>),
+    Title(Option<String>),
+    Index(TrackIndex),
+    Isrc(String),
+    Pregap(Frames),
+    Postgap(Frames),
+    Leadout(Frames),
+    Rem(String),
+    /// A `;` or `//` comment, kept like the text of a `REM` that has no field of its own
+    Comment(String),
+}
+
+impl Command {
+    /// The command a comment before this global one is kept in front of
+    fn global(&self) -> Option<CueCommand> {
+        let command = match self {
+            Self::Catalog(_) => CueCommand::Catalog,
+            Self::CdTextFile(_) => CueCommand::CdTextFile,
+            Self::File(_) => CueCommand::File,
+            Self::Performer(_) => CueCommand::Performer,
+            Self::Songwriter(_) => CueCommand::Songwriter,
+            Self::Arranger(_) => CueCommand::Arranger,
+            Self::Title(_) => CueCommand::Title,
+            _ => return None,
+        };
+
+        Some(command)
+    }
+
+    /// Like [`Command::global`], for a command within a track. A `FILE` there is written before
+    /// the next `TRACK` instead, so comments aren't kept in front of it.
+    fn in_track(&self) -> Option<CueCommand> {
+        let command = match self {
+            Self::Flags(_) => CueCommand::Flags,
+            Self::Performer(_) => CueCommand::Performer,
+            Self::Songwriter(_) => CueCommand::Songwriter,
+            Self::Arranger(_) => CueCommand::Arranger,
+            Self::Title(_) => CueCommand::Title,
+            Self::Isrc(_) => CueCommand::Isrc,
+            Self::Pregap(_) => CueCommand::Pregap,
+            Self::Postgap(_) => CueCommand::Postgap,
+            Self::Index(index) => CueCommand::Index(index.index()),
+            _ => return None,
+        };
+
+        Some(command)
+    }
+}
+
+/// Reads a command of either section, or `None` for an unknown one skipped in lenient mode
+fn command(i: Node) -> Result<Option<Command>> {
+    let command = match i.as_rule() {
+        Rule::catalog => Command::Catalog(CueParser::catalog(i)?),
+        Rule::cd_text_file => Command::CdTextFile(CueParser::cd_text_file(i)?),
+        Rule::file => Command::File(CueParser::file(i)?),
+        Rule::flags => Command::Flags(CueParser::flags(i)?),
+        Rule::performer => Command::Performer(CueParser::performer(i)?),
+        Rule::songwriter => Command::Songwriter(CueParser::songwriter(i)?),
+        Rule::arranger => Command::Arranger(CueParser::arranger(i)?),
+        Rule::title => Command::Title(CueParser::title(i)?),
+        Rule::index => Command::Index(CueParser::index(i)?),
+        Rule::isrc => Command::Isrc(CueParser::isrc(i)?),
+        Rule::pregap => Command::Pregap(CueParser::pregap(i)?),
+        Rule::postgap => Command::Postgap(CueParser::postgap(i)?),
+        Rule::leadout => Command::Leadout(CueParser::leadout(i)?),
+        Rule::rem => Command::Rem(CueParser::rem(i)?),
+        Rule::alt_comment => Command::Comment(CueParser::alt_comment(i)?),
+        Rule::unknown_command => {
+            CueParser::unknown_command(i)?;
+
+            return Ok(None);
+        }
+        _ => return Err(i.error("Unexpected command")),
+    };
+
+    Ok(Some(command))
+}
+
+/// The global section of a cue, built from its commands in turn
+#[derive(Default)]
+struct GlobalSection {
+    cue: Cue,
+    /// Where the comments that aren't yet placed start. Those before the first command are in
+    /// the usual place, and have no position.
+    placed: Option<usize>,
+}
+
+impl GlobalSection {
+    /// Adds a global command. Commands only allowed inside a track are rejected before they get
+    /// here.
+    fn add(&mut self, command: Command) {
+        let cue = &mut self.cue;
+
+        if let Some(before) = command.global() {
+            let start = self.placed.unwrap_or(cue.comments.len());
+
+            comments::place(
+                &mut cue.comment_positions,
+                start..cue.comments.len(),
+                before,
+            );
+            self.placed = Some(cue.comments.len());
+        }
+
+        match command {
+            Command::Catalog(number) => cue.catalog = Some(Catalog::raw(number)),
+            Command::CdTextFile(path) => cue.cd_text_file = Some(path),
+            Command::File(file) => {
+                cue.path = Some(file.path);
+                cue.format = file.format;
+            }
+            Command::Performer(performer) => cue.performer = performer,
+            Command::Songwriter(songwriter) => cue.songwriter = songwriter,
+            Command::Arranger(arranger) => cue.arranger = arranger,
+            Command::Title(title) => cue.title = title,
+            Command::Leadout(leadout) => cue.leadout = Some(leadout),
+            Command::Rem(comment) => {
+                if !set_typed_rem(cue, &comment) {
+                    cue.comments.push(comment);
+                }
+            }
+            Command::Comment(comment) => cue.comments.push(comment),
+            Command::Flags(_)
+            | Command::Index(_)
+            | Command::Isrc(_)
+            | Command::Pregap(_)
+            | Command::Postgap(_) => {}
+        }
+    }
+
+    fn finish(self) -> Cue {
+        let Self { mut cue, placed } = self;
+
+        // those before the command written first end up with no position when written out
+        if let Some(first) = order::first_global_written(&cue) {
+            cue.comment_positions
+                .retain(|position| position.before != first);
+        }
+
+        if let Some(start) = placed {
+            comments::place(
+                &mut cue.comment_positions,
+                start..cue.comments.len(),
+                CueCommand::Track,
+            );
+        }
+
+        cue
+    }
+}
+
+/// A track, built from its `TRACK` command and the commands after it in turn
+struct TrackSection {
+    parsed: ParsedTrack,
+    /// Where the comments that aren't yet placed start
+    placed: usize,
+}
+
+impl TrackSection {
+    fn new(track: Track) -> Self {
+        Self {
+            parsed: ParsedTrack {
+                track,
+                next_file: None,
+                leadout: None,
+            },
+            placed: 0,
+        }
+    }
+
+    /// Adds a command of the track. `CATALOG` and `CDTEXTFILE` end the track section, so they
+    /// never get here.
+    fn add(&mut self, command: Command) {
+        let track = &mut self.parsed.track;
+
+        if let Some(before) = command.in_track() {
+            comments::place(
+                &mut track.comment_positions,
+                self.placed..track.comments.len(),
+                before,
+            );
+            self.placed = track.comments.len();
+        }
+
+        match command {
+            Command::File(file) => {
+                if track.index(1).is_some() {
+                    self.parsed.next_file = Some(file);
+                } else {
+                    track.set_file(file.path, file.format);
+                }
+            }
+            Command::Flags(flags) => track.flags |= flags,
+            Command::Performer(performer) => track.performer = performer,
+            Command::Songwriter(songwriter) => track.songwriter = songwriter,
+            Command::Arranger(arranger) => track.arranger = arranger,
+            Command::Title(title) => track.title = title,
+            Command::Index(index) => track.indices.push(index),
+            Command::Isrc(isrc) => track.isrc = Some(isrc),
+            Command::Pregap(pregap) => track.pregap = Some(pregap),
+            Command::Postgap(postgap) => track.postgap = Some(postgap),
+            Command::Leadout(leadout) => self.parsed.leadout = Some(leadout),
+            Command::Rem(comment) => {
+                if !set_typed_track_rem(track, &comment) {
+                    track.comments.push(comment);
+                }
+            }
+            Command::Comment(comment) => track.comments.push(comment),
+            Command::Catalog(_) | Command::CdTextFile(_) => {}
+        }
+    }
+
+    fn finish(self) -> ParsedTrack {
+        self.parsed
+    }
+}
 
-    fn track_property(i: Node) -> Result<TrackProperty> {
-        let property = match_nodes!(i.into_children();
-            [file(track_file)] => TrackProperty::File(track_file),
-            [flags(flags)] => TrackProperty::Flags(flags),
-            [performer(performer)] => TrackProperty::Performer(performer),
-            [songwriter(songwriter)] => TrackProperty::SongWriter(songwriter),
-            [title(title)] => TrackProperty::Title(title),
-            [index(index)] => TrackProperty::Index(index),
-            [isrc(isrc)] => TrackProperty::Isrc(isrc),
-            [pregap(pregap)] => TrackProperty::PreGap(pregap),
-            [postgap(postgap)] => TrackProperty::PostGap(postgap),
-            [rem(rem)] => TrackProperty::Rem(rem),
-            [arranger(arranger)] => TrackProperty::Arranger(arranger),
+/// Joins the global section of a cue with its tracks, placing those without a `FILE` of their
+/// own in the one before
+fn assemble(mut cue: Cue, tracks: impl IntoIterator<Item = ParsedTrack>) -> Cue {
+    let mut files = FileState::new(&cue);
+
+    for parsed in tracks {
+        let (track, leadout) = files.resolve(parsed);
+
+        if leadout.is_some() {
+            cue.leadout = leadout;
+        }
+
+        cue.tracks.push(track);
+    }
+
+    // some tools mark the lead-out with a final dummy track, numbered as in the TOC
+    let previous = cue
+        .tracks
+        .len()
+        .checked_sub(2)
+        .map(|i| cue.tracks[i].track_index);
+
+    if let Some(track) = cue
+        .tracks
+        .pop_if(|track| is_leadout_dummy(track.track_index, previous))
+    {
+        cue.leadout = track.start().or(cue.leadout);
+    }
+
+    // comments can't be kept before a first track there isn't
+    if cue.tracks.is_empty() {
+        cue.comment_positions
+            .retain(|position| position.before != CueCommand::Track);
+    }
+
+    cue
+}
+
+/// Sets the field of a global `REM` that [`Cue`] has one for, such as `REM GENRE "Alt Rock"`,
+/// or returns false to keep it as a comment
+fn set_typed_rem(cue: &mut Cue, comment: &str) -> bool {
+    let Some((name, value)) = rem_value(comment) else {
+        return false;
+    };
+
+    match name {
+        "GENRE" => cue.genre = Some(value.into()),
+        "DATE" => cue.date = Some(value.into()),
+        "DISCNUMBER" => match value.parse() {
+            Ok(disc) => cue.disc_number = Some(disc),
+            Err(_) => return false,
+        },
+        "TOTALDISCS" => match value.parse() {
+            Ok(discs) => cue.total_discs = Some(discs),
+            Err(_) => return false,
+        },
+        _ => return replaygain::set(&mut cue.replay_gain, "ALBUM", name, value),
+    }
+
+    true
+}
+
+/// Like [`set_typed_rem`], for the `REM DISCNUMBER` and ReplayGain of a track
+fn set_typed_track_rem(track: &mut Track, comment: &str) -> bool {
+    let Some((name, value)) = rem_value(comment) else {
+        return false;
+    };
+
+    match name {
+        "DISCNUMBER" => match value.parse() {
+            Ok(disc) => track.disc_number = Some(disc),
+            Err(_) => return false,
+        },
+        _ => return replaygain::set(&mut track.replay_gain, "TRACK", name, value),
+    }
+
+    true
+}
+
+/// The name of a `REM` and its value without quotes, if it has one
+fn rem_value(comment: &str) -> Option<(&str, &str)> {
+    let (name, value) = comment.split_once([' ', '\t'])?;
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value);
+
+    (!value.is_empty()).then_some((name, value))
+}
+
+/// Applies [`ParseOptions::msf_overflow`] to a time with too many seconds or frames
+fn out_of_range(i: &Node<'_>, unit: &str) -> Result<()> {
+    let context = *i.user_data();
+
+    match context.options.msf_overflow {
+        MsfOverflow::Reject => Err(i.error(format!("Too many {unit} in {}", i.as_str()))),
+        MsfOverflow::Normalize => {
+            context.warn(
+                WarningKind::TimeOutOfRange,
+                format!(
+                    "too many {unit} in {}, carried into the next unit",
+                    i.as_str()
+                ),
+                i.as_span(),
+            );
+
+            Ok(())
+        }
+        MsfOverflow::Accept => Ok(()),
+    }
+}
+
+/// A text command without a value, which leaves its field unset if
+/// [`ParseOptions::allow_missing_values`] is set
+fn missing_value<T>(i: &Node<'_>) -> Result<Option<T>> {
+    let context = *i.user_data();
+    let command = i.as_str();
+
+    if !context.options.allow_missing_values {
+        // the same error as when values were required by the grammar
+        let variant = ErrorVariant::ParsingError {
+            positives: vec![Rule::string],
+            negatives: vec![],
+        };
+
+        return Err(Error::new_from_pos(variant, i.as_span().end_pos()));
+    }
+
+    context.warn(
+        WarningKind::MissingValue,
+        format!("{command} has no value"),
+        i.as_span(),
+    );
+
+    Ok(None)
+}
+
+/// Single words can be left unquoted, but anything with spaces needs lenient mode
+fn unquoted(i: Node<'_>) -> Result<&str> {
+    let text = i.as_str();
+
+    if text.contains([' ', '\t']) {
+        let context = *i.user_data();
+
+        if !context.options.lenient {
+            return Err(i.error("Text containing spaces must be quoted"));
+        }
+
+        context.warn(
+            WarningKind::UnquotedString,
+            "text containing spaces should be quoted",
+            i.as_span(),
         );
+    }
+
+    Ok(text)
+}
+
+pub(crate) fn parse_cue(
+    i: impl AsRef<str>,
+    options: &ParseOptions,
+) -> std::result::Result<Cue, crate::Error> {
+    parse(i.as_ref(), &Context::new(options))
+}
+
+pub(crate) fn parse_outcome(
+    i: impl AsRef<str>,
+    options: &ParseOptions,
+) -> std::result::Result<ParseOutcome, crate::Error> {
+    let context = Context::new(options);
+    let cue = parse(i.as_ref(), &context)?;
 
-        Ok(property)
+    Ok(ParseOutcome {
+        cue,
+        warnings: context.warnings.into_inner(),
+    })
+}
+
+pub(crate) fn parse_bytes(
+    bytes: &[u8],
+    options: &ParseOptions,
+) -> std::result::Result<Cue, crate::Error> {
+    // checked before decoding too, as that's as much work as parsing
+    if let Some(max) = options.max_input_len.filter(|&max| bytes.len() > max) {
+        return Err(too_large(bytes.len(), max).into());
     }
 
-    // entry point
-    fn cue(i: Node) -> Result<Cue> {
-        match_nodes!(i.into_children();
-            [global_section(mut cue), track_list(tracks), EOI(_)] => {
-                cue.tracks = tracks;
-                Ok(cue)
+    parse_cue(encoding::decode(bytes), options)
+}
+
+fn parse(i: &str, context: &Context) -> std::result::Result<Cue, crate::Error> {
+    if let Some(max) = context.options.max_input_len.filter(|&max| i.len() > max) {
+        return Err(too_large(i.len(), max).into());
+    }
+
+    if i.starts_with('\u{FEFF}') {
+        let span = pest::Span::new(i, 0, '\u{FEFF}'.len_utf8()).unwrap();
+
+        context.warn(
+            WarningKind::ByteOrderMark,
+            "skipped a byte order mark",
+            span,
+        );
+    }
+
+    if let Some(cue) = fast::parse(i, context) {
+        return Ok(cue);
+    }
+
+    parse_as(Rule::cue, i, context, CueParser::cue).map_err(|e| match e {
+        crate::Error::Parse(cause) if context.options.keep_partial => {
+            match lazy::parse_prefix(i, &context.options) {
+                Some(parsed) => crate::Error::Partial {
+                    parsed: Box::new(parsed),
+                    cause,
+                },
+                None => crate::Error::Parse(cause),
             }
-        )
+        }
+        e => e,
+    })
+}
+
+/// Parses `input` as `rule` and consumes the result, converting any error to the crate's own
+fn parse_as<T>(
+    rule: Rule,
+    input: &str,
+    context: &Context,
+    consume: fn(Node<'_>) -> Result<T>,
+) -> std::result::Result<T, crate::Error> {
+    CueParser::parse_with_userdata(rule, input, context)
+        .and_then(|nodes| consume(nodes.single()?))
+        .map_err(|e| parse_error(e).into())
+}
+
+/// Longest line kept in a [`ParseError`], in bytes
+const MAX_LINE_TEXT: usize = 1024;
+
+/// Commands that can start a line
+pub(crate) const COMMANDS: &[&str] = &[
+    "ARRANGER",
+    "CATALOG",
+    "CDTEXTFILE",
+    "FILE",
+    "FLAGS",
+    "INDEX",
+    "ISRC",
+    "PERFORMER",
+    "POSTGAP",
+    "PREGAP",
+    "REM",
+    "SONGWRITER",
+    "TITLE",
+    "TRACK",
+    "UPC_EAN",
+];
+
+fn parse_error(e: Error<Rule>) -> ParseError {
+    let (start, end) = match e.location {
+        InputLocation::Pos(pos) => (pos, pos),
+        InputLocation::Span(span) => span,
+    };
+
+    let (line, column) = match e.line_col {
+        LineColLocation::Pos(pos) | LineColLocation::Span(pos, _) => pos,
+    };
+
+    let mut line_text = e.line().trim_end_matches(['\r', '\n']).to_string();
+
+    // a single line could be most of a huge input, which isn't worth copying in full
+    if line_text.len() > MAX_LINE_TEXT {
+        let mut end = MAX_LINE_TEXT;
+
+        while !line_text.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        line_text.truncate(end);
+    }
+
+    let word = line_text.split_whitespace().next().unwrap_or_default();
+    let command = Some(word)
+        .filter(|word| COMMANDS.contains(word))
+        .map(str::to_string);
+
+    // errors at the start of a line are about the command as a whole, rather than its value
+    let at_command = line_text.chars().take(column - 1).all(char::is_whitespace);
+    let unknown_command = at_command && command.is_none() && !word.is_empty();
+    let suggestion = unknown_command.then(|| suggest(word)).flatten();
+
+    let (kind, message, expected) = match &e.variant {
+        _ if unknown_command => {
+            let message = match &suggestion {
+                Some(suggestion) => format!("unknown command {word}, did you mean {suggestion}?"),
+                None => format!("unknown command {word}"),
+            };
+
+            let expected = COMMANDS.iter().map(|command| command.to_string()).collect();
+
+            (ParseErrorKind::UnknownCommand, message, expected)
+        }
+        ErrorVariant::ParsingError { positives, .. } if !positives.is_empty() => {
+            let mut expected: Vec<String> = Vec::new();
+
+            for rule in positives.iter().map(|&rule| describe(rule).to_string()) {
+                if !expected.contains(&rule) {
+                    expected.push(rule);
+                }
+            }
+
+            let message = match expected.split_last() {
+                Some((last, [])) => format!("expected {last}"),
+                Some((last, rest)) => format!("expected {} or {last}", rest.join(", ")),
+                None => unreachable!(),
+            };
+
+            (ParseErrorKind::Syntax, message, expected)
+        }
+        ErrorVariant::ParsingError { .. } => {
+            (ParseErrorKind::Syntax, e.variant.message().into(), vec![])
+        }
+        ErrorVariant::CustomError { message } if at_command => {
+            (ParseErrorKind::MisplacedCommand, message.clone(), vec![])
+        }
+        ErrorVariant::CustomError { message } => {
+            (ParseErrorKind::InvalidValue, message.clone(), vec![])
+        }
+    };
+
+    let location = Location {
+        start,
+        end,
+        line,
+        column,
+    };
+
+    ParseError::new(
+        kind, message, location, line_text, command, expected, suggestion,
+    )
+}
+
+fn too_large(len: usize, max: usize) -> ParseError {
+    let location = Location {
+        start: 0,
+        end: len,
+        line: 1,
+        column: 1,
+    };
+
+    ParseError::new(
+        ParseErrorKind::TooLarge,
+        format!("input is {len} bytes, over the limit of {max}"),
+        location,
+        String::new(),
+        None,
+        vec![],
+        None,
+    )
+}
+
+fn location(span: pest::Span) -> Location {
+    let (line, column) = span.start_pos().line_col();
+
+    Location {
+        start: span.start(),
+        end: span.end(),
+        line,
+        column,
     }
 }
 
-pub(crate) fn parse_cue(i: impl AsRef<str>) -> std::result::Result<Cue, crate::Error> {
-    let nodes = CueParser::parse(Rule::cue, i.as_ref())?;
+/// The command closest to a misspelled `word`, if any is close enough to be a likely typo
+fn suggest(word: &str) -> Option<String> {
+    let word = word.to_ascii_uppercase();
+    let max_distance = (word.len() / 3).max(1);
+
+    COMMANDS
+        .iter()
+        .map(|command| (edit_distance(&word, command), command))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, command)| command.to_string())
+}
+
+/// Number of single character insertions, deletions, substitutions or transpositions of
+/// neighbours needed to turn `a` into `b`
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // rows for the previous two prefixes of `a`, needed to cost transpositions
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// How a rule is described to users, as its command's name where it's a command
+fn describe(rule: Rule) -> &'static str {
+    match rule {
+        Rule::cue
+        | Rule::header
+        | Rule::track_block
+        | Rule::global_section
+        | Rule::global_property
+        | Rule::track_property
+        | Rule::unknown_command
+        | Rule::keyword
+        | Rule::BYTE_ORDER_MARK
+        | Rule::COMMAND_END => "a command",
+        Rule::track_list | Rule::track | Rule::track_command => "TRACK",
+        Rule::catalog => "CATALOG",
+        Rule::cd_text_file => "CDTEXTFILE",
+        Rule::file => "FILE",
+        Rule::flags => "FLAGS",
+        Rule::performer => "PERFORMER",
+        Rule::songwriter => "SONGWRITER",
+        Rule::title => "TITLE",
+        Rule::index => "INDEX",
+        Rule::pregap => "PREGAP",
+        Rule::postgap => "POSTGAP",
+        Rule::isrc => "ISRC",
+        Rule::leadout | Rule::rem | Rule::alt_comment => "REM",
+        Rule::arranger => "ARRANGER",
+        Rule::string
+        | Rule::quoted_string
+        | Rule::curly_string
+        | Rule::unquoted_string
+        | Rule::path
+        | Rule::unquoted_path
+        | Rule::word => "a quoted string",
+        Rule::integer => "a number",
+        Rule::track_number => "a track number",
+        Rule::time => "a time",
+        Rule::track_mode => "a track mode",
+        Rule::catalog_number => "a 12 or 13 digit catalog number",
+        Rule::file_format => "a file format",
+        Rule::flag => "a flag",
+        Rule::isrc_code => "an ISRC",
+        Rule::rem_text => "a comment",
+        Rule::EOI => "the end of the file",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggestions() {
+        assert_eq!(edit_distance("PERFROMER", "PERFORMER"), 1);
+        assert_eq!(edit_distance("TACK", "TRACK"), 1);
+        assert_eq!(edit_distance("", "REM"), 3);
+        assert_eq!(suggest("Titel").as_deref(), Some("TITLE"));
+        assert_eq!(suggest("INDX").as_deref(), Some("INDEX"));
+        assert_eq!(suggest("XYZZY"), None);
+    }
+
+    #[test]
+    fn track_properties_and_files() {
+        let cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nPOSTGAP 00:02:00\nFILE \"b.wav\" WAVE\nTRACK 02 AUDIO\nINDEX 01 00:00:00\nTRACK 03 AUDIO\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(cue.tracks[0].postgap, Some(Frames::PREGAP_2S));
+        assert_eq!(cue.tracks[0].file, Some(PathBuf::from("a.wav")));
+        assert_eq!(cue.tracks[1].file, Some(PathBuf::from("b.wav")));
+        assert_eq!(cue.tracks[2].file, Some(PathBuf::from("b.wav")));
+        assert_eq!(cue.tracks[2].start(), Some(Frames::new(60 * 75)));
+    }
+
+    #[test]
+    fn typed_rem_fields() {
+        let cue = Cue::from_str(
+            "REM GENRE \"Alt Rock\"\nREM DATE 1999\nREM DISCNUMBER 2\nREM TOTALDISCS x\nREM COMMENT \"EAC\"\n",
+        )
+        .unwrap();
 
+        assert_eq!(cue.genre.as_deref(), Some("Alt Rock"));
+        assert_eq!(cue.date.as_deref(), Some("1999"));
+        assert_eq!(cue.disc_number, Some(2));
+        assert_eq!(cue.total_discs, None);
+        assert_eq!(cue.comments, ["TOTALDISCS x", "COMMENT \"EAC\""]);
+        assert_eq!(Cue::from_str(cue.to_string()).unwrap(), cue);
+    }
+
+    #[test]
+    fn whitespace_and_times() {
+        let cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE  \n\n\tTRACK\t01 AUDIO\n  \n\tINDEX 01 00:02:10\n\tPREGAP 150\nTRACK 02 AUDIO\r\nINDEX 01 01:00:00",
+        )
+        .unwrap();
 
-    Ok(CueParser::cue(nodes.single()?)?)
+        assert_eq!(cue.tracks[0].start(), Some(Frames::new(160)));
+        assert_eq!(cue.tracks[0].pregap, Some(Frames::new(150)));
+        assert_eq!(cue.tracks[1].start(), Some(Frames::new(60 * 75)));
+        assert!(Cue::from_str("TRACK 01 AUDIO\nINDEX 01 00:02\n").is_err());
+    }
+
+    #[test]
+    fn leadout() {
+        let from_rem = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nREM LEAD-OUT 03:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(from_rem.leadout, Some(Frames::new(3 * 60 * 75)));
+        assert!(from_rem.tracks[0].comments().is_empty());
+
+        let from_track = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 170 AUDIO\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(from_track.tracks.len(), 1);
+        assert_eq!(from_track.leadout, Some(Frames::new(60 * 75)));
+
+        // a 170th track that follows the 169th isn't a lead-out
+        let mut long = String::new();
+
+        for number in 1..=170 {
+            long += &format!(
+                "TRACK {number:02} AUDIO\nINDEX 01 {:02}:{:02}:00\n",
+                number / 60,
+                number % 60
+            );
+        }
+
+        assert_eq!(Cue::from_str(&long).unwrap().tracks.len(), 170);
+        assert_eq!(Cue::parse_tracks_iter(&long).unwrap().count(), 170);
+    }
+
+    #[test]
+    fn lenient_warnings() {
+        let input = "\u{FEFF}FILE My Album.wav WAVE\nTITLE \u{201C}One Two\u{201D}\nPERFROMER Someone Else\n";
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        assert!(Cue::from_str(input).is_err());
+
+        let ParseOutcome { cue, warnings } = Cue::from_str_with_warnings(input, &options).unwrap();
+        let kinds: Vec<WarningKind> = warnings.iter().map(|w| w.kind).collect();
+
+        assert_eq!(cue.path, Some(PathBuf::from("My Album.wav")));
+        assert_eq!(cue.format, FileFormat::Wave);
+        assert_eq!(cue.title.as_deref(), Some("One Two"));
+        assert_eq!(cue.performer, None);
+        assert_eq!(
+            kinds,
+            [
+                WarningKind::ByteOrderMark,
+                WarningKind::UnquotedString,
+                WarningKind::TypographicQuotes,
+                WarningKind::UnknownCommand
+            ]
+        );
+        assert_eq!(
+            warnings[3].to_string(),
+            "3:1: skipped unknown command PERFROMER, did you mean PERFORMER?"
+        );
+        assert_eq!(warnings[3].code(), "CUE103");
+
+        // outside lenient mode they're part of the text, as they always were
+        let cue = Cue::from_str("TITLE \u{201E}One\u{201C}\n").unwrap();
+
+        assert_eq!(cue.title.as_deref(), Some("\u{201E}One\u{201C}"));
+    }
+
+    #[test]
+    fn missing_values() {
+        let input = "PERFORMER\nTITLE \"\"\n";
+        let options = ParseOptions {
+            allow_missing_values: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Cue::from_str(input).unwrap_err().to_string(),
+            "1:10: expected a quoted string"
+        );
+
+        let ParseOutcome { cue, warnings } = Cue::from_str_with_warnings(input, &options).unwrap();
+
+        assert_eq!((cue.performer, cue.title.as_deref()), (None, Some("")));
+        assert_eq!(warnings[0].to_string(), "1:1: PERFORMER has no value");
+    }
+
+    #[test]
+    fn other_file_formats() {
+        let cue = Cue::from_str("FILE \"a.flac\" FLAC\n").unwrap();
+
+        assert_eq!(cue.format, FileFormat::Other("FLAC".into()));
+        assert_eq!(cue.to_string(), "FILE \"a.flac\" FLAC\n");
+
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        let cue = Cue::from_str_with("FILE My Song.flac\n", &options).unwrap();
+
+        assert_eq!(cue.path, Some(PathBuf::from("My Song.flac")));
+        assert_eq!(cue.format, FileFormat::Unspecified);
+    }
+
+    #[test]
+    fn alternative_comments() {
+        let input = "; ripped by hand\nTRACK 01 AUDIO\n// intro\n";
+        let options = ParseOptions {
+            alternative_comments: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Cue::from_str(input).unwrap_err().to_string(),
+            "1:1: unknown command ;"
+        );
+
+        let cue = Cue::from_str_with(input, &options).unwrap();
+
+        assert_eq!(cue.comments, ["ripped by hand"]);
+        assert_eq!(cue.tracks[0].comments(), ["intro"]);
+    }
+
+    #[test]
+    fn hour_times() {
+        let input = "TRACK 01 AUDIO\nINDEX 01 01:05:30:10\n";
+        let options = ParseOptions {
+            hour_times: true,
+            ..Default::default()
+        };
+
+        assert!(Cue::from_str(input)
+            .unwrap_err()
+            .to_string()
+            .ends_with("Expected MM:SS:FF or a number of frames, as HH:MM:SS:FF needs hour times to be enabled"));
+
+        let cue = Cue::from_str_with(input, &options).unwrap();
+
+        assert_eq!(cue.tracks[0].start(), Frames::from_msf(65, 30, 10).ok());
+        assert!(Cue::from_str("REM LEAD-OUT 02:00:00:00\n").is_err());
+    }
+
+    #[test]
+    fn msf_overflow() {
+        let input = "TRACK 01 AUDIO\nINDEX 01 03:60:80\n";
+        let parse = |msf_overflow| {
+            let options = ParseOptions {
+                msf_overflow,
+                ..Default::default()
+            };
+
+            Cue::from_str_with_warnings(input, &options)
+        };
+
+        let ParseOutcome { cue, warnings } = parse(MsfOverflow::Normalize).unwrap();
+
+        assert_eq!(cue.tracks[0].start(), Frames::from_msf(4, 1, 5).ok());
+        assert_eq!(warnings[0].kind, WarningKind::TimeOutOfRange);
+        assert_eq!(parse(MsfOverflow::Accept).unwrap().warnings, []);
+        assert_eq!(
+            parse(MsfOverflow::Reject).unwrap_err().to_string(),
+            "2:10: INDEX: Too many seconds in 03:60:80"
+        );
+    }
+
+    #[test]
+    fn partial_result() {
+        let input = "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 100:00:00\n";
+        let options = ParseOptions {
+            keep_partial: true,
+            ..Default::default()
+        };
+
+        let Err(crate::Error::Partial { parsed, cause }) = Cue::from_str_with(input, &options)
+        else {
+            panic!("expected a partial result");
+        };
+
+        assert_eq!(parsed.tracks.len(), 1);
+        assert_eq!(parsed.tracks[0].file, Some(PathBuf::from("a.wav")));
+        assert_eq!(cause.location().line, 5);
+
+        assert!(matches!(
+            Cue::from_str_with("TITLE\nTRACK 01 AUDIO\n", &options),
+            Err(crate::Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn intern_strings() {
+        let options = ParseOptions {
+            intern_strings: true,
+            ..Default::default()
+        };
+        let cue = Cue::from_str_with(
+            "PERFORMER Band\nTRACK 01 AUDIO\nPERFORMER Band\nTRACK 02 AUDIO\nPERFORMER Band\n",
+            &options,
+        )
+        .unwrap();
+        let performers: Vec<&Arc<str>> = cue.tracks.iter().flat_map(|t| &t.performer).collect();
+
+        assert!(Arc::ptr_eq(cue.performer.as_ref().unwrap(), performers[0]));
+        assert!(Arc::ptr_eq(performers[0], performers[1]));
+    }
 }
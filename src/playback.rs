@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use crate::{Cue, Frames};
+
+/// A track of a rip as a stream of its own, in samples per channel of its file's decoded audio,
+/// which is the time base of decoders such as symphonia's
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VirtualTrack<'a> {
+    pub track: u8,
+    pub file: Option<&'a Path>,
+    /// The track's `INDEX 01`. Its pregap belongs to the track before, as players play it.
+    pub start: u64,
+    /// The next track's `INDEX 01`, or `None` if the track runs to the end of the file
+    pub end: Option<u64>,
+}
+
+impl VirtualTrack<'_> {
+    /// Length in samples, if the end is known
+    pub fn len(&self) -> Option<u64> {
+        self.end.map(|end| end.saturating_sub(self.start))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Whether a position in the file's audio is in this track
+    pub fn contains(&self, sample: u64) -> bool {
+        self.start <= sample && self.end.is_none_or(|end| sample < end)
+    }
+
+    /// A position in the file's audio as one from the start of the track, for showing a track's
+    /// elapsed time or seeking within it
+    pub fn to_track_position(&self, sample: u64) -> u64 {
+        sample.saturating_sub(self.start)
+    }
+
+    /// A position from the start of the track as one in the file's audio
+    pub fn to_file_position(&self, sample: u64) -> u64 {
+        self.start + sample
+    }
+}
+
+impl Cue {
+    /// Splits a rip into a virtual track for each track with an `INDEX 01`, at `sample_rate`, so
+    /// a player decoding a single file album can treat it as separate tracks. The last track of
+    /// a single file image ends at the lead-out if it's known.
+    pub fn virtual_tracks(&self, sample_rate: u32) -> Vec<VirtualTrack<'_>> {
+        let samples = |frames: Frames| u64::from(frames) * u64::from(sample_rate) / 75;
+        let single_file = self.tracks_by_file().nth(1).is_none();
+        let mut tracks: Vec<VirtualTrack> = Vec::new();
+
+        for track in &self.tracks {
+            let Some(start) = track.start() else {
+                continue;
+            };
+            let file = track.file.as_deref();
+
+            if let Some(previous) = tracks.last_mut().filter(|previous| previous.file == file) {
+                previous.end = Some(samples(start));
+            }
+
+            tracks.push(VirtualTrack {
+                track: track.track_index,
+                file,
+                start: samples(start),
+                end: None,
+            });
+        }
+
+        if let Some(last) = tracks.last_mut().filter(|_| single_file) {
+            last.end = self.leadout.map(samples);
+        }
+
+        tracks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn virtual_tracks() {
+        let cue = Cue::from_str(
+            "FILE \"album.flac\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 00 02:58:00\nINDEX 01 03:00:00\nREM LEAD-OUT 05:00:00\n",
+        )
+        .unwrap();
+
+        let tracks = cue.virtual_tracks(48_000);
+
+        assert_eq!(tracks[0].len(), Some(180 * 48_000));
+        // the pregap of track 2 plays at the end of track 1
+        assert!(tracks[0].contains(179 * 48_000));
+        assert_eq!(tracks[1].end, Some(300 * 48_000));
+        assert_eq!(tracks[1].to_track_position(181 * 48_000), 48_000);
+        assert_eq!(tracks[1].to_file_position(48_000), 181 * 48_000);
+    }
+}
@@ -0,0 +1,213 @@
+//! Playing a single file rip as separate tracks with `symphonia`, whose format reader is wrapped
+//! to tell which track of the cue each packet is in
+
+use symphonia::core::{
+    errors::{Error, Result, SeekErrorKind},
+    formats::{FormatReader, Packet, SeekMode, SeekTo, SeekedTo},
+    units::TimeBase,
+};
+
+use crate::{Cue, VirtualTrack};
+
+/// A format reader of a rip's audio, along with the virtual tracks of its cue in the time base of
+/// the reader's default track
+pub struct CueReader<'a> {
+    reader: Box<dyn FormatReader>,
+    track_id: u32,
+    time_base: TimeBase,
+    tracks: Vec<VirtualTrack<'a>>,
+    /// The virtual track the last packet read was in
+    current: Option<usize>,
+}
+
+impl<'a> CueReader<'a> {
+    /// Wraps a reader of the rip's audio, splitting its default track into the cue's tracks. The
+    /// track's time base needs to count samples, as it does for every lossless format, for its
+    /// timestamps to be the virtual tracks' positions.
+    pub fn new(reader: Box<dyn FormatReader>, cue: &'a Cue) -> Result<Self> {
+        let track = reader
+            .default_track()
+            .ok_or(Error::Unsupported("the audio has no track"))?;
+        let params = &track.codec_params;
+        let sample_rate = match params.time_base {
+            Some(TimeBase { numer: 1, denom }) => denom,
+            Some(_) => return Err(Error::Unsupported("the time base doesn't count samples")),
+            None => params
+                .sample_rate
+                .ok_or(Error::Unsupported("the sample rate isn't known"))?,
+        };
+
+        Ok(Self {
+            track_id: track.id,
+            time_base: TimeBase::new(1, sample_rate),
+            tracks: cue.virtual_tracks(sample_rate),
+            current: None,
+            reader,
+        })
+    }
+
+    /// The time base of the virtual tracks' positions and of the packets read
+    pub fn time_base(&self) -> TimeBase {
+        self.time_base
+    }
+
+    pub fn tracks(&self) -> &[VirtualTrack<'a>] {
+        &self.tracks
+    }
+
+    /// The virtual track the last packet read was in
+    pub fn current_track(&self) -> Option<&VirtualTrack<'a>> {
+        self.current.map(|i| &self.tracks[i])
+    }
+
+    /// Reads the next packet of the audio, keeping track of the virtual track it's in. Packets
+    /// of the reader's other tracks, such as cover art, are skipped.
+    pub fn next_packet(&mut self) -> Result<Packet> {
+        loop {
+            let packet = self.reader.next_packet()?;
+
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            self.current = self
+                .tracks
+                .iter()
+                .position(|track| track.contains(packet.ts));
+
+            return Ok(packet);
+        }
+    }
+
+    /// Seeks to the start of the `i`th virtual track
+    pub fn seek_to_track(&mut self, i: usize) -> Result<SeekedTo> {
+        let start = self
+            .tracks
+            .get(i)
+            .ok_or(Error::SeekError(SeekErrorKind::OutOfRange))?
+            .start;
+
+        self.seek(SeekMode::Accurate, start)
+    }
+
+    /// Seeks to a position in the audio, as one from [`VirtualTrack::to_file_position`]
+    pub fn seek(&mut self, mode: SeekMode, ts: u64) -> Result<SeekedTo> {
+        let seeked = self.reader.seek(
+            mode,
+            SeekTo::TimeStamp {
+                ts,
+                track_id: self.track_id,
+            },
+        )?;
+
+        self.current = self
+            .tracks
+            .iter()
+            .position(|track| track.contains(seeked.actual_ts));
+
+        Ok(seeked)
+    }
+
+    pub fn into_inner(self) -> Box<dyn FormatReader> {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use symphonia::core::{
+        codecs::CodecParameters,
+        formats::{Cue as FormatCue, FormatOptions, Track},
+        io::MediaSourceStream,
+        meta::{Metadata, MetadataLog},
+    };
+
+    use super::*;
+
+    /// Packets of a second each, of 44.1 kHz audio
+    struct Seconds {
+        tracks: Vec<Track>,
+        ts: u64,
+        metadata: MetadataLog,
+    }
+
+    impl FormatReader for Seconds {
+        fn try_new(_: MediaSourceStream, _: &FormatOptions) -> Result<Self> {
+            let mut params = CodecParameters::new();
+            params.with_sample_rate(44_100);
+
+            Ok(Self {
+                tracks: vec![Track::new(1, params)],
+                ts: 0,
+                metadata: MetadataLog::default(),
+            })
+        }
+
+        fn cues(&self) -> &[FormatCue] {
+            &[]
+        }
+
+        fn metadata(&mut self) -> Metadata<'_> {
+            self.metadata.metadata()
+        }
+
+        fn seek(&mut self, _: SeekMode, to: SeekTo) -> Result<SeekedTo> {
+            let SeekTo::TimeStamp { ts, track_id } = to else {
+                unreachable!()
+            };
+
+            self.ts = ts;
+
+            Ok(SeekedTo {
+                track_id,
+                required_ts: ts,
+                actual_ts: ts,
+            })
+        }
+
+        fn tracks(&self) -> &[Track] {
+            &self.tracks
+        }
+
+        fn next_packet(&mut self) -> Result<Packet> {
+            let packet = Packet::new_from_slice(1, self.ts, 44_100, &[]);
+
+            self.ts += 44_100;
+
+            Ok(packet)
+        }
+
+        fn into_inner(self: Box<Self>) -> MediaSourceStream {
+            MediaSourceStream::new(Box::new(Cursor::new(Vec::new())), Default::default())
+        }
+    }
+
+    #[test]
+    fn reads_virtual_tracks() {
+        let cue = Cue::from_str(
+            "FILE \"album.flac\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 00:02:00\n",
+        )
+        .unwrap();
+        let source = MediaSourceStream::new(Box::new(Cursor::new(Vec::new())), Default::default());
+        let reader = Seconds::try_new(source, &FormatOptions::default()).unwrap();
+        let mut reader = CueReader::new(Box::new(reader), &cue).unwrap();
+
+        assert_eq!(reader.time_base(), TimeBase::new(1, 44_100));
+
+        let tracks: Vec<_> = (0..3)
+            .map(|_| {
+                reader.next_packet().unwrap();
+                reader.current_track().unwrap().track
+            })
+            .collect();
+
+        assert_eq!(tracks, [1, 1, 2]);
+
+        reader.seek_to_track(0).unwrap();
+
+        assert_eq!(reader.next_packet().unwrap().ts, 0);
+        assert_eq!(reader.current_track().unwrap().track, 1);
+    }
+}
@@ -0,0 +1,353 @@
+//! Bindings for Python, for music library scripts
+//!
+//! Build a wheel with `maturin build --release --features python`, then:
+//!
+//! ```python
+//! import cueparse
+//!
+//! cue = cueparse.Cue.from_file("album.cue")
+//! for track in cue.tracks:
+//!     print(track.number, track.title, cue.track_length(track.number))
+//! ```
+
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*, types::PyDict};
+
+use crate::{Cue, Diagnostic, Error, Frames, ParseOptions, Track};
+
+create_exception!(
+    cueparse,
+    CueError,
+    PyValueError,
+    "A cue that couldn't be parsed"
+);
+
+fn to_py_err(e: Error) -> PyErr {
+    match e {
+        Error::Io(e) => std::io::Error::new(e.kind(), e.to_string()).into(),
+        e => CueError::new_err(e.to_string()),
+    }
+}
+
+/// A count of 1/75th of a second frames
+#[pyclass(name = "Frames", module = "cueparse", frozen, eq, ord, from_py_object)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct PyFrames(Frames);
+
+#[pymethods]
+impl PyFrames {
+    #[new]
+    fn new(frames: u32) -> PyResult<Self> {
+        Frames::try_from(frames)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[staticmethod]
+    fn from_msf(minutes: u32, seconds: u32, frames: u32) -> PyResult<Self> {
+        Frames::from_msf(minutes, seconds, frames)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn frames(&self) -> u32 {
+        self.0.frames()
+    }
+
+    #[getter]
+    fn seconds(&self) -> f64 {
+        self.0.to_secs_f64()
+    }
+
+    /// `(minutes, seconds, frames)`
+    #[getter]
+    fn msf(&self) -> (u32, u32, u32) {
+        self.0.to_msf()
+    }
+
+    fn __int__(&self) -> u32 {
+        self.0.frames()
+    }
+
+    fn __str__(&self) -> String {
+        let (m, s, f) = self.0.to_msf();
+
+        format!("{m:02}:{s:02}:{f:02}")
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Frames({})", self.0.frames())
+    }
+}
+
+fn frames(frames: Option<Frames>) -> Option<PyFrames> {
+    frames.map(PyFrames)
+}
+
+#[pyclass(name = "Track", module = "cueparse", frozen)]
+#[derive(Debug)]
+pub struct PyTrack(Track);
+
+#[pymethods]
+impl PyTrack {
+    #[getter]
+    fn number(&self) -> u8 {
+        self.0.track_index
+    }
+
+    #[getter]
+    fn title(&self) -> Option<&str> {
+        self.0.title.as_deref()
+    }
+
+    #[getter]
+    fn performer(&self) -> Option<&str> {
+        self.0.performer.as_deref()
+    }
+
+    #[getter]
+    fn songwriter(&self) -> Option<&str> {
+        self.0.songwriter.as_deref()
+    }
+
+    #[getter]
+    fn isrc(&self) -> Option<&str> {
+        self.0.isrc.as_deref()
+    }
+
+    #[getter]
+    fn file(&self) -> Option<String> {
+        let file = self.0.file.as_deref()?;
+
+        Some(file.to_string_lossy().into_owned())
+    }
+
+    /// As written in a cue, e.g. `"AUDIO"`
+    #[getter]
+    fn mode(&self) -> &'static str {
+        self.0.mode.as_str()
+    }
+
+    /// The flags that are set, as written in a cue, e.g. `["DCP", "PRE"]`
+    #[getter]
+    fn flags(&self) -> Vec<&'static str> {
+        self.0.flags.names().collect()
+    }
+
+    /// `(number, time)` for each `INDEX`
+    #[getter]
+    fn indices(&self) -> Vec<(usize, Option<PyFrames>)> {
+        self.0
+            .indices
+            .iter()
+            .map(|index| (index.index(), frames(index.time())))
+            .collect()
+    }
+
+    /// Start of the track proper (`INDEX 01`) within its file
+    #[getter]
+    fn start(&self) -> Option<PyFrames> {
+        frames(self.0.start())
+    }
+
+    #[getter]
+    fn pregap(&self) -> Option<PyFrames> {
+        frames(self.0.pregap)
+    }
+
+    #[getter]
+    fn postgap(&self) -> Option<PyFrames> {
+        frames(self.0.postgap)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Track {:02} {:?}>", self.0.track_index, self.title())
+    }
+}
+
+#[pyclass(name = "Cue", module = "cueparse", frozen)]
+#[derive(Debug)]
+pub struct PyCue(Cue);
+
+#[pymethods]
+impl PyCue {
+    /// Parses a cue, raising `CueError` if it's malformed. `lenient` works around common
+    /// mistakes instead, see [`ParseOptions::lenient`].
+    #[staticmethod]
+    #[pyo3(signature = (text, lenient = false))]
+    fn parse(text: &str, lenient: bool) -> PyResult<Self> {
+        let options = ParseOptions {
+            lenient,
+            ..Default::default()
+        };
+
+        Cue::from_str_with(text, &options)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    /// Reads and parses a cue file, detecting its text encoding
+    #[staticmethod]
+    fn from_file(path: std::path::PathBuf) -> PyResult<Self> {
+        Cue::from_file(path).map(Self).map_err(to_py_err)
+    }
+
+    #[getter]
+    fn title(&self) -> Option<&str> {
+        self.0.title.as_deref()
+    }
+
+    #[getter]
+    fn performer(&self) -> Option<&str> {
+        self.0.performer.as_deref()
+    }
+
+    #[getter]
+    fn songwriter(&self) -> Option<&str> {
+        self.0.songwriter.as_deref()
+    }
+
+    #[getter]
+    fn catalog(&self) -> Option<&str> {
+        self.0.catalog.as_deref()
+    }
+
+    #[getter]
+    fn genre(&self) -> Option<&str> {
+        self.0.genre.as_deref()
+    }
+
+    #[getter]
+    fn date(&self) -> Option<&str> {
+        self.0.date.as_deref()
+    }
+
+    #[getter]
+    fn comments(&self) -> Vec<String> {
+        self.0.comments.clone()
+    }
+
+    #[getter]
+    fn tracks(&self) -> Vec<PyTrack> {
+        self.0.tracks.iter().cloned().map(PyTrack).collect()
+    }
+
+    /// Length of the disc, if the lead-out is known
+    #[getter]
+    fn duration(&self) -> Option<PyFrames> {
+        frames(self.0.duration())
+    }
+
+    fn track(&self, number: u8) -> Option<PyTrack> {
+        self.0.track(number).cloned().map(PyTrack)
+    }
+
+    fn track_length(&self, number: u8) -> Option<PyFrames> {
+        frames(self.0.track_length(number))
+    }
+
+    /// The cue as plain dicts and lists, in the same shape as the `serde` JSON
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(pythonize::pythonize(py, &self.0)?)
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.tracks.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Cue {:?}, {} tracks>", self.title(), self.0.tracks.len())
+    }
+}
+
+/// Checks a cue, returning a dict for each problem with its `code`, `severity`, `message`,
+/// `line` and `column`, or an empty list for a valid cue
+#[pyfunction]
+fn validate<'py>(py: Python<'py>, text: &str) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let options = ParseOptions {
+        lenient: true,
+        ..Default::default()
+    };
+
+    let diagnostics: Vec<Diagnostic> = match Cue::from_str_with_warnings(text, &options) {
+        Ok(outcome) => outcome.warnings.iter().map(Diagnostic::from).collect(),
+        Err(e) => vec![Diagnostic::from(&e)],
+    };
+
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            let dict = PyDict::new(py);
+
+            dict.set_item("code", diagnostic.code)?;
+            dict.set_item("severity", diagnostic.severity.as_str())?;
+            dict.set_item("message", diagnostic.message)?;
+            dict.set_item("line", diagnostic.location.map(|location| location.line))?;
+            dict.set_item(
+                "column",
+                diagnostic.location.map(|location| location.column),
+            )?;
+
+            Ok(dict)
+        })
+        .collect()
+}
+
+#[pymodule]
+fn cueparse(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCue>()?;
+    m.add_class::<PyTrack>()?;
+    m.add_class::<PyFrames>()?;
+    m.add_function(wrap_pyfunction!(validate, m)?)?;
+    m.add("CueError", m.py().get_type::<CueError>())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_from_python() {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "cueparse").unwrap();
+
+            cueparse(&module).unwrap();
+
+            let cue = PyCue::parse(
+                "TITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nFLAGS DCP\nINDEX 01 00:00:00\n",
+                false,
+            )
+            .unwrap();
+            let track = &cue.tracks()[0];
+
+            assert_eq!(cue.title(), Some("Album"));
+            assert_eq!(track.flags(), ["DCP"]);
+            assert_eq!(track.start().unwrap().__str__(), "00:00:00");
+
+            let dict = cue.to_dict(py).unwrap();
+
+            assert_eq!(
+                dict.get_item("title").unwrap().extract::<String>().unwrap(),
+                "Album"
+            );
+
+            let problems = validate(py, "FILE a b.wav WAVE\n").unwrap();
+
+            assert_eq!(
+                problems[0]
+                    .get_item("code")
+                    .unwrap()
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "CUE102"
+            );
+            assert!(PyCue::parse("TRACK", false)
+                .unwrap_err()
+                .is_instance_of::<CueError>(py));
+        });
+    }
+}
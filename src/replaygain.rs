@@ -0,0 +1,95 @@
+use std::hash::{Hash, Hasher};
+
+/// ReplayGain values of the album, from `REM REPLAYGAIN_ALBUM_GAIN` and `REM
+/// REPLAYGAIN_ALBUM_PEAK`, or of a track from the `TRACK` ones within it. Values compare equal
+/// bit for bit, so that cues can still be compared and hashed.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReplayGain {
+    /// The gain to apply, in dB
+    pub gain: f32,
+    /// The loudest sample, where 1.0 is full scale
+    pub peak: Option<f32>,
+}
+
+impl ReplayGain {
+    /// The factor to multiply samples by to apply the gain
+    pub fn scale(&self) -> f32 {
+        10f32.powf(self.gain / 20.0)
+    }
+}
+
+impl PartialEq for ReplayGain {
+    fn eq(&self, other: &Self) -> bool {
+        self.gain.to_bits() == other.gain.to_bits()
+            && self.peak.map(f32::to_bits) == other.peak.map(f32::to_bits)
+    }
+}
+
+impl Eq for ReplayGain {}
+
+impl Hash for ReplayGain {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.gain.to_bits().hash(state);
+        self.peak.map(f32::to_bits).hash(state);
+    }
+}
+
+/// Sets the gain or peak from the `REM` with the given name, such as `REPLAYGAIN_TRACK_GAIN` for
+/// the `TRACK` scope, returning false if it isn't one. A peak is only taken after the gain.
+pub(crate) fn set(
+    replay_gain: &mut Option<ReplayGain>,
+    scope: &str,
+    name: &str,
+    value: &str,
+) -> bool {
+    let Some(name) = name
+        .strip_prefix("REPLAYGAIN_")
+        .and_then(|name| name.strip_prefix(scope))
+    else {
+        return false;
+    };
+
+    match (name, replay_gain) {
+        ("_GAIN", replay_gain) => {
+            let value = value.strip_suffix("dB").unwrap_or(value).trim();
+
+            match value.parse() {
+                Ok(gain) => {
+                    replay_gain.get_or_insert_default().gain = gain;
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+        ("_PEAK", Some(replay_gain)) => match value.parse() {
+            Ok(peak) => {
+                replay_gain.peak = Some(peak);
+                true
+            }
+            Err(_) => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Cue;
+
+    #[test]
+    fn replay_gain_rems() {
+        let cue = Cue::from_str(
+            "REM REPLAYGAIN_ALBUM_GAIN -7.89 dB\nREM REPLAYGAIN_ALBUM_PEAK 0.988556\nFILE \"a.flac\" WAVE\nTRACK 01 AUDIO\nREM REPLAYGAIN_TRACK_GAIN +2.10 dB\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        let album = cue.replay_gain.unwrap();
+        let track = cue.tracks[0].replay_gain.unwrap();
+
+        assert_eq!((album.gain, album.peak), (-7.89, Some(0.988556)));
+        assert_eq!((track.gain, track.peak), (2.1, None));
+        assert_eq!(Cue::from_str(cue.to_string()).unwrap(), cue);
+        assert!((album.scale() - 0.403).abs() < 0.001);
+    }
+}
@@ -0,0 +1,187 @@
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::parser::edit_distance;
+use crate::Cue;
+
+/// How close a [`FileMatch`] is to the file a cue refers to, the closest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MatchKind {
+    /// The file exists as written
+    Exact,
+    /// Only the case of the name differs, as when a cue written on Windows is used on Linux
+    Case,
+    /// The stem is the same but the extension isn't, such as `.flac` for a `.wav` that was
+    /// converted
+    Extension,
+    /// The stems are this many edits apart, ignoring case
+    Similar(usize),
+}
+
+/// A file that may be the one a cue refers to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileMatch {
+    pub path: PathBuf,
+    pub kind: MatchKind,
+}
+
+/// Looks for `file`, relative to `dir` where the cue is. If it doesn't exist, the files in its
+/// directory are searched for ones with the same name in another case, the same stem with
+/// another extension, or a stem a few edits away, and returned closest first.
+pub fn find_file(dir: &Path, file: &Path) -> io::Result<Vec<FileMatch>> {
+    let wanted = dir.join(file);
+
+    if wanted.is_file() {
+        return Ok(vec![FileMatch {
+            path: wanted,
+            kind: MatchKind::Exact,
+        }]);
+    }
+
+    let Some(name) = wanted.file_name().and_then(OsStr::to_str) else {
+        return Ok(Vec::new());
+    };
+    let parent = wanted.parent().unwrap_or(dir);
+    let wanted_stem = stem(name).to_lowercase();
+    // more than about a quarter of the stem changed is a different file
+    let max_distance = (wanted_stem.chars().count() / 4).max(2);
+    let mut matches = Vec::new();
+
+    for entry in parent.read_dir()? {
+        let entry = entry?;
+
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+
+        let Some(candidate) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+        let candidate_stem = stem(&candidate).to_lowercase();
+
+        let kind = if candidate.to_lowercase() == name.to_lowercase() {
+            MatchKind::Case
+        } else if candidate_stem == wanted_stem {
+            MatchKind::Extension
+        } else {
+            match edit_distance(&candidate_stem, &wanted_stem) {
+                distance if distance <= max_distance => MatchKind::Similar(distance),
+                _ => continue,
+            }
+        };
+
+        matches.push(FileMatch {
+            path: entry.path(),
+            kind,
+        });
+    }
+
+    matches.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.path.cmp(&b.path)));
+
+    Ok(matches)
+}
+
+impl Cue {
+    /// Rewrites the `FILE` and `CDTEXTFILE` references to absolute paths, relative to
+    /// `base_dir`, usually the cue's directory. References to files that don't exist are left as
+    /// they are and returned, once each.
+    pub fn resolve_paths(&mut self, base_dir: &Path) -> Vec<PathBuf> {
+        let mut unresolved = Vec::new();
+        let paths = [&mut self.path, &mut self.cd_text_file]
+            .into_iter()
+            .chain(self.tracks.iter_mut().map(|track| &mut track.file))
+            .flatten();
+
+        for path in paths {
+            match std::path::absolute(base_dir.join(&*path)) {
+                Ok(absolute) if absolute.exists() => *path = absolute,
+                _ if !unresolved.contains(path) => unresolved.push(path.clone()),
+                _ => {}
+            }
+        }
+
+        unresolved
+    }
+}
+
+/// The name before its last dot, ignoring a leading dot
+fn stem(name: &str) -> &str {
+    match name.rfind('.') {
+        Some(dot) if dot > 0 => &name[..dot],
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn ranks_candidates() {
+        let dir = std::env::temp_dir().join(format!("cueparse-resolve-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        for name in [
+            "Album.FLAC",
+            "album.flac",
+            "Albun.wav",
+            "other.wav",
+            "album.log",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let found = find_file(&dir, Path::new("album.wav")).unwrap();
+        let kinds: Vec<_> = found
+            .iter()
+            .map(|m| (m.path.file_name().unwrap().to_str().unwrap(), m.kind))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                ("Album.FLAC", MatchKind::Extension),
+                ("album.flac", MatchKind::Extension),
+                ("album.log", MatchKind::Extension),
+                ("Albun.wav", MatchKind::Similar(1)),
+            ]
+        );
+        assert_eq!(
+            find_file(&dir, Path::new("ALBUM.LOG")).unwrap()[0].kind,
+            MatchKind::Case
+        );
+        assert_eq!(
+            find_file(&dir, Path::new("other.wav")).unwrap(),
+            [FileMatch {
+                path: dir.join("other.wav"),
+                kind: MatchKind::Exact
+            }]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn resolves_paths() {
+        let dir = std::env::temp_dir().join(format!("cueparse-paths-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.wav"), "").unwrap();
+
+        let mut cue = Cue::from_str(
+            "CDTEXTFILE \"text.cdt\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nFILE \"b.wav\" WAVE\nTRACK 02 AUDIO\nINDEX 01 00:00:00\nTRACK 03 AUDIO\nINDEX 01 00:01:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            cue.resolve_paths(&dir),
+            [PathBuf::from("text.cdt"), PathBuf::from("b.wav")]
+        );
+        assert_eq!(cue.tracks[0].file, Some(dir.join("a.wav")));
+        assert_eq!(cue.tracks[1].file, Some("b.wav".into()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+//! JSON Schema of the `serde` representation, for services exchanging parsed cues
+
+use std::borrow::Cow;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::{
+    tokens::{FILE_FORMATS, TRACK_FLAGS, TRACK_MODES},
+    Catalog, Cue, FileFormat, Frames, TrackFlags, TrackMode,
+};
+
+/// The schema of a [`Cue`] as serialized with `serde`, e.g. to validate JSON from another
+/// service or generate types for another language from it
+pub fn json_schema() -> Schema {
+    schemars::schema_for!(Cue)
+}
+
+impl JsonSchema for TrackMode {
+    fn schema_name() -> Cow<'static, str> {
+        "TrackMode".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let modes: Vec<_> = TRACK_MODES.iter().map(|(_, name)| *name).collect();
+
+        json_schema!({ "type": "string", "enum": modes })
+    }
+}
+
+impl JsonSchema for FileFormat {
+    fn schema_name() -> Cow<'static, str> {
+        "FileFormat".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let formats: Vec<_> = std::iter::once("UNSPECIFIED")
+            .chain(FILE_FORMATS.iter().map(|(_, name)| *name))
+            .collect();
+
+        json_schema!({
+            "description": "One of the examples, or another format in upper case, such as FLAC",
+            "type": "string",
+            "pattern": "^[A-Z][A-Z0-9_]*$",
+            "examples": formats
+        })
+    }
+}
+
+impl JsonSchema for TrackFlags {
+    fn schema_name() -> Cow<'static, str> {
+        "TrackFlags".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let flags: Vec<_> = TRACK_FLAGS.iter().map(|(_, name)| *name).collect();
+
+        json_schema!({
+            "type": "array",
+            "items": { "type": "string", "enum": flags },
+            "uniqueItems": true
+        })
+    }
+}
+
+impl JsonSchema for Frames {
+    fn schema_name() -> Cow<'static, str> {
+        "Frames".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A count of 1/75th of a second frames",
+            "type": "integer",
+            "minimum": 0,
+            "maximum": Frames::MAX.frames()
+        })
+    }
+}
+
+impl JsonSchema for Catalog {
+    fn schema_name() -> Cow<'static, str> {
+        "Catalog".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A UPC or EAN",
+            "type": "string",
+            "pattern": "^[0-9]{12,13}$"
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_serde_shape() {
+        let schema = serde_json::to_value(json_schema()).unwrap();
+        let defs = &schema["$defs"];
+
+        assert_eq!(
+            schema["properties"]["title"]["type"],
+            serde_json::json!(["string", "null"])
+        );
+        assert_eq!(defs["TrackMode"]["enum"][3], "MODE1/2352");
+        assert_eq!(defs["FileFormat"]["examples"][4], "WAVE");
+        assert_eq!(defs["Frames"]["maximum"], 449999);
+    }
+}
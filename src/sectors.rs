@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use crate::{Cue, FrameSpan, Frames, TrackIndex};
+
+/// Region of a `FILE` holding one track's data, from its first index (so including any `INDEX 00`
+/// pregap) up to the first index of the next track in the same file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectorRange<'a> {
+    pub track_index: u8,
+    pub file: &'a Path,
+    pub sector_size: usize,
+    pub start: Frames,
+    /// `None` for the last track in a file, which runs to the end of it
+    pub end: Option<Frames>,
+    /// Offset of `start` in the file, accounting for differing sector sizes of earlier tracks
+    pub byte_start: u64,
+}
+
+impl SectorRange<'_> {
+    /// Number of sectors in the range, if it doesn't run to the end of the file. Empty if the
+    /// next track starts before this one, which only a malformed cue has.
+    pub fn sectors(&self) -> Option<usize> {
+        self.end
+            .map(|end| end.frames().saturating_sub(self.start.frames()) as usize)
+    }
+
+    /// The frames of the range, if it doesn't run to the end of the file
+    pub fn span(&self) -> Option<FrameSpan> {
+        self.end.map(|end| FrameSpan::new(self.start, end))
+    }
+
+    pub fn byte_end(&self) -> Option<u64> {
+        self.sectors()
+            .map(|sectors| self.byte_start + sectors as u64 * self.sector_size as u64)
+    }
+}
+
+impl Cue {
+    /// Splits the referenced files into per-track sector ranges, in track order. Tracks with no
+    /// `FILE` or no timed `INDEX` can't be located and are left out.
+    pub fn sector_ranges(&self) -> Vec<SectorRange<'_>> {
+        let mut ranges: Vec<SectorRange> = Vec::new();
+
+        for track in &self.tracks {
+            let start = track.indices.iter().filter_map(TrackIndex::time).min();
+
+            let (Some(file), Some(start)) = (track.file.as_deref(), start) else {
+                continue;
+            };
+
+            let mut byte_start = u64::from(start) * track.mode.sector_size() as u64;
+
+            if let Some(previous) = ranges.last_mut().filter(|range| range.file == file) {
+                previous.end = Some(start);
+                byte_start = previous.byte_end().unwrap_or(byte_start);
+            }
+
+            ranges.push(SectorRange {
+                track_index: track.track_index,
+                file,
+                sector_size: track.mode.sector_size(),
+                start,
+                end: None,
+                byte_start,
+            });
+        }
+
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_include_pregaps() {
+        let cue = Cue::from_str(
+            "FILE \"disc.bin\" BINARY\nTRACK 01 MODE1/2048\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 00 00:00:10\nINDEX 01 00:00:12\nFILE \"extra.bin\" BINARY\nTRACK 03 AUDIO\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        let ranges = cue.sector_ranges();
+
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0].end, Some(Frames::new(10)));
+        assert_eq!(ranges[0].byte_end(), Some(2048 * 10));
+        assert_eq!(ranges[1].start, Frames::new(10));
+        assert_eq!(ranges[1].byte_start, 2048 * 10);
+        assert_eq!(ranges[1].end, None);
+        assert_eq!(ranges[2].file, Path::new("extra.bin"));
+        assert_eq!(ranges[2].byte_start, 0);
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use crate::Cue;
+
+/// Where an index starts in decoded audio, from [`Cue::seek_table`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SeekPoint<'a> {
+    pub track: u8,
+    /// The number of the index, 0 for the start of a pregap and 1 for the start of the track
+    pub index: usize,
+    pub file: Option<&'a Path>,
+    /// Position in the file's audio, counted in samples per channel
+    pub sample: u64,
+}
+
+impl SeekPoint<'_> {
+    /// Whether this is the start of a track's pregap, which players skipping to the track jump
+    /// over
+    pub fn is_pregap(&self) -> bool {
+        self.index == 0
+    }
+}
+
+impl Cue {
+    /// Every timed index as a position in samples at `sample_rate`, in order, for a gapless
+    /// player to skip between the tracks of a single file rip. Frames don't divide evenly into
+    /// rates other than multiples of 75 Hz, so positions are rounded down.
+    pub fn seek_table(&self, sample_rate: u32) -> Vec<SeekPoint<'_>> {
+        self.tracks
+            .iter()
+            .flat_map(|track| {
+                track.indices.iter().filter_map(move |index| {
+                    let time = index.time()?;
+
+                    Some(SeekPoint {
+                        track: track.track_index,
+                        index: index.index(),
+                        file: track.file.as_deref(),
+                        sample: u64::from(time) * u64::from(sample_rate) / 75,
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seek_table() {
+        let cue = Cue::from_str(
+            "FILE \"a.flac\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 00 03:00:00\nINDEX 01 03:02:01\n",
+        )
+        .unwrap();
+
+        let table = cue.seek_table(44_100);
+        let samples: Vec<_> = table
+            .iter()
+            .map(|point| (point.track, point.index, point.sample))
+            .collect();
+
+        assert_eq!(
+            samples,
+            [(1, 1, 0), (2, 0, 180 * 44_100), (2, 1, 182 * 44_100 + 588)]
+        );
+        assert!(table[1].is_pregap());
+        assert_eq!(table[1].file, Some(Path::new("a.flac")));
+    }
+}
@@ -0,0 +1,258 @@
+//! Serialization of the types that don't map directly onto serde's data model, using the same
+//! names as in a cue
+
+use std::fmt;
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{FileFormat, Frames, TrackFlags, TrackMode};
+
+/// As a string, e.g. `"MODE1/2352"`
+impl Serialize for TrackMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TrackMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ModeVisitor;
+
+        impl Visitor<'_> for ModeVisitor {
+            type Value = TrackMode;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a track mode, such as \"AUDIO\"")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<TrackMode, E> {
+                value
+                    .parse()
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(ModeVisitor)
+    }
+}
+
+/// As its token, e.g. `"WAVE"` or `"FLAC"`, or `"UNSPECIFIED"`
+impl Serialize for FileFormat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str().unwrap_or("UNSPECIFIED"))
+    }
+}
+
+impl<'de> Deserialize<'de> for FileFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FormatVisitor;
+
+        impl Visitor<'_> for FormatVisitor {
+            type Value = FileFormat;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a file format, such as \"WAVE\"")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<FileFormat, E> {
+                match value {
+                    "UNSPECIFIED" => Ok(FileFormat::Unspecified),
+                    value => value
+                        .parse()
+                        .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(FormatVisitor)
+    }
+}
+
+/// As a list of the flags that are set, e.g. `["DCP", "PRE"]`
+impl Serialize for TrackFlags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.names().count()))?;
+
+        for name in self.names() {
+            seq.serialize_element(name)?;
+        }
+
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for TrackFlags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FlagsVisitor;
+
+        impl<'de> Visitor<'de> for FlagsVisitor {
+            type Value = TrackFlags;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a list of track flags, such as [\"DCP\"]")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<TrackFlags, A::Error> {
+                let mut flags = TrackFlags::empty();
+
+                while let Some(value) = seq.next_element::<String>()? {
+                    flags |= value.parse::<TrackFlags>().map_err(|_| {
+                        de::Error::invalid_value(de::Unexpected::Str(&value), &"a track flag")
+                    })?;
+                }
+
+                Ok(flags)
+            }
+        }
+
+        deserializer.deserialize_seq(FlagsVisitor)
+    }
+}
+
+/// `with` helpers that serialize [`Frames`] as an `"MM:SS:FF"` string, which is easier to read
+/// and edit by hand than a frame count. Either form is accepted when deserializing.
+///
+/// ```
+/// # use cueparse::Frames;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Chapter {
+///     #[serde(with = "cueparse::serde_msf")]
+///     start: Frames,
+///     #[serde(with = "cueparse::serde_msf::option")]
+///     end: Option<Frames>,
+/// }
+/// ```
+pub mod msf {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(frames: &Frames, serializer: S) -> Result<S::Ok, S::Error> {
+        let (m, s, f) = frames.to_msf();
+
+        serializer.collect_str(&format_args!("{m:02}:{s:02}:{f:02}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Frames, D::Error> {
+        deserializer.deserialize_any(MsfVisitor)
+    }
+
+    struct MsfVisitor;
+
+    impl Visitor<'_> for MsfVisitor {
+        type Value = Frames;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a time as \"MM:SS:FF\" or a number of frames")
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<Frames, E> {
+            Frames::try_from(value).map_err(E::custom)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<Frames, E> {
+            let value = u64::try_from(value)
+                .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))?;
+
+            self.visit_u64(value)
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Frames, E> {
+            let invalid = || E::invalid_value(de::Unexpected::Str(value), &self);
+            let mut fields = value
+                .split(':')
+                .map(|field| field.parse().map_err(|_| invalid()));
+
+            match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some(m), Some(s), Some(f), None) => {
+                    Frames::from_msf(m?, s?, f?).map_err(E::custom)
+                }
+                _ => Err(invalid()),
+            }
+        }
+    }
+
+    /// The same for `Option<Frames>`, with `None` as `null`
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            frames: &Option<Frames>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match frames {
+                Some(frames) => super::serialize(frames, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Frames>, D::Error> {
+            #[derive(Deserialize)]
+            struct Msf(#[serde(with = "super")] Frames);
+
+            let frames = Option::<Msf>::deserialize(deserializer)?;
+
+            Ok(frames.map(|Msf(frames)| frames))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cue, Frames};
+
+    #[test]
+    fn round_trip() {
+        let cue = Cue::from_str(
+            "FILE \"a.bin\" BINARY\nTRACK 01 MODE1/2352\nFLAGS DCP PRE\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 00 01:00:00\nINDEX 01 01:02:00\n",
+        )
+        .unwrap();
+
+        let json = serde_json::to_value(&cue).unwrap();
+        let track = &json["tracks"][0];
+
+        assert_eq!(json["format"], "BINARY");
+        assert_eq!(track["mode"], "MODE1/2352");
+        assert_eq!(track["flags"], serde_json::json!(["DCP", "PRE"]));
+        assert_eq!(json["tracks"][1]["indices"][1]["time"], 4650);
+
+        let parsed: Cue = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+        assert!(serde_json::from_str::<Cue>(r#"{"leadout": 450000}"#).is_err());
+    }
+
+    #[test]
+    fn msf_strings() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Chapter {
+            #[serde(with = "crate::serde_msf")]
+            start: Frames,
+            #[serde(with = "crate::serde_msf::option", default)]
+            end: Option<Frames>,
+        }
+
+        let chapter = Chapter {
+            start: Frames::new(4650),
+            end: None,
+        };
+        let json = serde_json::to_string(&chapter).unwrap();
+
+        assert_eq!(json, r#"{"start":"01:02:00","end":null}"#);
+        assert_eq!(serde_json::from_str::<Chapter>(&json).unwrap(), chapter);
+        assert_eq!(
+            serde_json::from_str::<Chapter>(r#"{"start":4650,"end":"1:2:0"}"#).unwrap(),
+            Chapter {
+                start: Frames::new(4650),
+                end: Some(Frames::new(4650)),
+            }
+        );
+        assert!(serde_json::from_str::<Chapter>(r#"{"start":"01:02"}"#).is_err());
+        assert!(serde_json::from_str::<Chapter>(r#"{"start":-1}"#).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{comments, Cue, Track, TrackMode};
+
+/// A session of a multisession disc, from [`Cue::sessions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session<'a> {
+    pub number: u8,
+    pub tracks: &'a [Track],
+}
+
+impl Session<'_> {
+    pub fn is_audio(&self) -> bool {
+        self.tracks.iter().all(Track::is_audio)
+    }
+
+    pub fn is_data(&self) -> bool {
+        !self.tracks.is_empty() && !self.tracks.iter().any(Track::is_audio)
+    }
+}
+
+/// What a disc holds, from [`Cue::disc_kind`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiscKind {
+    /// Only audio tracks, an ordinary audio CD. Cues without tracks count as this.
+    Audio,
+    /// Only data tracks, such as a CD-ROM
+    Data,
+    /// A data track first and audio tracks after it in the same session, as on game discs with
+    /// a CD audio soundtrack
+    MixedMode,
+    /// An audio session followed by a data session, also called Enhanced CD, which audio players
+    /// only see the audio of
+    CdExtra,
+    /// Data and audio in some other order
+    Other,
+}
+
+impl Track {
+    /// Whether the track holds audio, including CD+G's audio with graphics in the subchannels
+    pub fn is_audio(&self) -> bool {
+        matches!(self.mode, TrackMode::Audio | TrackMode::Cdg)
+    }
+}
+
+impl Cue {
+    /// Splits the tracks into sessions, from the `REM SESSION` comments rippers such as Exact
+    /// Audio Copy write before the first track of each. Without them every track is in session
+    /// 1.
+    pub fn sessions(&self) -> Vec<Session<'_>> {
+        let mut sessions: Vec<Session> = Vec::new();
+        let mut number = self.comments.iter().find_map(|c| session(c)).unwrap_or(1);
+        let mut first = 0;
+
+        for (i, track) in self.tracks.iter().enumerate() {
+            // comments after the rest of a track come before the next
+            let next = comments::unplaced(&track.comments, &track.comment_positions, |_| true)
+                .find_map(session);
+
+            if let Some(next) = next.filter(|&next| next != number) {
+                sessions.push(Session {
+                    number,
+                    tracks: &self.tracks[first..=i],
+                });
+                number = next;
+                first = i + 1;
+            }
+        }
+
+        if first < self.tracks.len() || sessions.is_empty() {
+            sessions.push(Session {
+                number,
+                tracks: &self.tracks[first..],
+            });
+        }
+
+        sessions
+    }
+
+    /// Tells audio discs from data and mixed ones, so that a player can skip the data and an
+    /// emulator frontend can tell a game from an album. An audio cue ending in data tracks
+    /// without `REM SESSION` comments counts as CD-Extra, as rippers that don't write them
+    /// lose the session boundary.
+    pub fn disc_kind(&self) -> DiscKind {
+        let audio = self.tracks.iter().filter(|track| track.is_audio()).count();
+
+        if audio == self.tracks.len() {
+            return DiscKind::Audio;
+        }
+
+        if audio == 0 {
+            return DiscKind::Data;
+        }
+
+        let sessions = self.sessions();
+        let regions: Vec<bool> = self
+            .tracks
+            .chunk_by(|a, b| a.is_audio() == b.is_audio())
+            .map(|tracks| tracks[0].is_audio())
+            .collect();
+
+        match regions[..] {
+            [false, true] if sessions.len() == 1 => DiscKind::MixedMode,
+            [true, false]
+                if sessions.len() == 1
+                    || sessions.len() == 2 && sessions[0].is_audio() && sessions[1].is_data() =>
+            {
+                DiscKind::CdExtra
+            }
+            _ => DiscKind::Other,
+        }
+    }
+}
+
+/// The number in a `REM SESSION 02` comment
+fn session(comment: &str) -> Option<u8> {
+    let (command, number) = comment.trim().split_once(char::is_whitespace)?;
+
+    command
+        .eq_ignore_ascii_case("SESSION")
+        .then(|| number.trim().parse().ok())
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_sessions() {
+        let cue = Cue::from_str(
+            "REM SESSION 01\nFILE \"disc.bin\" BINARY\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 03:00:00\nREM SESSION 02\nTRACK 03 MODE2/2352\nINDEX 01 06:00:00\n",
+        )
+        .unwrap();
+
+        let sessions = cue.sessions();
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].tracks.len(), 2);
+        assert!(sessions[0].is_audio());
+        assert_eq!((sessions[1].number, sessions[1].is_data()), (2, true));
+        assert_eq!(cue.disc_kind(), DiscKind::CdExtra);
+
+        let game = Cue::from_str(
+            "FILE \"game.bin\" BINARY\nTRACK 01 MODE1/2352\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 10:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(game.disc_kind(), DiscKind::MixedMode);
+        assert_eq!(game.sessions()[0].number, 1);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Cue, Frames};
+
+/// A stretch of time on a disc or in a file, from `start` up to but not including `end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameSpan {
+    pub start: Frames,
+    pub end: Frames,
+}
+
+impl FrameSpan {
+    pub fn new(start: Frames, end: Frames) -> Self {
+        Self { start, end }
+    }
+
+    /// The length of the span, or zero if it ends before it starts
+    pub fn len(&self) -> Frames {
+        Frames::new(self.end.frames().saturating_sub(self.start.frames()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, frames: Frames) -> bool {
+        self.start <= frames && frames < self.end
+    }
+
+    /// Whether the spans share at least one frame
+    pub fn overlaps(&self, other: &FrameSpan) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The frames in both spans, `None` if there are none
+    pub fn intersection(&self, other: &FrameSpan) -> Option<FrameSpan> {
+        let span = FrameSpan::new(self.start.max(other.start), self.end.min(other.end));
+
+        (!span.is_empty()).then_some(span)
+    }
+}
+
+impl Cue {
+    /// Where a track is in its file, from its `INDEX 01` to the next track's, or to the lead-out
+    /// for the last track of a single file image. Like [`Cue::track_length`], tracks followed by
+    /// another file, or ending a cue of several files, can't be measured.
+    pub fn track_span(&self, track_index: u8) -> Option<FrameSpan> {
+        let position = self.track_position(track_index)?;
+        let track = &self.tracks[position];
+        let start = track.start()?;
+
+        let end = match self.tracks.get(position + 1) {
+            Some(next) if next.file == track.file => next.start()?,
+            Some(_) => return None,
+            // the lead-out is a position on the disc, not in the last file
+            None if self.tracks_by_file().nth(1).is_none() => self.leadout?,
+            None => return None,
+        };
+
+        Some(FrameSpan::new(start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans() {
+        let span = |start, end| FrameSpan::new(Frames::new(start), Frames::new(end));
+
+        assert_eq!(span(75, 150).len(), Frames::SECOND);
+        assert!(span(75, 150).contains(Frames::new(75)));
+        assert!(!span(75, 150).contains(Frames::new(150)));
+        assert!(!span(0, 75).overlaps(&span(75, 150)));
+        assert_eq!(
+            span(0, 100).intersection(&span(75, 150)),
+            Some(span(75, 100))
+        );
+        assert_eq!(span(150, 75).len(), Frames::ZERO);
+
+        let cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 00:01:00\nREM LEAD-OUT 00:03:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(cue.track_span(2), Some(span(75, 225)));
+    }
+}
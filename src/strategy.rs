@@ -0,0 +1,144 @@
+//! [`proptest`] strategies for cues. Like the `arbitrary` implementations, only values that a cue
+//! can hold are generated, so that they can be written out and parsed back.
+
+use proptest::{collection::vec, option, prelude::*};
+
+use crate::{Catalog, Cue, FileFormat, Frames, Track, TrackFlags, TrackIndex, TrackMode};
+
+/// Text that can be written quoted
+const TEXT: &str = "[^\"\r\n]{0,40}";
+
+pub fn frames() -> impl Strategy<Value = Frames> {
+    (0..=Frames::MAX.0).prop_map(Frames)
+}
+
+impl Arbitrary for Frames {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        frames().boxed()
+    }
+}
+
+pub fn track_mode() -> impl Strategy<Value = TrackMode> {
+    use TrackMode::*;
+
+    prop_oneof![
+        Just(Audio),
+        Just(Cdg),
+        Just(Mode1_2048),
+        Just(Mode1_2352),
+        Just(Mode2_2336),
+        Just(Mode2_2352),
+        Just(Cdi_2336),
+        Just(Cdi_2352),
+    ]
+}
+
+pub fn file_format() -> impl Strategy<Value = FileFormat> {
+    use FileFormat::*;
+
+    prop_oneof![
+        Just(Unspecified),
+        Just(Binary),
+        Just(Motorola),
+        Just(Aiff),
+        Just(Wave),
+        Just(Mp3),
+        "[A-Z][A-Z0-9]{0,7}".prop_map(|name| name.parse().unwrap()),
+    ]
+}
+
+/// A track with the given number, in a file of its own or in no file
+pub fn track(number: u8) -> impl Strategy<Value = Track> {
+    let indices = vec((0..=99usize, frames()), 0..4);
+    let text = (
+        option::of(TEXT),
+        option::of(TEXT),
+        option::of(TEXT),
+        option::of("[A-Z]{5}[0-9]{7}"),
+    );
+    let times = (option::of(frames()), option::of(frames()));
+    let file = option::of((TEXT, file_format()));
+
+    (track_mode(), any::<u8>(), indices, text, times, file).prop_map(
+        move |(mode, flags, indices, text, times, file)| {
+            let mut track = Track::new(number, mode);
+
+            track.flags = TrackFlags::from_bits_truncate(flags);
+            track.indices = indices
+                .into_iter()
+                .map(|(index, time)| TrackIndex::new(index, Some(time)))
+                .collect();
+            (track.title, track.performer, track.songwriter, track.isrc) = (
+                text.0,
+                text.1.map(Into::into),
+                text.2.map(Into::into),
+                text.3,
+            );
+            (track.pregap, track.postgap) = times;
+
+            if let Some((path, format)) = file {
+                track.set_file(path + ".wav", format);
+            }
+
+            track
+        },
+    )
+}
+
+/// A cue of up to 99 tracks, numbered from 1 and each in a file
+pub fn cue() -> impl Strategy<Value = Cue> {
+    let tracks = (0..=99u8).prop_flat_map(|len| (1..=len).map(track).collect::<Vec<_>>());
+    let text = (
+        option::of("[0-9]{12,13}"),
+        option::of(TEXT),
+        option::of(TEXT),
+        option::of(TEXT),
+        vec("[^\r\n]{0,40}", 0..4),
+    );
+    let file = (TEXT, file_format());
+
+    (text, option::of(frames()), file, tracks).prop_map(
+        |((catalog, title, performer, songwriter, comments), leadout, (path, format), tracks)| {
+            let mut cue = Cue {
+                catalog: catalog.map(Catalog::raw),
+                title,
+                performer: performer.map(Into::into),
+                songwriter: songwriter.map(Into::into),
+                comments,
+                leadout,
+                tracks,
+                ..Default::default()
+            };
+
+            for track in &mut cue.tracks {
+                if track.file.is_none() {
+                    track.set_file(format!("{path}.wav"), format.clone());
+                }
+            }
+
+            cue
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn generates_valid_cues(cue in cue()) {
+            for (i, track) in cue.tracks.iter().enumerate() {
+                prop_assert_eq!(usize::from(track.track_index), i + 1);
+                prop_assert!(track.file.is_some());
+            }
+
+            prop_assert!(cue.leadout.is_none_or(|leadout| leadout <= Frames::MAX));
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{Cue, Frames};
+
+/// Tools whose name they write into a `REM COMMENT`, as `REM COMMENT "ExactAudioCopy v1.6"`,
+/// matched case-insensitively
+const GENERATORS: &[(&str, &str)] = &[
+    ("exactaudiocopy", "Exact Audio Copy"),
+    ("xld", "X Lossless Decoder"),
+    ("cuetools", "CUETools"),
+    ("dbpoweramp", "dBpoweramp"),
+    ("foobar2000", "foobar2000"),
+    ("whipper", "whipper"),
+    ("morituri", "morituri"),
+    ("cyanrip", "cyanrip"),
+    ("rubyripper", "Rubyripper"),
+    ("cdex", "CDex"),
+    ("fre:ac", "fre:ac"),
+    ("ez cd audio converter", "EZ CD Audio Converter"),
+    ("audiograbber", "Audiograbber"),
+];
+
+/// How a cue is laid out, from [`Cue::classify_style`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CueStyle {
+    pub layout: FileLayout,
+    pub gaps: GapStyle,
+    /// The ripper or tool that wrote the cue, if its `REM COMMENT` names a known one
+    pub generator: Option<&'static str>,
+}
+
+impl CueStyle {
+    /// Whether the cue was written by a known tool, rather than by hand or by an unknown one
+    pub fn is_machine_generated(&self) -> bool {
+        self.generator.is_some()
+    }
+}
+
+/// How the tracks are split between files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileLayout {
+    /// Every track is in one file, the image of a disc. Cues without tracks count as this.
+    SingleFile,
+    /// Each track has a file of its own
+    FilePerTrack,
+    /// Some files hold several tracks
+    Mixed,
+}
+
+/// Where the gaps between tracks are, the pregaps marked by `INDEX 00`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GapStyle {
+    /// No track has a gap
+    None,
+    /// The gaps are part of the timeline of a single file
+    InImage,
+    /// Each gap is at the end of the file of the track before, with `INDEX 00` before the next
+    /// `FILE`. Exact Audio Copy's default, also called noncompliant.
+    Appended,
+    /// Each gap is at the start of its track's file, also called compliant
+    Prepended,
+    /// The gaps aren't in any file, and are given by `PREGAP` for a burner to add as silence
+    Omitted,
+    /// Gaps are stored in more than one of these ways
+    Mixed,
+}
+
+impl Cue {
+    /// Tells how the cue is laid out, so that tools can pick how to convert or split it: whether
+    /// it's for one file or one per track, where its gaps are, and what wrote it
+    pub fn classify_style(&self) -> CueStyle {
+        let files = self.tracks_by_file().count();
+        let layout = if files <= 1 {
+            FileLayout::SingleFile
+        } else if files == self.tracks.len() {
+            FileLayout::FilePerTrack
+        } else {
+            FileLayout::Mixed
+        };
+
+        let mut styles = Vec::new();
+
+        for (i, track) in self.tracks.iter().enumerate() {
+            let starts_file = i == 0 || self.tracks[i - 1].file != track.file;
+
+            // the first track's gap is at the start of its file in either style
+            if i == 0 && layout != FileLayout::SingleFile {
+                continue;
+            }
+
+            let pregap = track.index(0).and_then(|index| index.time());
+
+            let style = if track.pregap.is_some() {
+                GapStyle::Omitted
+            } else if let (Some(pregap), Some(start)) = (pregap, track.start()) {
+                if layout == FileLayout::SingleFile || !starts_file {
+                    GapStyle::InImage
+                } else if start == Frames::ZERO || pregap > start {
+                    // INDEX 00 can't be in this file, so it's in the one before
+                    GapStyle::Appended
+                } else {
+                    GapStyle::Prepended
+                }
+            } else {
+                continue;
+            };
+
+            if !styles.contains(&style) {
+                styles.push(style);
+            }
+        }
+
+        let gaps = match styles[..] {
+            [] => GapStyle::None,
+            [style] => style,
+            _ => GapStyle::Mixed,
+        };
+
+        let generator = self.comments.iter().find_map(|comment| {
+            let comment = comment.to_lowercase();
+            let text = comment.strip_prefix("comment")?;
+
+            GENERATORS
+                .iter()
+                .find(|(signature, _)| text.contains(signature))
+                .map(|&(_, name)| name)
+        });
+
+        CueStyle {
+            layout,
+            gaps,
+            generator,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_styles() {
+        let style = |text: &str| Cue::from_str(text).unwrap().classify_style();
+
+        let appended = style(
+            "REM COMMENT \"ExactAudioCopy v1.6\"\nFILE \"01.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 00 03:58:20\nFILE \"02.wav\" WAVE\nINDEX 01 00:00:00\n",
+        );
+
+        assert_eq!(
+            appended,
+            CueStyle {
+                layout: FileLayout::FilePerTrack,
+                gaps: GapStyle::Appended,
+                generator: Some("Exact Audio Copy"),
+            }
+        );
+
+        let prepended = style(
+            "FILE \"01.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nFILE \"02.wav\" WAVE\nTRACK 02 AUDIO\nINDEX 00 00:00:00\nINDEX 01 00:02:00\n",
+        );
+
+        assert_eq!(prepended.gaps, GapStyle::Prepended);
+        assert!(!prepended.is_machine_generated());
+
+        let image = style(
+            "FILE \"disc.flac\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 00 03:58:20\nINDEX 01 04:00:20\n",
+        );
+
+        assert_eq!(
+            (image.layout, image.gaps),
+            (FileLayout::SingleFile, GapStyle::InImage)
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Writing a cue's metadata into the tags of the audio files split from it, and reading cues
+//! embedded in tags, with `lofty`
+
+use std::fs::File;
+use std::path::Path;
+
+use lofty::{config::WriteOptions, error::LoftyError, flac::FlacFile, prelude::*, tag::Tag};
+
+use crate::{Cue, ReplayGain, Track};
+
+/// Name of the tag item single-file rips keep their cue in
+const CUESHEET: &str = "CUESHEET";
+
+/// Why a cue couldn't be read from an audio file
+#[derive(Debug, thiserror::Error)]
+pub enum EmbeddedCueError {
+    #[error(transparent)]
+    Read(#[from] LoftyError),
+    #[error("the file has no CUESHEET tag")]
+    Missing,
+    #[error(transparent)]
+    Parse(#[from] crate::Error),
+}
+
+/// Sets the tags of `tag` from the cue and one of its tracks: titles, performers, songwriter,
+/// arranger, ISRC, catalog, track and disc numbers and counts, date, genre and ReplayGain. Tags the cue
+/// has no value for are left as they are.
+pub fn apply(cue: &Cue, track: &Track, tag: &mut Tag) {
+    let mut set = |key, value: Option<&str>| {
+        if let Some(value) = value {
+            tag.insert_text(key, value.into());
+        }
+    };
+
+    set(ItemKey::AlbumTitle, cue.title.as_deref());
+    set(ItemKey::AlbumArtist, cue.performer.as_deref());
+    set(ItemKey::TrackTitle, track.title.as_deref());
+    set(
+        ItemKey::TrackArtist,
+        track.performer.as_deref().or(cue.performer.as_deref()),
+    );
+    set(
+        ItemKey::Composer,
+        track.songwriter.as_deref().or(cue.songwriter.as_deref()),
+    );
+    set(
+        ItemKey::Arranger,
+        track.arranger.as_deref().or(cue.arranger.as_deref()),
+    );
+    set(ItemKey::Isrc, track.isrc.as_deref());
+    set(ItemKey::Barcode, cue.catalog.as_deref());
+    set(ItemKey::RecordingDate, cue.date.as_deref());
+    set(ItemKey::Genre, cue.genre.as_deref());
+    set(ItemKey::TrackNumber, Some(&track.track_index.to_string()));
+    set(ItemKey::TrackTotal, Some(&cue.tracks.len().to_string()));
+    set(
+        ItemKey::DiscNumber,
+        track
+            .disc_number
+            .or(cue.disc_number)
+            .map(|disc| disc.to_string())
+            .as_deref(),
+    );
+    set(
+        ItemKey::DiscTotal,
+        cue.total_discs.map(|discs| discs.to_string()).as_deref(),
+    );
+
+    for (replay_gain, gain_key, peak_key) in [
+        (
+            cue.replay_gain,
+            ItemKey::ReplayGainAlbumGain,
+            ItemKey::ReplayGainAlbumPeak,
+        ),
+        (
+            track.replay_gain,
+            ItemKey::ReplayGainTrackGain,
+            ItemKey::ReplayGainTrackPeak,
+        ),
+    ] {
+        if let Some(ReplayGain { gain, peak }) = replay_gain {
+            set(gain_key, Some(&format!("{gain:+.2} dB")));
+            set(peak_key, peak.map(|peak| format!("{peak:.6}")).as_deref());
+        }
+    }
+}
+
+/// Tags the audio file at `path` as `track` of the cue, see [`apply`]. The file's usual tag
+/// format is used, and created if the file has no tag yet.
+pub fn write(cue: &Cue, track: &Track, path: impl AsRef<Path>) -> Result<(), LoftyError> {
+    let path = path.as_ref();
+    let mut file = lofty::read_from_path(path)?;
+
+    if file.primary_tag().is_none() {
+        file.insert_tag(Tag::new(file.primary_tag_type()));
+    }
+
+    let tag = file.primary_tag_mut().unwrap();
+
+    apply(cue, track, tag);
+    tag.save_to_path(path, WriteOptions::default())
+}
+
+/// The text of the first `CUESHEET` item in the tags, found in the APEv2 tags of Monkey's Audio
+/// and WavPack files and the ID3v2 `TXXX` frames of MP3s. Its name is matched in any case, as
+/// APEv2 writers call it `Cuesheet`.
+pub fn cue_text(tags: &[Tag]) -> Option<&str> {
+    tags.iter()
+        .flat_map(Tag::items)
+        .find(|item| matches!(item.key(), ItemKey::Unknown(key) if key.eq_ignore_ascii_case(CUESHEET)))
+        .and_then(|item| item.value().text())
+}
+
+/// Reads and parses the cue embedded in the tags of the audio file at `path`, see [`cue_text`]
+pub fn read_cue(path: impl AsRef<Path>) -> Result<Cue, EmbeddedCueError> {
+    let file = lofty::read_from_path(path)?;
+    let text = cue_text(file.tags()).ok_or(EmbeddedCueError::Missing)?;
+
+    Ok(Cue::from_str(text)?)
+}
+
+/// Reads and parses the cue in the `CUESHEET` Vorbis comment of the FLAC file at `path`, where
+/// single-file lossless rips usually keep it. The binary `CUESHEET` metadata block isn't read, as
+/// it has no titles or performers.
+pub fn read_flac_cue(path: impl AsRef<Path>) -> Result<Cue, EmbeddedCueError> {
+    let mut file = File::open(path).map_err(LoftyError::from)?;
+    let flac = FlacFile::read_from(&mut file, lofty::config::ParseOptions::new())?;
+    let text = flac
+        .vorbis_comments()
+        .and_then(|comments| comments.get(CUESHEET))
+        .ok_or(EmbeddedCueError::Missing)?;
+
+    Ok(Cue::from_str(text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use lofty::tag::{ItemValue, TagItem, TagType};
+
+    use super::*;
+
+    #[test]
+    fn maps_cue_fields() {
+        let cue = Cue::from_str(
+            "REM GENRE \"Alt Rock\"\nREM DATE 1999\nPERFORMER \"Band\"\nTITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One\"\nISRC USABC9900001\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+        let mut tag = Tag::new(TagType::VorbisComments);
+
+        apply(&cue, &cue.tracks[0], &mut tag);
+
+        assert_eq!(tag.get_string(&ItemKey::TrackTitle), Some("One"));
+        assert_eq!(tag.get_string(&ItemKey::TrackArtist), Some("Band"));
+        assert_eq!(tag.get_string(&ItemKey::Genre), Some("Alt Rock"));
+        assert_eq!(tag.get_string(&ItemKey::RecordingDate), Some("1999"));
+        assert_eq!(tag.get_string(&ItemKey::Isrc), Some("USABC9900001"));
+        assert_eq!(tag.get_string(&ItemKey::TrackTotal), Some("2"));
+    }
+
+    #[test]
+    fn finds_embedded_cue() {
+        let mut ape = Tag::new(TagType::Ape);
+        ape.insert_text(ItemKey::TrackTitle, "Album".into());
+        // unknown keys are only kept by the unchecked insert
+        ape.insert_unchecked(TagItem::new(
+            ItemKey::Unknown("Cuesheet".into()),
+            ItemValue::Text("FILE \"a.ape\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\n".into()),
+        ));
+        let tags = [Tag::new(TagType::Id3v2), ape];
+
+        let cue = Cue::from_str(cue_text(&tags).unwrap()).unwrap();
+
+        assert_eq!(cue.tracks.len(), 1);
+        assert_eq!(cue_text(&tags[..1]), None);
+    }
+
+    #[test]
+    fn reads_flac_cue() {
+        let cue_text = "FILE \"a.flac\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\n";
+        let comment = format!("CUESHEET={cue_text}");
+        let mut comments = Vec::new();
+        comments.extend(0u32.to_le_bytes());
+        comments.extend(1u32.to_le_bytes());
+        comments.extend((comment.len() as u32).to_le_bytes());
+        comments.extend(comment.as_bytes());
+
+        // STREAMINFO for a second of 16 bit stereo at 44.1 kHz, then the comments as the last block
+        let mut flac = b"fLaC\x00\x00\x00\x22".to_vec();
+        flac.extend([0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+        flac.extend([0x0A, 0xC4, 0x42, 0xF0, 0x00, 0x00, 0xAC, 0x44]);
+        flac.extend([0; 16]);
+        flac.push(0x84);
+        flac.extend(&(comments.len() as u32).to_be_bytes()[1..]);
+        flac.extend(comments);
+
+        let path = std::env::temp_dir().join(format!("cueparse-{}.flac", std::process::id()));
+        std::fs::write(&path, flac).unwrap();
+        let cue = read_flac_cue(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(cue.unwrap(), Cue::from_str(cue_text).unwrap());
+    }
+}
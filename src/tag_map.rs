@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+
+use crate::{Cue, Track};
+
+impl Track {
+    /// The track's metadata as Vorbis comment style tags, for taggers of the files split from the
+    /// cue: `ARTIST`, `ALBUM`, `TITLE`, `TRACKNUMBER`, `TOTALTRACKS`, `DATE`, `GENRE` and `ISRC`.
+    /// The performer is the cue's if the track has none, and tags without a value are left out.
+    /// With the `lofty` feature, [`tag::write`](crate::tag::write) tags a file directly.
+    pub fn to_tag_map(&self, cue: &Cue) -> BTreeMap<&'static str, String> {
+        let performer = self.performer.as_deref().or(cue.performer.as_deref());
+
+        [
+            ("ARTIST", performer),
+            ("ALBUM", cue.title.as_deref()),
+            ("TITLE", self.title.as_deref()),
+            ("TRACKNUMBER", Some(&*self.track_index.to_string())),
+            ("TOTALTRACKS", Some(&*cue.tracks.len().to_string())),
+            ("DATE", cue.date.as_deref()),
+            ("GENRE", cue.genre.as_deref()),
+            ("ISRC", self.isrc.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?.to_owned())))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_track_tags() {
+        let cue = Cue::from_str(
+            "REM GENRE Jazz\nPERFORMER \"Band\"\nTITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One\"\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nPERFORMER \"Guest\"\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            cue.tracks[1].to_tag_map(&cue),
+            BTreeMap::from([
+                ("ALBUM", "Album".to_owned()),
+                ("ARTIST", "Guest".to_owned()),
+                ("GENRE", "Jazz".to_owned()),
+                ("TOTALTRACKS", "2".to_owned()),
+                ("TRACKNUMBER", "2".to_owned()),
+            ])
+        );
+        assert_eq!(cue.tracks[0].to_tag_map(&cue)["TITLE"], "One");
+    }
+}
@@ -0,0 +1,254 @@
+use std::str::FromStr;
+
+use crate::{Cue, Track};
+
+/// A pattern for naming the files of a cue's tracks, such as `%tracknumber:2% - %title%`.
+///
+/// Fields are written between `%` signs, with `%%` for a literal `%`:
+///
+/// - `tracknumber`, `totaltracks`, `discnumber` and `totaldiscs`
+/// - `title` and `artist`, the track's, and `album` and `albumartist`, the cue's
+/// - `songwriter`, `arranger` and `isrc`, the track's or else the cue's where it has one
+/// - `genre`, `date` and `catalog`
+///
+/// A field can fall back on others, tried in turn, and lastly on text in quotes:
+/// `%artist|albumartist|"Unknown"%`. A field without a value is left empty. `:` and a width pads
+/// numbers with zeros, as in `%tracknumber:2%` for `01`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Field { choices: Vec<Choice>, width: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Choice {
+    Field(Field),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    TrackNumber,
+    TotalTracks,
+    DiscNumber,
+    TotalDiscs,
+    Title,
+    Artist,
+    Album,
+    AlbumArtist,
+    Songwriter,
+    Arranger,
+    Isrc,
+    Genre,
+    Date,
+    Catalog,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    #[error("unknown field %{0}%")]
+    UnknownField(String),
+    #[error("a % at byte {0} isn't closed")]
+    Unclosed(usize),
+    #[error("the width of %{0}% isn't a number")]
+    Width(String),
+}
+
+impl Template {
+    /// The file name of `track`, without an extension
+    pub fn render(&self, cue: &Cue, track: &Track) -> String {
+        let mut out = String::new();
+
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Field { choices, width } => {
+                    let value = choices.iter().find_map(|choice| match choice {
+                        Choice::Field(field) => field.value(cue, track),
+                        Choice::Text(text) => Some(text.clone()),
+                    });
+
+                    match value {
+                        Some(value) if value.bytes().all(|b| b.is_ascii_digit()) => {
+                            out.push_str(&format!("{value:0>width$}"))
+                        }
+                        Some(value) => out.push_str(&value),
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut rest = s;
+
+        while let Some(start) = rest.find('%') {
+            text.push_str(&rest[..start]);
+
+            let after = &rest[start + 1..];
+            let end =
+                field_end(after).ok_or(TemplateError::Unclosed(s.len() - rest.len() + start))?;
+            let field = &after[..end];
+
+            rest = &after[end + 1..];
+
+            if field.is_empty() {
+                text.push('%');
+                continue;
+            }
+
+            if !text.is_empty() {
+                parts.push(Part::Text(std::mem::take(&mut text)));
+            }
+
+            parts.push(parse_field(field)?);
+        }
+
+        text.push_str(rest);
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self { parts })
+    }
+}
+
+/// Where the `%` closing a field is, skipping any in quoted fallbacks
+fn field_end(field: &str) -> Option<usize> {
+    let mut quoted = false;
+
+    field.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        '%' if !quoted => Some(i),
+        _ => None,
+    })
+}
+
+fn parse_field(field: &str) -> Result<Part, TemplateError> {
+    let (choices, width) = match field.rsplit_once(':').filter(|(_, w)| !w.contains('"')) {
+        Some((choices, width)) => (
+            choices,
+            width
+                .parse()
+                .map_err(|_| TemplateError::Width(field.into()))?,
+        ),
+        None => (field, 0),
+    };
+
+    let choices = choices
+        .split('|')
+        .map(
+            |choice| match choice.strip_prefix('"').and_then(|c| c.strip_suffix('"')) {
+                Some(text) => Ok(Choice::Text(text.into())),
+                None => Field::from_name(choice)
+                    .map(Choice::Field)
+                    .ok_or_else(|| TemplateError::UnknownField(choice.into())),
+            },
+        )
+        .collect::<Result<_, _>>()?;
+
+    Ok(Part::Field { choices, width })
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        let field = match name.to_ascii_lowercase().as_str() {
+            "tracknumber" => Self::TrackNumber,
+            "totaltracks" => Self::TotalTracks,
+            "discnumber" => Self::DiscNumber,
+            "totaldiscs" => Self::TotalDiscs,
+            "title" => Self::Title,
+            "artist" => Self::Artist,
+            "album" => Self::Album,
+            "albumartist" => Self::AlbumArtist,
+            "songwriter" => Self::Songwriter,
+            "arranger" => Self::Arranger,
+            "isrc" => Self::Isrc,
+            "genre" => Self::Genre,
+            "date" => Self::Date,
+            "catalog" => Self::Catalog,
+            _ => return None,
+        };
+
+        Some(field)
+    }
+
+    fn value(self, cue: &Cue, track: &Track) -> Option<String> {
+        match self {
+            Self::TrackNumber => Some(track.track_index.to_string()),
+            Self::TotalTracks => Some(cue.tracks.len().to_string()),
+            Self::DiscNumber => track.disc_number.or(cue.disc_number).map(|n| n.to_string()),
+            Self::TotalDiscs => cue.total_discs.map(|n| n.to_string()),
+            Self::Title => track.title.clone(),
+            Self::Artist => track.performer.as_deref().map(Into::into),
+            Self::Album => cue.title.clone(),
+            Self::AlbumArtist => cue.performer.as_deref().map(Into::into),
+            Self::Songwriter => track
+                .songwriter
+                .as_deref()
+                .or(cue.songwriter.as_deref())
+                .map(Into::into),
+            Self::Arranger => track
+                .arranger
+                .as_deref()
+                .or(cue.arranger.as_deref())
+                .map(Into::into),
+            Self::Isrc => track.isrc.clone(),
+            Self::Genre => cue.genre.clone(),
+            Self::Date => cue.date.clone(),
+            Self::Catalog => cue.catalog.as_deref().map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_fields() {
+        let cue = Cue::from_str(
+            "PERFORMER \"Band\"\nTITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One\"\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nPERFORMER \"Guest\"\nINDEX 01 01:00:00\n",
+        )
+        .unwrap();
+        let template: Template =
+            "%tracknumber:2% - %artist|albumartist% - %title|\"Untitled\"% (100%%)"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            template.render(&cue, &cue.tracks[0]),
+            "01 - Band - One (100%)"
+        );
+        assert_eq!(
+            template.render(&cue, &cue.tracks[1]),
+            "02 - Guest - Untitled (100%)"
+        );
+        assert_eq!(
+            "%year%".parse::<Template>(),
+            Err(TemplateError::UnknownField("year".into()))
+        );
+        assert_eq!(
+            "%title".parse::<Template>(),
+            Err(TemplateError::Unclosed(0))
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use crate::{Cue, Frames, Track};
+
+/// Something that happens on a disc, from [`Cue::timeline`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TimelineEvent<'a> {
+    pub time: Frames,
+    /// The track the event belongs to. A `FILE` belongs to the first track in it.
+    pub track: u8,
+    pub file: Option<&'a Path>,
+    pub kind: TimelineEventKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimelineEventKind {
+    FileStart,
+    /// The start of a track's pregap, at its `INDEX 00`, or silence added by `PREGAP`
+    PregapStart,
+    /// The track's `INDEX 01`
+    TrackStart,
+    /// An index after `INDEX 01`, with its number
+    IndexPoint(usize),
+    /// Silence added by `POSTGAP` after the track's audio
+    PostgapStart,
+}
+
+impl Cue {
+    /// Walks the structure of the disc in order. Times include the silence `PREGAP` and
+    /// `POSTGAP` add, so for a single file image they're times on the disc. The lengths of files
+    /// aren't known, so in a cue with several they start again from zero at each
+    /// [`TimelineEventKind::FileStart`], and a `POSTGAP` at the end of a file is left out.
+    pub fn timeline(&self) -> impl Iterator<Item = TimelineEvent<'_>> {
+        let mut events = Vec::new();
+        // silence added since the start of the file
+        let mut silence = 0;
+
+        for (i, track) in self.tracks.iter().enumerate() {
+            let file = track.file.as_deref();
+            let mut event = |time: Frames, silence: u32, kind| {
+                let time = Frames::try_from(u64::from(time) + u64::from(silence));
+
+                events.push(TimelineEvent {
+                    time: time.unwrap_or(Frames::MAX),
+                    track: track.track_index,
+                    file,
+                    kind,
+                });
+            };
+
+            if i == 0 || self.tracks[i - 1].file != track.file {
+                silence = 0;
+                event(Frames::ZERO, 0, TimelineEventKind::FileStart);
+            }
+
+            let mut indices: Vec<_> = track
+                .indices
+                .iter()
+                .filter_map(|index| Some((index.index(), index.time()?)))
+                .collect();
+            indices.sort_by_key(|&(number, _)| number);
+
+            if let Some(pregap) = track.pregap {
+                let start = indices.first().map_or(Frames::ZERO, |&(_, time)| time);
+
+                event(start, silence, TimelineEventKind::PregapStart);
+                silence += pregap.frames();
+            }
+
+            for (number, time) in indices {
+                let kind = match number {
+                    0 if track.pregap.is_some() => continue,
+                    0 => TimelineEventKind::PregapStart,
+                    1 => TimelineEventKind::TrackStart,
+                    number => TimelineEventKind::IndexPoint(number),
+                };
+
+                event(time, silence, kind);
+            }
+
+            if let (Some(postgap), Some(end)) = (track.postgap, self.audio_end(i)) {
+                event(end, silence, TimelineEventKind::PostgapStart);
+                silence += postgap.frames();
+            }
+        }
+
+        events.into_iter()
+    }
+
+    /// Where the audio of the track at `position` ends in its file: the first index of the next
+    /// track in the same file, or the lead-out of a single file image
+    fn audio_end(&self, position: usize) -> Option<Frames> {
+        let track = &self.tracks[position];
+
+        match self.tracks.get(position + 1) {
+            Some(next) if next.file == track.file => first_index(next),
+            Some(_) => None,
+            None if self.tracks_by_file().nth(1).is_none() => self.leadout,
+            None => None,
+        }
+    }
+}
+
+fn first_index(track: &Track) -> Option<Frames> {
+    track.indices.iter().filter_map(|index| index.time()).min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walks_the_disc() {
+        use TimelineEventKind::*;
+
+        let cue = Cue::from_str(
+            "FILE \"disc.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nINDEX 02 00:30:00\nPOSTGAP 00:01:00\nTRACK 02 AUDIO\nINDEX 00 01:00:00\nINDEX 01 01:02:00\nTRACK 03 AUDIO\nPREGAP 00:02:00\nINDEX 01 02:00:00\n",
+        )
+        .unwrap();
+
+        let events: Vec<_> = cue
+            .timeline()
+            .map(|event| (event.track, event.kind, event.time.to_msf()))
+            .collect();
+
+        assert_eq!(
+            events,
+            [
+                (1, FileStart, (0, 0, 0)),
+                (1, TrackStart, (0, 0, 0)),
+                (1, IndexPoint(2), (0, 30, 0)),
+                (1, PostgapStart, (1, 0, 0)),
+                (2, PregapStart, (1, 1, 0)),
+                (2, TrackStart, (1, 3, 0)),
+                (3, PregapStart, (2, 1, 0)),
+                (3, TrackStart, (2, 3, 0)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Conversion of the enums and flags of a cue to and from the tokens they're written as
+
+use std::{fmt, str::FromStr};
+
+use crate::{FileFormat, InvalidValue, TrackFlags, TrackMode};
+
+pub(crate) const TRACK_MODES: [(TrackMode, &str); 8] = [
+    (TrackMode::Audio, "AUDIO"),
+    (TrackMode::Cdg, "CDG"),
+    (TrackMode::Mode1_2048, "MODE1/2048"),
+    (TrackMode::Mode1_2352, "MODE1/2352"),
+    (TrackMode::Mode2_2336, "MODE2/2336"),
+    (TrackMode::Mode2_2352, "MODE2/2352"),
+    (TrackMode::Cdi_2336, "CDI/2336"),
+    (TrackMode::Cdi_2352, "CDI/2352"),
+];
+
+/// In the order they're usually written
+pub(crate) const TRACK_FLAGS: [(TrackFlags, &str); 4] = [
+    (TrackFlags::DIGITAL_COPY_PERMITTED, "DCP"),
+    (TrackFlags::FOUR_CHANNEL, "4CH"),
+    (TrackFlags::PRE_EMPHASIS_ENABLED, "PRE"),
+    (TrackFlags::SERIAL_COPY_MANAGEMENT_SYSTEM, "SCMS"),
+];
+
+pub(crate) const FILE_FORMATS: [(FileFormat, &str); 5] = [
+    (FileFormat::Binary, "BINARY"),
+    (FileFormat::Motorola, "MOTOROLA"),
+    (FileFormat::Aiff, "AIFF"),
+    (FileFormat::Wave, "WAVE"),
+    (FileFormat::Mp3, "MP3"),
+];
+
+impl TrackMode {
+    /// The token of the mode in a `TRACK` command, such as `MODE1/2352`
+    pub fn as_str(self) -> &'static str {
+        let (_, name) = TRACK_MODES.iter().find(|(mode, _)| *mode == self).unwrap();
+
+        name
+    }
+}
+
+impl fmt::Display for TrackMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// Parses the token of a mode as written in a cue, in upper case
+impl FromStr for TrackMode {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TRACK_MODES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|&(mode, _)| mode)
+            .ok_or_else(|| InvalidValue::TrackMode(s.into()))
+    }
+}
+
+impl TrackFlags {
+    /// The tokens of the flags that are set, as in a `FLAGS` command
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        TRACK_FLAGS
+            .into_iter()
+            .filter(move |(flag, _)| self.contains(*flag))
+            .map(|(_, name)| name)
+    }
+}
+
+/// The flags that are set separated by spaces, as in `DCP PRE`, and nothing when none are
+impl fmt::Display for TrackFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, name) in self.names().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+
+            f.write_str(name)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a list of flags separated by whitespace, in any order
+impl FromStr for TrackFlags {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_whitespace()
+            .try_fold(Self::empty(), |flags, token| {
+                let (flag, _) = TRACK_FLAGS
+                    .iter()
+                    .find(|(_, name)| *name == token)
+                    .ok_or_else(|| InvalidValue::TrackFlag(token.into()))?;
+
+                Ok(flags | *flag)
+            })
+    }
+}
+
+impl FileFormat {
+    /// The token of the format in a `FILE` command, such as `WAVE`. `None` for
+    /// [`FileFormat::Unspecified`], which is written without one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Other(name) => Some(name),
+            format => FILE_FORMATS
+                .iter()
+                .find(|(known, _)| known == format)
+                .map(|(_, name)| *name),
+        }
+    }
+}
+
+/// The token of the format, or nothing when it's unspecified
+impl fmt::Display for FileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str().unwrap_or_default())
+    }
+}
+
+/// Parses the token of a format as written in a cue, or an empty string as
+/// [`FileFormat::Unspecified`]. Other tokens in upper case, such as `FLAC`, are
+/// [`FileFormat::Other`].
+impl FromStr for FileFormat {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::Unspecified);
+        }
+
+        if let Some((format, _)) = FILE_FORMATS.iter().find(|(_, name)| *name == s) {
+            return Ok(format.clone());
+        }
+
+        let is_token = s.starts_with(|c: char| c.is_ascii_uppercase())
+            && s.chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+
+        if is_token {
+            Ok(Self::Other(s.into()))
+        } else {
+            Err(InvalidValue::FileFormat(s.into()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_modes_round_trip() {
+        for (mode, name) in TRACK_MODES {
+            assert_eq!(mode.to_string(), name);
+            assert_eq!(name.parse(), Ok(mode));
+        }
+
+        assert_eq!(format!("{:<8}|", TrackMode::Cdg), "CDG     |");
+        assert_eq!(
+            "mode1/2352".parse::<TrackMode>(),
+            Err(InvalidValue::TrackMode("mode1/2352".into()))
+        );
+    }
+
+    #[test]
+    fn track_flags_round_trip() {
+        let flags = TrackFlags::all();
+
+        assert_eq!(flags.to_string(), "DCP 4CH PRE SCMS");
+        assert_eq!("SCMS  PRE 4CH DCP".parse(), Ok(flags));
+        assert_eq!("".parse(), Ok(TrackFlags::empty()));
+        assert_eq!(TrackFlags::empty().to_string(), "");
+        assert_eq!(
+            "DCP XYZ".parse::<TrackFlags>(),
+            Err(InvalidValue::TrackFlag("XYZ".into()))
+        );
+    }
+
+    #[test]
+    fn file_formats_round_trip() {
+        for format in [FileFormat::Unspecified, FileFormat::Other("FLAC".into())]
+            .into_iter()
+            .chain(FILE_FORMATS.map(|(f, _)| f))
+        {
+            assert_eq!(format.to_string().parse(), Ok(format));
+        }
+
+        assert_eq!(FileFormat::Wave.to_string(), "WAVE");
+        assert_eq!(
+            "FLAC".parse::<FileFormat>(),
+            Ok(FileFormat::Other("FLAC".into()))
+        );
+        assert_eq!(
+            "flac".parse::<FileFormat>(),
+            Err(InvalidValue::FileFormat("flac".into()))
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! TOML for editing a cue's metadata by hand, in the shape of its serde serialization but with
+//! times as `MM:SS:FF` strings
+
+use std::convert::Infallible;
+
+use serde::{de::Error as _, Deserialize};
+use toml::{Table, Value};
+
+use crate::{Cue, Frames};
+
+/// The fields holding times, of the cue, of each track, and of each index
+const CUE_TIMES: &[&str] = &["leadout"];
+const TRACK_TIMES: &[&str] = &["time", "pregap", "postgap"];
+const INDEX_TIMES: &[&str] = &["time"];
+
+impl Cue {
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        let mut table = Table::try_from(self)?;
+
+        let Ok(()) = map_times(&mut table, &mut |time| {
+            let frames = time
+                .as_integer()
+                .and_then(|frames| u32::try_from(frames).ok());
+
+            if let Some(frames) = frames.and_then(|frames| Frames::try_from(frames).ok()) {
+                let (m, s, f) = frames.to_msf();
+
+                *time = Value::String(format!("{m:02}:{s:02}:{f:02}"));
+            }
+
+            Ok::<_, Infallible>(())
+        });
+
+        toml::to_string(&table)
+    }
+
+    /// Reads a cue written by [`Cue::to_toml`], or by hand in the same shape. Times can also be
+    /// given as numbers of frames.
+    pub fn from_toml(text: &str) -> Result<Self, toml::de::Error> {
+        let mut table: Table = toml::from_str(text)?;
+
+        map_times(&mut table, &mut |time| match time {
+            Value::String(msf) => {
+                let frames = parse_msf(msf).ok_or_else(|| {
+                    toml::de::Error::custom(format!("{msf:?} isn't a time as MM:SS:FF"))
+                })?;
+
+                *time = Value::Integer(frames.frames().into());
+                Ok(())
+            }
+            _ => Ok(()),
+        })?;
+
+        Cue::deserialize(table)
+    }
+}
+
+/// Calls `map` on every time in a serialized cue
+fn map_times<E>(
+    cue: &mut Table,
+    map: &mut impl FnMut(&mut Value) -> Result<(), E>,
+) -> Result<(), E> {
+    map_fields(cue, CUE_TIMES, map)?;
+
+    for track in tables(cue.get_mut("tracks")) {
+        map_fields(track, TRACK_TIMES, map)?;
+
+        for index in tables(track.get_mut("indices")) {
+            map_fields(index, INDEX_TIMES, map)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn map_fields<E>(
+    table: &mut Table,
+    fields: &[&str],
+    map: &mut impl FnMut(&mut Value) -> Result<(), E>,
+) -> Result<(), E> {
+    for field in fields {
+        if let Some(value) = table.get_mut(*field) {
+            map(value)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The tables in an array of tables, skipping anything else for deserializing to reject
+fn tables(array: Option<&mut Value>) -> impl Iterator<Item = &mut Table> {
+    array
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_table_mut)
+}
+
+fn parse_msf(msf: &str) -> Option<Frames> {
+    let mut fields = msf.split(':').map(str::parse);
+
+    match (fields.next(), fields.next(), fields.next(), fields.next()) {
+        (Some(m), Some(s), Some(f), None) => Frames::from_msf(m.ok()?, s.ok()?, f.ok()?).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let cue = Cue::from_str(
+            "PERFORMER \"Artist\"\nTITLE \"Album\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"One\"\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nPREGAP 00:01:00\nINDEX 00 03:00:00\nINDEX 01 03:02:00\nREM LEAD-OUT 06:00:00\n",
+        )
+        .unwrap();
+
+        let toml = cue.to_toml().unwrap();
+
+        assert!(toml.contains("leadout = \"06:00:00\""));
+        assert!(toml.contains("pregap = \"00:01:00\""));
+        assert!(toml.contains("time = \"03:02:00\""));
+        assert_eq!(Cue::from_toml(&toml).unwrap(), cue);
+
+        let edited = toml.replace("\"06:00:00\"", "27000");
+        assert_eq!(Cue::from_toml(&edited).unwrap(), cue);
+        assert!(Cue::from_toml(&toml.replace("\"06:00:00\"", "\"6:00\"")).is_err());
+    }
+}
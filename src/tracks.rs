@@ -0,0 +1,98 @@
+use crate::{Cue, Track};
+
+/// A cue can't hold more than 99 tracks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("a cue can't hold more than 99 tracks")]
+pub struct TooManyTracks;
+
+impl Cue {
+    /// Numbers the tracks consecutively in the order they're in, from the first track's number
+    pub fn renumber_tracks(&mut self) {
+        let first = self.tracks.first().map_or(1, |track| track.track_index);
+
+        self.renumber_from(first);
+    }
+
+    /// Inserts a track at position `at`, counting from 0, and renumbers the tracks. A track
+    /// without a `FILE` is placed in the one of the track before it, or after it when inserted
+    /// first, and its indices are sorted.
+    ///
+    /// # Panics
+    ///
+    /// If `at` is past the end of the tracks
+    pub fn insert_track(&mut self, at: usize, mut track: Track) -> Result<(), TooManyTracks> {
+        assert!(
+            at <= self.tracks.len(),
+            "inserting track at {at}, past the end"
+        );
+
+        if self.tracks.len() >= 99 {
+            return Err(TooManyTracks);
+        }
+
+        if track.file.is_none() {
+            let neighbour = at
+                .checked_sub(1)
+                .map_or(self.tracks.first(), |i| self.tracks.get(i));
+
+            if let Some(file) = neighbour.and_then(|neighbour| neighbour.file.as_ref()) {
+                track.set_file(file, neighbour.unwrap().format.clone());
+            }
+        }
+
+        track.indices.sort_by_key(|index| index.index());
+
+        let first = self.tracks.first().map_or(1, |track| track.track_index);
+
+        self.tracks.insert(at, track);
+        self.renumber_from(first);
+
+        Ok(())
+    }
+
+    /// Removes the track with the given number and renumbers those after it. The track before
+    /// it in the same `FILE` then runs on to the start of the next.
+    pub fn remove_track(&mut self, track_index: u8) -> Option<Track> {
+        let first = self.tracks.first()?.track_index;
+        let track = self.tracks.remove(self.track_position(track_index)?);
+
+        self.renumber_from(first);
+
+        Some(track)
+    }
+
+    fn renumber_from(&mut self, first: u8) {
+        for (track, number) in self.tracks.iter_mut().zip(first..) {
+            track.track_index = number;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TrackMode;
+
+    use super::*;
+
+    #[test]
+    fn keeps_tracks_numbered() {
+        let mut cue = Cue::from_str(
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 01:00:00\nTRACK 05 AUDIO\nINDEX 01 02:00:00\n",
+        )
+        .unwrap();
+        let numbers = |cue: &Cue| -> Vec<u8> { cue.tracks.iter().map(|t| t.track_index).collect() };
+
+        cue.renumber_tracks();
+        assert_eq!(numbers(&cue), [1, 2, 3]);
+
+        cue.insert_track(0, Track::new(9, TrackMode::Audio))
+            .unwrap();
+        assert_eq!(numbers(&cue), [1, 2, 3, 4]);
+        assert_eq!(cue.tracks[0].file, Some("a.wav".into()));
+
+        assert_eq!(cue.remove_track(1).unwrap().file, Some("a.wav".into()));
+        assert_eq!(numbers(&cue), [1, 2, 3]);
+        assert_eq!(cue.tracks[2].start().unwrap().frames(), 2 * 60 * 75);
+        assert!(cue.remove_track(4).is_none());
+    }
+}
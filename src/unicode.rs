@@ -0,0 +1,145 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::{is_nfc, is_nfd, UnicodeNormalization};
+
+use crate::Cue;
+
+/// A Unicode normalization form. Text typed on macOS is often decomposed, with accents as
+/// separate characters, and elsewhere composed, so the same title can be written two ways.
+#[cfg(feature = "unicode-normalization")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NormalizationForm {
+    /// Composed, with accented letters as single characters where possible. Used by most
+    /// systems other than macOS.
+    Nfc,
+    /// Decomposed, with accents as characters of their own after the letter
+    Nfd,
+}
+
+/// How the text of titles, performers, songwriters and arrangers is normalized, by
+/// [`Cue::normalize_text`] or while parsing with
+/// [`ParseOptions::text_normalization`](crate::ParseOptions::text_normalization)
+#[derive(Debug, Clone, Default)]
+pub struct TextNormalization {
+    /// The normalization form to convert to, or `None` to leave characters as they are
+    #[cfg(feature = "unicode-normalization")]
+    pub form: Option<NormalizationForm>,
+    /// Trim the text and replace each run of whitespace in it with a single space
+    pub collapse_whitespace: bool,
+}
+
+impl TextNormalization {
+    /// The text normalized, borrowed if that changes nothing
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+
+        if self.collapse_whitespace {
+            let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+            if collapsed != text {
+                text = Cow::Owned(collapsed);
+            }
+        }
+
+        #[cfg(feature = "unicode-normalization")]
+        match self.form {
+            Some(NormalizationForm::Nfc) if !is_nfc(&text) => {
+                text = Cow::Owned(text.nfc().collect())
+            }
+            Some(NormalizationForm::Nfd) if !is_nfd(&text) => {
+                text = Cow::Owned(text.nfd().collect())
+            }
+            _ => {}
+        }
+
+        text
+    }
+
+    fn apply_string(&self, text: &mut Option<String>) {
+        if let Some(Cow::Owned(normalized)) = text.as_deref().map(|text| self.apply(text)) {
+            *text = Some(normalized);
+        }
+    }
+
+    fn apply_arc(&self, text: &mut Option<Arc<str>>) {
+        if let Some(Cow::Owned(normalized)) = text.as_deref().map(|text| self.apply(text)) {
+            *text = Some(normalized.into());
+        }
+    }
+}
+
+impl Cue {
+    /// Normalizes the titles, performers, songwriters and arrangers of the cue and its tracks, so
+    /// that text written the same way compares equal when deduplicating or sorting
+    pub fn normalize_text(&mut self, normalization: &TextNormalization) {
+        normalization.apply_string(&mut self.title);
+        normalization.apply_arc(&mut self.performer);
+        normalization.apply_arc(&mut self.songwriter);
+        normalization.apply_arc(&mut self.arranger);
+
+        for track in &mut self.tracks {
+            normalization.apply_string(&mut track.title);
+            normalization.apply_arc(&mut track.performer);
+            normalization.apply_arc(&mut track.songwriter);
+            normalization.apply_arc(&mut track.arranger);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ParseOptions;
+
+    use super::*;
+
+    #[test]
+    fn collapses_whitespace() {
+        let input =
+            "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nTITLE \"  Se   de  \"\nINDEX 01 00:00:00\n";
+        // only the field left without the `unicode-normalization` feature
+        #[cfg_attr(not(feature = "unicode-normalization"), allow(clippy::needless_update))]
+        let normalization = TextNormalization {
+            collapse_whitespace: true,
+            ..Default::default()
+        };
+        let options = ParseOptions {
+            text_normalization: normalization.clone(),
+            ..Default::default()
+        };
+        let mut cue = Cue::from_str(input).unwrap();
+
+        cue.normalize_text(&normalization);
+
+        assert_eq!(cue.tracks[0].title.as_deref(), Some("Se de"));
+        assert_eq!(Cue::from_str_with(input, &options).unwrap(), cue);
+        assert!(matches!(normalization.apply("plain"), Cow::Borrowed(_)));
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn normalizes_forms() {
+        let apply = |form, text| {
+            TextNormalization {
+                form: Some(form),
+                ..Default::default()
+            }
+            .apply(text)
+            .into_owned()
+        };
+
+        assert_eq!(
+            apply(NormalizationForm::Nfd, "Sigur Rós"),
+            "Sigur Ro\u{301}s"
+        );
+        assert_eq!(
+            apply(NormalizationForm::Nfc, "Sigur Ro\u{301}s"),
+            "Sigur Rós"
+        );
+        assert_eq!(
+            apply(NormalizationForm::Nfc, "\u{1100}\u{1161}\u{11A8}"),
+            "각"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use crate::Location;
+
+/// A position as counted by the Language Server Protocol, for use in editors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utf16Position {
+    /// Line, counting from 0
+    pub line: u32,
+    /// Offset into the line in UTF-16 code units, counting from 0
+    pub character: u32,
+}
+
+/// A range as counted by the Language Server Protocol, which converts directly into an LSP
+/// `Range`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Utf16Range {
+    pub start: Utf16Position,
+    /// Exclusive, the same as `start` for an error at a single position
+    pub end: Utf16Position,
+}
+
+impl Location {
+    /// Converts the byte offsets into positions an LSP client understands. `source` must be the
+    /// text the location was found in.
+    pub fn to_utf16_range(&self, source: &str) -> Utf16Range {
+        Utf16Range {
+            start: utf16_position(source, self.start),
+            end: utf16_position(source, self.end),
+        }
+    }
+}
+
+/// Counts lines ending in `\n`, `\r\n` or `\r`, like LSP does
+fn utf16_position(source: &str, offset: usize) -> Utf16Position {
+    let mut offset = offset.min(source.len());
+
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let bytes = source.as_bytes();
+    let mut line = 0;
+    let mut line_start = 0;
+
+    for (i, &byte) in bytes[..offset].iter().enumerate() {
+        if byte == b'\n' || (byte == b'\r' && bytes.get(i + 1) != Some(&b'\n')) {
+            line += 1;
+            line_start = i + 1;
+        }
+    }
+
+    let character = source[line_start..offset].encode_utf16().count();
+
+    Utf16Position {
+        line: line as u32,
+        character: character as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cue, Error};
+
+    #[test]
+    fn counts_utf16_units() {
+        let input = "FILE \"a\" WAVE\r\nTITLE \"é🎵\" INDEX";
+        let Err(Error::Parse(e)) = Cue::from_str(input) else {
+            panic!("expected a parse error");
+        };
+
+        let range = e.location().to_utf16_range(input);
+
+        // é is a single unit and 🎵 a surrogate pair
+        assert_eq!(
+            range.start,
+            Utf16Position {
+                line: 1,
+                character: 12
+            }
+        );
+        assert_eq!(utf16_position("a\rb\r\nc", 5).line, 2);
+    }
+}
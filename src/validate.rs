@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use crate::{Cue, Isrc, Track};
+
+/// The longest text CD-TEXT holds in a field
+pub const MAX_TEXT_LENGTH: usize = 80;
+
+/// Why a validating setter such as [`Cue::set_catalog`] refused a value
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum InvalidValue {
+    #[error("a catalog number is 12 or 13 digits")]
+    CatalogLength,
+    #[error("the catalog number's check digit should be {0}")]
+    CatalogCheckDigit(u8),
+    #[error("an ISRC is 2 letters, 3 letters or digits, then 7 digits")]
+    Isrc,
+    #[error("CD-TEXT holds at most 80 characters, not {0}")]
+    TooLong(usize),
+    #[error("text can't contain double quotes or line breaks")]
+    Unwritable,
+    #[error("{0:?} isn't a track mode")]
+    TrackMode(String),
+    #[error("{0:?} isn't a track flag")]
+    TrackFlag(String),
+    #[error("{0:?} isn't a file format")]
+    FileFormat(String),
+}
+
+impl Cue {
+    /// Sets the `CATALOG`, a UPC or EAN with a valid check digit
+    pub fn set_catalog(&mut self, catalog: &str) -> Result<(), InvalidValue> {
+        self.catalog = Some(catalog.parse()?);
+
+        Ok(())
+    }
+
+    pub fn set_title(&mut self, title: &str) -> Result<(), InvalidValue> {
+        self.title = Some(text(title)?.into());
+
+        Ok(())
+    }
+
+    pub fn set_performer(&mut self, performer: &str) -> Result<(), InvalidValue> {
+        self.performer = Some(text(performer)?.into());
+
+        Ok(())
+    }
+
+    pub fn set_songwriter(&mut self, songwriter: &str) -> Result<(), InvalidValue> {
+        self.songwriter = Some(text(songwriter)?.into());
+
+        Ok(())
+    }
+
+    pub fn set_arranger(&mut self, arranger: &str) -> Result<(), InvalidValue> {
+        self.arranger = Some(text(arranger)?.into());
+
+        Ok(())
+    }
+}
+
+impl Track {
+    /// Sets the `ISRC`, such as `USABC9900001`, see [`Isrc`] for the forms accepted
+    pub fn set_isrc(&mut self, isrc: &str) -> Result<(), InvalidValue> {
+        self.isrc = Some(isrc.parse::<Isrc>()?.to_string());
+
+        Ok(())
+    }
+
+    pub fn set_title(&mut self, title: &str) -> Result<(), InvalidValue> {
+        self.title = Some(text(title)?.into());
+
+        Ok(())
+    }
+
+    pub fn set_performer(&mut self, performer: &str) -> Result<(), InvalidValue> {
+        self.performer = Some(Arc::from(text(performer)?));
+
+        Ok(())
+    }
+
+    pub fn set_songwriter(&mut self, songwriter: &str) -> Result<(), InvalidValue> {
+        self.songwriter = Some(Arc::from(text(songwriter)?));
+
+        Ok(())
+    }
+
+    pub fn set_arranger(&mut self, arranger: &str) -> Result<(), InvalidValue> {
+        self.arranger = Some(Arc::from(text(arranger)?));
+
+        Ok(())
+    }
+}
+
+fn text(value: &str) -> Result<&str, InvalidValue> {
+    let length = value.chars().count();
+
+    if length > MAX_TEXT_LENGTH {
+        Err(InvalidValue::TooLong(length))
+    } else if value.contains(['"', '\r', '\n']) {
+        Err(InvalidValue::Unwritable)
+    } else {
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TrackMode;
+
+    use super::*;
+
+    #[test]
+    fn validates_values() {
+        let mut cue = Cue::default();
+        let mut track = Track::new(1, TrackMode::Audio);
+
+        assert_eq!(cue.set_catalog("4006381333931"), Ok(()));
+        assert_eq!(cue.set_catalog("036000291452"), Ok(()));
+        assert_eq!(
+            cue.set_catalog("4006381333932"),
+            Err(InvalidValue::CatalogCheckDigit(1))
+        );
+        assert_eq!(cue.catalog.as_deref(), Some("036000291452"));
+        assert_eq!(track.set_isrc("USABC9900001"), Ok(()));
+        assert_eq!(track.set_isrc("US-ABC-9900001"), Ok(()));
+        assert_eq!(track.isrc.as_deref(), Some("USABC9900001"));
+        assert_eq!(track.set_isrc("USABC990001"), Err(InvalidValue::Isrc));
+        assert_eq!(
+            track.set_title(&"a".repeat(81)),
+            Err(InvalidValue::TooLong(81))
+        );
+        assert_eq!(track.set_title("\"Quoted\""), Err(InvalidValue::Unwritable));
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+use crate::{Cue, Location};
+
+/// A cue along with any problems that were recovered from while parsing it
+#[derive(Debug, Clone, Default)]
+pub struct ParseOutcome {
+    pub cue: Cue,
+    pub warnings: Vec<Warning>,
+}
+
+/// Something wrong with a cue that parsing worked around
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+    pub location: Location,
+}
+
+impl Warning {
+    /// The stable code of the warning's kind, see [`WarningKind::code`]
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Location { line, column, .. } = self.location;
+
+        write!(f, "{line}:{column}: {}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// The text starts with a byte order mark, which was skipped
+    ByteOrderMark,
+    /// Text containing spaces isn't quoted, so it was taken to run to the end of the line, or up
+    /// to the format of a `FILE`
+    UnquotedString,
+    /// A line doesn't start with a known command, so it was skipped
+    UnknownCommand,
+    /// Text is between typographic quotes such as `“` and `”`, which were taken as straight ones
+    TypographicQuotes,
+    /// A text command such as `PERFORMER` has no value, so it was skipped
+    MissingValue,
+    /// A time has 60 or more seconds or 75 or more frames, which were carried into the next unit
+    TimeOutOfRange,
+}
+
+impl WarningKind {
+    /// A code identifying the kind of warning, such as `CUE101`, which stays the same across
+    /// versions so that tools can suppress or look it up. Errors use codes below `CUE100`, see
+    /// [`Error::code`](crate::Error::code).
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::ByteOrderMark => "CUE101",
+            Self::UnquotedString => "CUE102",
+            Self::UnknownCommand => "CUE103",
+            Self::TypographicQuotes => "CUE104",
+            Self::MissingValue => "CUE105",
+            Self::TimeOutOfRange => "CUE106",
+        }
+    }
+}
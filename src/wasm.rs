@@ -0,0 +1,18 @@
+//! Bindings for JavaScript, for parsing cues in the browser
+//!
+//! Build with `wasm-pack build --features wasm`.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::Cue;
+
+/// Parses a cue into a plain object in the same shape as the `serde` JSON, throwing an `Error`
+/// with the parse error's message on failure
+#[wasm_bindgen(js_name = parseCue)]
+pub fn parse_cue(text: &str) -> Result<JsValue, JsError> {
+    let cue = Cue::from_str(text).map_err(|e| JsError::new(&e.to_string()))?;
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+
+    Ok(cue.serialize(&serializer)?)
+}
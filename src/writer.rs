@@ -0,0 +1,428 @@
+//! Writing a [`Cue`] back out as a cue sheet
+
+use std::{fmt, path::Path};
+
+use crate::{
+    comments, layout,
+    order::{global_written, track_written},
+    Cue, CueCommand, FileFormat, Frames, ReplayGain, Track,
+};
+
+/// Writes the cue as a cue sheet, in a canonical layout: every string quoted, track commands
+/// indented under their `TRACK`, and each `FILE` written once, before the first track in it, or
+/// after its `INDEX 00` when the track's gap is at the end of the file before.
+/// Comments with a [position](crate::CommentPosition) are written before the same command, and
+/// the rest first in the global section and last in a track. Parsing the output gives back the
+/// same cue, except that straight quotes in text, which can't be written inside quotes, are
+/// written as typographic ones.
+impl fmt::Display for Cue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_rem(f, "GENRE", self.genre.as_deref())?;
+        write_rem(f, "DATE", self.date.as_deref())?;
+        write_rem(
+            f,
+            "DISCNUMBER",
+            self.disc_number.map(|n| n.to_string()).as_deref(),
+        )?;
+        write_rem(
+            f,
+            "TOTALDISCS",
+            self.total_discs.map(|n| n.to_string()).as_deref(),
+        )?;
+        write_replay_gain(f, "", "ALBUM", self.replay_gain)?;
+
+        let positions = &self.comment_positions;
+        let comments = |f: &mut fmt::Formatter<'_>, command| {
+            write_comments(f, "", comments::before(&self.comments, positions, command))
+        };
+
+        write_comments(
+            f,
+            "",
+            comments::unplaced(&self.comments, positions, |command| {
+                global_written(self, command)
+            }),
+        )?;
+
+        if let Some(leadout) = self.leadout {
+            writeln!(f, "REM LEAD-OUT {}", msf(leadout))?;
+        }
+
+        if let Some(catalog) = &self.catalog {
+            comments(f, CueCommand::Catalog)?;
+            writeln!(f, "CATALOG {catalog}")?;
+        }
+
+        if let Some(path) = &self.cd_text_file {
+            comments(f, CueCommand::CdTextFile)?;
+            writeln!(f, "CDTEXTFILE {}", quoted_path(path))?;
+        }
+
+        for (command, name, value) in [
+            (
+                CueCommand::Performer,
+                "PERFORMER",
+                self.performer.as_deref(),
+            ),
+            (
+                CueCommand::Songwriter,
+                "SONGWRITER",
+                self.songwriter.as_deref(),
+            ),
+            (CueCommand::Arranger, "ARRANGER", self.arranger.as_deref()),
+            (CueCommand::Title, "TITLE", self.title.as_deref()),
+        ] {
+            if value.is_some() {
+                comments(f, command)?;
+            }
+
+            write_text(f, "", name, value)?;
+        }
+
+        let mut file = None;
+
+        if let Some(path) = &self.path {
+            comments(f, CueCommand::File)?;
+            write_file(f, path, &self.format)?;
+            file = Some((path.as_path(), &self.format));
+        }
+
+        for (i, track) in self.tracks.iter().enumerate() {
+            let mut next_file = None;
+
+            if let Some(path) = &track.file {
+                if file != Some((path.as_path(), &track.format)) {
+                    next_file = Some((path.as_path(), &track.format));
+                    file = next_file;
+                }
+            }
+
+            // a gap at the end of the file before is kept there, with the FILE after INDEX 00
+            let gap_file = next_file.filter(|_| layout::gap_in_previous_file(self, i));
+
+            if let (Some((path, format)), None) = (next_file, gap_file) {
+                write_file(f, path, format)?;
+            }
+
+            if i == 0 {
+                comments(f, CueCommand::Track)?;
+            }
+
+            write_track(f, track, gap_file)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a track, with `gap_file` written after its `INDEX 00`
+fn write_track(
+    f: &mut fmt::Formatter<'_>,
+    track: &Track,
+    mut gap_file: Option<(&Path, &FileFormat)>,
+) -> fmt::Result {
+    writeln!(f, "  TRACK {:02} {}", track.track_index, track.mode)?;
+
+    let positions = &track.comment_positions;
+    let comments = |f: &mut fmt::Formatter<'_>, command| {
+        write_comments(
+            f,
+            "    ",
+            comments::before(&track.comments, positions, command),
+        )
+    };
+
+    for (command, name, value) in [
+        (CueCommand::Title, "TITLE", track.title.as_deref()),
+        (
+            CueCommand::Performer,
+            "PERFORMER",
+            track.performer.as_deref(),
+        ),
+        (
+            CueCommand::Songwriter,
+            "SONGWRITER",
+            track.songwriter.as_deref(),
+        ),
+        (CueCommand::Arranger, "ARRANGER", track.arranger.as_deref()),
+    ] {
+        if value.is_some() {
+            comments(f, command)?;
+        }
+
+        write_text(f, "    ", name, value)?;
+    }
+
+    if let Some(disc) = track.disc_number {
+        writeln!(f, "    REM DISCNUMBER {disc}")?;
+    }
+
+    write_replay_gain(f, "    ", "TRACK", track.replay_gain)?;
+
+    if let Some(isrc) = &track.isrc {
+        comments(f, CueCommand::Isrc)?;
+        writeln!(f, "    ISRC {isrc}")?;
+    }
+
+    if !track.flags.is_empty() {
+        comments(f, CueCommand::Flags)?;
+        writeln!(f, "    FLAGS {}", track.flags)?;
+    }
+
+    if let Some(pregap) = track.pregap {
+        comments(f, CueCommand::Pregap)?;
+        writeln!(f, "    PREGAP {}", msf(pregap))?;
+    }
+
+    for index in &track.indices {
+        if index.index() != 0 {
+            if let Some((path, format)) = gap_file.take() {
+                write_file(f, path, format)?;
+            }
+        }
+
+        comments(f, CueCommand::Index(index.index()))?;
+
+        match index.time() {
+            Some(time) => writeln!(f, "    INDEX {:02} {}", index.index(), msf(time))?,
+            None => writeln!(f, "    INDEX {:02}", index.index())?,
+        }
+    }
+
+    if let Some(postgap) = track.postgap {
+        comments(f, CueCommand::Postgap)?;
+        writeln!(f, "    POSTGAP {}", msf(postgap))?;
+    }
+
+    // last, where comments without a position are read back from
+    write_comments(
+        f,
+        "    ",
+        comments::unplaced(&track.comments, positions, |command| {
+            track_written(track, command)
+        }),
+    )
+}
+
+fn write_comments<'a>(
+    f: &mut fmt::Formatter<'_>,
+    indent: &str,
+    comments: impl Iterator<Item = &'a str>,
+) -> fmt::Result {
+    for comment in comments {
+        writeln!(f, "{indent}REM {comment}")?;
+    }
+
+    Ok(())
+}
+
+fn write_text(
+    f: &mut fmt::Formatter<'_>,
+    indent: &str,
+    command: &str,
+    value: Option<&str>,
+) -> fmt::Result {
+    match value {
+        Some(value) => writeln!(f, "{indent}{command} {}", quoted(value)),
+        None => Ok(()),
+    }
+}
+
+/// A typed `REM`, quoted only if it has spaces, as rippers write them
+fn write_rem(f: &mut fmt::Formatter<'_>, name: &str, value: Option<&str>) -> fmt::Result {
+    match value {
+        Some(value) if value.contains(char::is_whitespace) => {
+            writeln!(f, "REM {name} {}", quoted(value))
+        }
+        Some(value) => writeln!(f, "REM {name} {value}"),
+        None => Ok(()),
+    }
+}
+
+fn write_replay_gain(
+    f: &mut fmt::Formatter<'_>,
+    indent: &str,
+    scope: &str,
+    replay_gain: Option<ReplayGain>,
+) -> fmt::Result {
+    let Some(ReplayGain { gain, peak }) = replay_gain else {
+        return Ok(());
+    };
+
+    writeln!(f, "{indent}REM REPLAYGAIN_{scope}_GAIN {gain:+} dB")?;
+
+    match peak {
+        Some(peak) => writeln!(f, "{indent}REM REPLAYGAIN_{scope}_PEAK {peak}"),
+        None => Ok(()),
+    }
+}
+
+fn write_file(f: &mut fmt::Formatter<'_>, path: &Path, format: &FileFormat) -> fmt::Result {
+    match format.as_str() {
+        Some(format) => writeln!(f, "FILE {} {format}", quoted_path(path)),
+        None => writeln!(f, "FILE {}", quoted_path(path)),
+    }
+}
+
+/// Text in quotes, with the straight quotes in it turned into typographic ones, opening and
+/// closing in turn, as the quoted text would otherwise end at the first
+fn quoted(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    let mut open = true;
+
+    quoted.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => {
+                quoted.push(if open { '\u{201C}' } else { '\u{201D}' });
+                open = !open;
+            }
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+
+    quoted
+}
+
+fn quoted_path(path: &Path) -> String {
+    format!("\"{}\"", path.display())
+}
+
+fn msf(frames: Frames) -> String {
+    let (m, s, f) = frames.to_msf();
+
+    format!("{m:02}:{s:02}:{f:02}")
+}
+
+impl Cue {
+    /// Tidies the cue without changing what it describes: tracks and indices are sorted by
+    /// number, whitespace is trimmed from text, and blank comments are removed. Writing a
+    /// normalized cue gives the same text for cues that differ only in such details.
+    pub fn normalize(&mut self) {
+        trim(&mut self.title);
+        trim_arc(&mut self.performer);
+        trim_arc(&mut self.songwriter);
+        trim_arc(&mut self.arranger);
+        trim(&mut self.genre);
+        trim(&mut self.date);
+        comments::retain(&self.comments, &mut self.comment_positions, |comment| {
+            !comment.trim().is_empty()
+        });
+        self.comments.retain(|comment| !comment.trim().is_empty());
+
+        for comment in &mut self.comments {
+            *comment = comment.trim().into();
+        }
+
+        self.tracks.sort_by_key(|track| track.track_index);
+
+        for track in &mut self.tracks {
+            trim(&mut track.title);
+            trim_arc(&mut track.performer);
+            trim_arc(&mut track.songwriter);
+            trim_arc(&mut track.arranger);
+            comments::retain(&track.comments, &mut track.comment_positions, |comment| {
+                !comment.trim().is_empty()
+            });
+            track.comments.retain(|comment| !comment.trim().is_empty());
+
+            for comment in &mut track.comments {
+                *comment = comment.trim().into();
+            }
+
+            track.indices.sort_by_key(|index| index.index());
+        }
+    }
+}
+
+fn trim(value: &mut Option<String>) {
+    if let Some(text) = value {
+        if text.trim().len() != text.len() {
+            *text = text.trim().into();
+        }
+    }
+}
+
+fn trim_arc(value: &mut Option<std::sync::Arc<str>>) {
+    if let Some(text) = value {
+        if text.trim().len() != text.len() {
+            *text = text.trim().into();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_canonical_layout() {
+        let mut cue = Cue::from_str(
+            "TITLE Album\nPERFORMER \"The Band \"\nREM GENRE Rock\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nTITLE One\nTRACK 02 AUDIO\nFLAGS PRE DCP\nINDEX 01 03:00:00\nINDEX 00 02:58:00\nFILE b.wav WAVE\nTRACK 03 AUDIO\nINDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        cue.normalize();
+
+        let text = cue.to_string();
+
+        assert_eq!(
+            text,
+            "REM GENRE Rock\nPERFORMER \"The Band\"\nTITLE \"Album\"\nFILE \"a.wav\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"One\"\n    INDEX 01 00:00:00\n  TRACK 02 AUDIO\n    FLAGS DCP PRE\n    INDEX 00 02:58:00\n    INDEX 01 03:00:00\nFILE \"b.wav\" WAVE\n  TRACK 03 AUDIO\n    INDEX 01 00:00:00\n"
+        );
+        assert_eq!(Cue::from_str(&text).unwrap(), cue);
+    }
+
+    #[test]
+    fn keeps_gaps_appended_to_the_file_before() {
+        let text = "FILE \"a.wav\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\n  TRACK 02 AUDIO\n    INDEX 00 03:00:00\nFILE \"b.wav\" WAVE\n    INDEX 01 00:00:00\n";
+        let cue = Cue::from_str(text).unwrap();
+
+        assert_eq!(cue.to_string(), text);
+    }
+
+    #[test]
+    fn writes_straight_quotes_as_typographic() {
+        let mut cue =
+            Cue::from_str("FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\n").unwrap();
+
+        cue.title = Some("The \"Best\" Of".into());
+        cue.genre = Some("Rock \"n\" Roll".into());
+        cue.tracks[0].performer = Some("\"".into());
+
+        let text = cue.to_string();
+
+        assert!(
+            text.contains("TITLE \"The \u{201C}Best\u{201D} Of\"\n"),
+            "{text}"
+        );
+        assert!(
+            text.contains("REM GENRE \"Rock \u{201C}n\u{201D} Roll\"\n"),
+            "{text}"
+        );
+
+        let parsed = Cue::from_str(&text).unwrap();
+
+        assert_eq!(parsed.title.as_deref(), Some("The \u{201C}Best\u{201D} Of"));
+        assert_eq!(parsed.genre.as_deref(), Some("Rock \u{201C}n\u{201D} Roll"));
+        assert_eq!(parsed.tracks[0].performer.as_deref(), Some("\u{201C}"));
+        assert_eq!(parsed.to_string(), text);
+    }
+
+    #[test]
+    fn writes_cues_without_global_commands() {
+        let mut track = Track::new(1, crate::TrackMode::Audio);
+        track
+            .indices
+            .push(crate::TrackIndex::new(1, Some(Frames::ZERO)));
+        let cue = Cue::new(vec![track]);
+
+        assert_eq!(cue.to_string(), "  TRACK 01 AUDIO\n    INDEX 01 00:00:00\n");
+        assert_eq!(Cue::from_str(cue.to_string()).unwrap(), cue);
+        assert_eq!(Cue::default().to_string(), "");
+        assert_eq!(Cue::from_str("").unwrap(), Cue::default());
+    }
+}
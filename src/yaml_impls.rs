@@ -0,0 +1,38 @@
+//! YAML in the shape of a cue's serde serialization, as for its JSON, for sidecar files of
+//! archived discs
+
+use serde::Deserialize;
+
+use crate::Cue;
+
+impl Cue {
+    /// Writes the cue as YAML with the same fields as its JSON
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    /// Reads a cue written by [`Cue::to_yaml`], or converted from its JSON
+    pub fn from_yaml(text: &str) -> Result<Self, serde_yaml::Error> {
+        Cue::deserialize(serde_yaml::Deserializer::from_str(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let cue = Cue::from_str(
+            "REM a comment\nTITLE \"Album: Live\"\nFILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nFLAGS DCP PRE\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nPREGAP 00:01:00\nINDEX 01 03:00:00\nREM LEAD-OUT 06:00:00\n",
+        )
+        .unwrap();
+
+        let yaml = cue.to_yaml().unwrap();
+
+        assert!(yaml.contains("title: 'Album: Live'\n"));
+        assert!(yaml.contains("\n  flags:\n  - DCP\n  - PRE\n"));
+        assert!(yaml.contains("\n  indices:\n  - index: 1\n    time: 13500\n"));
+        assert_eq!(Cue::from_yaml(&yaml).unwrap(), cue);
+    }
+}